@@ -0,0 +1,203 @@
+//! Журнал изменяющих запросов
+//!
+//! Клиент с [`AuditSink`] передает ему запись о каждом изменяющем запросе (POST, PUT,
+//! DELETE, которые меняют данные на сервере): endpoint, тело запроса в том виде, в котором
+//! оно ушло на сервер, ответ iiko (например, `documentValidationResult` или JSON
+//! `ProductOperationResult`) или ошибку, время и идентификатор пользователя, от имени
+//! которого работает вызывающий код (`IikoClient::with_actor`).
+//!
+//! Читающие POST (OLAP-отчеты, списки с фильтрами) в журнал не попадают.
+//!
+//! ```no_run
+//! # async fn example(invoice: iiko_server_api_sdk::IncomingInvoiceDto) -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::{IikoClient, IikoConfig, JsonLinesAuditSink};
+//!
+//! let client = IikoClient::new(IikoConfig::from_env()?)?
+//!     .with_audit_sink(JsonLinesAuditSink::new("/var/log/iiko/audit.jsonl"));
+//!
+//! // Клиент для запроса пользователя: общие сессия и очередь, свой actor
+//! let client = client.with_actor("accountant@example.com");
+//! client.documents().import_incoming_invoice(invoice).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Важно
+//! - Запись передается после ответа сервера. Ошибка журнала не превращает выполненный
+//!   запрос в ошибку (иначе вызывающий код мог бы повторить уже выполненный импорт);
+//!   с фичей `tracing` она пишется событием WARN
+//! - Запросы, отклоненные в режиме `WriteMode::ReadOnly` или не отправленные в режиме
+//!   `WriteMode::DryRun`, тоже записываются: с ошибкой или с `dry_run = true`
+//! - Ключ сессии в записи не попадает: он добавляется к запросу позже
+//! - Пароли (`<password>` сотрудника, поля `password` и `pass`) заменяются на `[REDACTED]`
+//!   и в теле запроса, и в ответе
+
+use crate::error::{IikoError, Result};
+use crate::transport::async_trait;
+use serde::{Serialize, Serializer};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Запись об одном изменяющем запросе
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct AuditEntry {
+    /// Время вызова, в JSON — RFC 3339 в UTC (`2024-03-01T09:15:02.417Z`)
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub timestamp: SystemTime,
+    /// Идентификатор из `IikoClient::with_actor`
+    pub actor: Option<String>,
+    pub method: String,
+    /// Путь относительно `IikoConfig::base_url`, например `documents/import/incomingInvoice`
+    pub endpoint: String,
+    /// Query-параметры без ключа сессии
+    pub params: Vec<(String, String)>,
+    /// Тело запроса (XML, JSON или закодированная форма) без паролей
+    pub payload: Option<String>,
+    /// Тело ответа iiko без паролей
+    pub response: Option<String>,
+    /// Текст ошибки, если запрос не выполнен
+    pub error: Option<String>,
+    /// Запрос не отправлялся (`WriteMode::DryRun`), `response` — синтетический
+    pub dry_run: bool,
+}
+
+/// Получатель записей об изменяющих запросах клиента
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn record(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+/// Записи в файле, по одному JSON-объекту на строку
+///
+/// Файл открывается на дозапись при каждой записи, поэтому его можно ротировать
+/// (переименовать) без перезапуска процесса. Создается с правами `0600`: в телах
+/// запросов бывают персональные данные сотрудников.
+#[derive(Debug)]
+pub struct JsonLinesAuditSink {
+    path: PathBuf,
+    /// Не дает строкам из разных задач перемешаться
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl JsonLinesAuditSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonLinesAuditSink {
+    async fn record(&self, entry: &AuditEntry) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let _lock = self.lock.lock().await;
+        let mut options = tokio::fs::OpenOptions::new();
+        options.append(true).create(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut file = options.open(&self.path).await.map_err(|e| {
+            IikoError::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to open {}: {}", self.path.display(), e),
+            ))
+        })?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+fn serialize_timestamp<S: Serializer>(
+    timestamp: &SystemTime,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&rfc3339(*timestamp))
+}
+
+/// `2024-03-01T09:15:02.417Z`. Время до 1970 года записывается как начало эпохи
+fn rfc3339(timestamp: SystemTime) -> String {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Гражданская дата из номера дня (алгоритм Говарда Хиннанта)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn writes_one_json_line_per_entry() {
+        let path = std::env::temp_dir().join(format!(
+            "iiko-audit-{}-{}.jsonl",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        let sink = JsonLinesAuditSink::new(&path);
+        let entry = AuditEntry {
+            timestamp: UNIX_EPOCH + Duration::from_millis(1_709_284_502_417),
+            actor: Some("accountant".to_string()),
+            method: "POST".to_string(),
+            endpoint: "documents/import/incomingInvoice".to_string(),
+            params: Vec::new(),
+            payload: Some("<document/>".to_string()),
+            response: Some("<documentValidationResult/>".to_string()),
+            error: None,
+            dry_run: false,
+        };
+        sink.record(&entry).await.unwrap();
+        sink.record(&entry).await.unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["timestamp"], "2024-03-01T09:15:02.417Z");
+        assert_eq!(lines[0]["actor"], "accountant");
+        assert_eq!(lines[0]["payload"], "<document/>");
+
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "2000-02-29T00:00:00.000Z"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
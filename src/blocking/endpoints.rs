@@ -0,0 +1,577 @@
+//! Блокирующие версии endpoints
+//!
+//! Сигнатуры совпадают с асинхронными методами из `crate::endpoints`, кроме потоковых:
+//! `ReportsEndpoint::stream_olap_report` возвращает итератор [`OlapRows`],
+//! `ImagesEndpoint::load_into` пишет в `std::io::Write`.
+
+use super::IikoClient;
+use crate::date::DateParam;
+use crate::endpoints::{
+    AssemblyChartsQuery, CounteragentBalanceQuery, DeliveryConsolidatedQuery,
+    DeliveryCouriersQuery, DeliveryLoyaltyQuery, DeliveryOrderCycleQuery, EmployeeSearch,
+    IngredientEntryQuery, InternalTransfersQuery, OlapReportV1Query, OutgoingInvoiceNumberQuery,
+    ProductExpenseQuery, ProductQuery, SalesQuery, StoreBalanceQuery, StoreOperationsQuery,
+    SupplierSearch,
+};
+use crate::error::Result;
+use crate::ids::{
+    DepartmentCode, DepartmentId, EmployeeCode, EmployeeId, ProductCategoryId, ProductId,
+    StoreCode, StoreId, SupplierCode, SupplierId,
+};
+use crate::streaming::{OlapRow, OlapRowStream};
+use crate::xml::response::*;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::io::Write;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+/// Объявить блокирующий endpoint и метод доступа к нему у `blocking::IikoClient`
+///
+/// Каждый метод выполняет одноименный асинхронный метод на runtime клиента.
+macro_rules! blocking_endpoints {
+    ($(
+        $accessor:ident => $name:ident {
+            $( $(#[$attr:meta])* fn $method:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?; )*
+        }
+    )*) => {
+        $(
+            #[doc = concat!("Блокирующая версия [`crate::endpoints::", stringify!($name), "`]")]
+            pub struct $name<'a> {
+                client: &'a IikoClient,
+            }
+
+            impl IikoClient {
+                pub fn $accessor(&self) -> $name<'_> {
+                    $name { client: self }
+                }
+            }
+
+            impl $name<'_> {
+                $(
+                    #[doc = concat!(
+                        "См. [`crate::endpoints::", stringify!($name), "::", stringify!($method), "`]"
+                    )]
+                    $(#[$attr])*
+                    // Сигнатура повторяет асинхронный метод
+                    #[allow(clippy::too_many_arguments, deprecated)]
+                    pub fn $method(&self, $($arg: $ty),*) $(-> $ret)? {
+                        self.client
+                            .block_on(self.client.as_async().$accessor().$method($($arg),*))
+                    }
+                )*
+            }
+        )*
+    };
+}
+
+blocking_endpoints! {
+    auth => AuthEndpoint {
+        fn login() -> Result<String>;
+        fn logout() -> Result<String>;
+        fn invalidate_session();
+    }
+
+    inventory => InventoryEndpoint {
+        fn get_inventory(store_id: Option<StoreId>) -> Result<Vec<InventoryItem>>;
+    }
+
+    suppliers => SuppliersEndpoint {
+        fn list(revision_from: Option<i32>) -> Result<Vec<Supplier>>;
+        fn find(search: SupplierSearch) -> Result<Vec<Supplier>>;
+        #[deprecated(note = "используйте `find`")]
+        fn search(
+            name: Option<&str>,
+            code: Option<&str>,
+            phone: Option<&str>,
+            cell_phone: Option<&str>,
+            first_name: Option<&str>,
+            middle_name: Option<&str>,
+            last_name: Option<&str>,
+            email: Option<&str>,
+            card_number: Option<&str>,
+            taxpayer_id_number: Option<&str>,
+        ) -> Result<Vec<Supplier>>;
+        fn get_pricelist(code: impl Into<SupplierCode>, date: Option<DateParam>) -> Result<Vec<SupplierPriceListItemDto>>;
+    }
+
+    employees => EmployeesEndpoint {
+        fn list(include_deleted: Option<bool>, revision_from: Option<i64>) -> Result<Vec<Employee>>;
+        fn list_by_department(
+            department_code: impl Into<DepartmentCode>,
+            include_deleted: Option<bool>,
+        ) -> Result<Vec<Employee>>;
+        fn get_by_id(employee_uuid: impl Into<EmployeeId>) -> Result<Employee>;
+        fn get_by_code(employee_code: impl Into<EmployeeCode>) -> Result<Employee>;
+        fn find(search: EmployeeSearch) -> Result<Vec<Employee>>;
+        #[deprecated(note = "используйте `find`")]
+        fn search(
+            address: Option<&str>,
+            card_number: Option<&str>,
+            cell_phone: Option<&str>,
+            client: Option<bool>,
+            code: Option<&str>,
+            email: Option<&str>,
+            employee: Option<bool>,
+            first_name: Option<&str>,
+            last_name: Option<&str>,
+            login: Option<&str>,
+            main_role_code: Option<&str>,
+            middle_name: Option<&str>,
+            name: Option<&str>,
+            note: Option<&str>,
+            phone: Option<&str>,
+            supplier: Option<bool>,
+            include_deleted: Option<bool>,
+        ) -> Result<Vec<Employee>>;
+        fn create_or_replace(employee_uuid: impl Into<EmployeeId>, employee: &Employee) -> Result<Employee>;
+        fn update_partial(employee_uuid: impl Into<EmployeeId>, employee: &Employee) -> Result<Employee>;
+        fn delete(employee_uuid: impl Into<EmployeeId>) -> Result<String>;
+    }
+
+    documents => DocumentsEndpoint {
+        fn get_documents(
+            store_id: Option<StoreId>,
+            date_from: Option<String>,
+            date_to: Option<String>,
+        ) -> Result<Vec<Document>>;
+        fn internal_transfers(query: InternalTransfersQuery) -> Result<InternalTransferListResult>;
+        #[deprecated(note = "используйте `internal_transfers`")]
+        fn list_internal_transfers(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            status: Option<crate::xml::response::DocumentStatus>,
+            revision_from: Option<i64>,
+        ) -> Result<InternalTransferListResult>;
+        fn get_internal_transfer_by_id(id: Uuid) -> Result<InternalTransferDto>;
+        fn get_internal_transfers_by_number(
+            document_number: impl AsRef<str>,
+        ) -> Result<Vec<InternalTransferDto>>;
+        fn upsert_internal_transfer(
+            transfer: InternalTransferDto,
+        ) -> Result<InternalTransferOperationResult>;
+        fn import_incoming_invoice(invoice: IncomingInvoiceDto) -> Result<DocumentValidationResult>;
+        fn import_outgoing_invoice(invoice: OutgoingInvoiceDto) -> Result<DocumentValidationResult>;
+        fn import_returned_invoice(invoice: ReturnedInvoiceDto) -> Result<DocumentValidationResult>;
+        fn import_incoming_inventory(
+            inventory: IncomingInventoryDto,
+        ) -> Result<IncomingInventoryValidationResultDto>;
+        fn unprocess_incoming_invoice(
+            invoice: IncomingInvoiceDto,
+        ) -> Result<DocumentValidationResult>;
+        fn unprocess_outgoing_invoice(
+            invoice: OutgoingInvoiceDto,
+        ) -> Result<DocumentValidationResult>;
+        fn export_outgoing_invoice(
+            from: impl Into<DateParam>,
+            to: impl Into<DateParam>,
+            supplier_id: Option<SupplierId>,
+        ) -> Result<Vec<OutgoingInvoiceDto>>;
+        fn outgoing_invoices_by_number(query: OutgoingInvoiceNumberQuery) -> Result<Vec<OutgoingInvoiceDto>>;
+        #[deprecated(note = "используйте `outgoing_invoices_by_number`")]
+        fn export_outgoing_invoice_by_number(
+            number: String,
+            current_year: bool,
+            from: Option<DateParam>,
+            to: Option<DateParam>,
+        ) -> Result<Vec<OutgoingInvoiceDto>>;
+    }
+
+    corporation => CorporationEndpoint {
+        fn get_departments(revision_from: Option<i64>) -> Result<Vec<CorporateItemDto>>;
+        fn get_stores(revision_from: Option<i64>) -> Result<Vec<CorporateItemDto>>;
+        fn get_groups(revision_from: Option<i64>) -> Result<Vec<GroupDto>>;
+        fn get_terminals(revision_from: Option<i64>) -> Result<Vec<TerminalDto>>;
+        fn search_department(code: impl Into<DepartmentCode>) -> Result<Option<CorporateItemDto>>;
+        fn search_store(code: impl Into<StoreCode>) -> Result<Option<CorporateItemDto>>;
+        fn search_groups(name: Option<&str>, department_id: Option<DepartmentId>) -> Result<Vec<GroupDto>>;
+        fn search_terminals(
+            name: Option<&str>,
+            computer_name: Option<&str>,
+            anonymous: Option<bool>,
+        ) -> Result<Vec<TerminalDto>>;
+        fn get_settings() -> Result<CorporationSettings>;
+    }
+
+    replication => ReplicationEndpoint {
+        fn get_statuses() -> Result<Vec<ReplicationStatus>>;
+        fn get_status_by_department(department_id: impl Into<DepartmentId>) -> Result<ReplicationStatus>;
+        fn get_server_type() -> Result<ServerType>;
+    }
+
+    events => EventsEndpoint {
+        fn get_events(
+            from_time: Option<DateParam>,
+            to_time: Option<DateParam>,
+            from_rev: Option<i64>,
+        ) -> Result<EventsList>;
+        fn get_events_by_filter(
+            event_types: Vec<String>,
+            order_nums: Option<Vec<String>>,
+        ) -> Result<EventsList>;
+        fn add_events(events: Vec<Event>) -> Result<EventsList>;
+        fn get_metadata() -> Result<GroupsList>;
+        fn get_metadata_by_filter(event_types: Vec<String>) -> Result<GroupsList>;
+        fn get_sessions(from_time: Option<DateParam>, to_time: Option<DateParam>) -> Result<Vec<CashSession>>;
+    }
+
+    products => ProductsEndpoint {
+        fn find(query: ProductQuery) -> Result<Vec<ProductDto>>;
+        #[deprecated(note = "используйте `find`")]
+        fn list(
+            include_deleted: Option<bool>,
+            ids: Option<Vec<String>>,
+            nums: Option<Vec<String>>,
+            types: Option<Vec<String>>,
+            category_ids: Option<Vec<String>>,
+            parent_ids: Option<Vec<String>>,
+        ) -> Result<Vec<ProductDto>>;
+        fn find_post(query: ProductQuery) -> Result<Vec<ProductDto>>;
+        #[deprecated(note = "используйте `find_post`")]
+        fn list_post(
+            include_deleted: Option<bool>,
+            revision_from: Option<i64>,
+            ids: Option<Vec<String>>,
+            nums: Option<Vec<String>>,
+            codes: Option<Vec<String>>,
+            types: Option<Vec<String>>,
+            category_ids: Option<Vec<String>>,
+            parent_ids: Option<Vec<String>>,
+        ) -> Result<Vec<ProductDto>>;
+        fn save(
+            product: ProductDto,
+            generate_nomenclature_code: Option<bool>,
+            generate_fast_code: Option<bool>,
+        ) -> Result<ProductOperationResult>;
+        fn update(
+            product: ProductDto,
+            override_fast_code: Option<bool>,
+            override_nomenclature_code: Option<bool>,
+        ) -> Result<ProductOperationResult>;
+        fn delete(ids: Vec<ProductId>) -> Result<ProductsOperationResult>;
+        fn restore(
+            ids: Vec<ProductId>,
+            override_nomenclature_code: Option<bool>,
+        ) -> Result<ProductsOperationResult>;
+        fn list_groups(include_deleted: Option<bool>) -> Result<Vec<ProductGroupDto>>;
+        fn list_categories(
+            include_deleted: Option<bool>,
+            ids: Option<Vec<String>>,
+            revision_from: Option<i64>,
+        ) -> Result<Vec<EntityDto>>;
+        fn list_categories_post(
+            include_deleted: Option<bool>,
+            ids: Option<Vec<String>>,
+            revision_from: Option<i64>,
+        ) -> Result<Vec<EntityDto>>;
+        fn save_category(name: String) -> Result<CategoryOperationResult>;
+        fn update_category(id: impl Into<ProductCategoryId>, name: String) -> Result<CategoryOperationResult>;
+        fn delete_category(id: impl Into<ProductCategoryId>) -> Result<CategoryOperationResult>;
+        fn restore_category(id: impl Into<ProductCategoryId>) -> Result<CategoryOperationResult>;
+    }
+
+    assembly_charts => AssemblyChartsEndpoint {
+        fn all(query: AssemblyChartsQuery) -> Result<ChartResultDto>;
+        #[deprecated(note = "используйте `all`")]
+        fn get_all(
+            date_from: impl Into<DateParam>,
+            date_to: Option<DateParam>,
+            include_deleted_products: Option<bool>,
+            include_prepared_charts: Option<bool>,
+        ) -> Result<ChartResultDto>;
+        fn all_update(known_revision: i64, query: AssemblyChartsQuery) -> Result<ChartResultDto>;
+        #[deprecated(note = "используйте `all_update`")]
+        fn get_all_update(
+            known_revision: i64,
+            date_from: impl Into<DateParam>,
+            date_to: Option<DateParam>,
+            include_deleted_products: Option<bool>,
+            include_prepared_charts: Option<bool>,
+        ) -> Result<ChartResultDto>;
+        fn get_tree(
+            date: impl Into<DateParam>,
+            product_id: impl Into<ProductId>,
+            department_id: Option<DepartmentId>,
+        ) -> Result<ChartResultDto>;
+        fn get_assembled(
+            date: impl Into<DateParam>,
+            product_id: impl Into<ProductId>,
+            department_id: Option<DepartmentId>,
+        ) -> Result<ChartResultDto>;
+        fn get_prepared(
+            date: impl Into<DateParam>,
+            product_id: impl Into<ProductId>,
+            department_id: Option<DepartmentId>,
+        ) -> Result<ChartResultDto>;
+        fn by_id(id: Uuid) -> Result<AssemblyChartDto>;
+        fn get_history(
+            product_id: impl Into<ProductId>,
+            department_id: Option<DepartmentId>,
+        ) -> Result<Vec<AssemblyChartDto>>;
+        fn save(chart: AssemblyChartDto) -> Result<AssemblyChartOperationResult>;
+        fn delete(id: Uuid) -> Result<AssemblyChartOperationResult>;
+    }
+
+    images => ImagesEndpoint {
+        fn load(image_id: Uuid) -> Result<ImageDto>;
+        fn save(data: String, id: Option<Uuid>) -> Result<ImageOperationResult>;
+        fn delete(ids: Vec<Uuid>) -> Result<ImageOperationResult>;
+    }
+
+    product_scales => ProductScalesEndpoint {
+        fn list(
+            ids: Option<Vec<Uuid>>,
+            include_deleted: Option<bool>,
+        ) -> Result<Vec<ProductScaleDto>>;
+        fn list_post(
+            ids: Option<Vec<Uuid>>,
+            include_deleted: Option<bool>,
+        ) -> Result<Vec<ProductScaleDto>>;
+        fn by_id(product_scale_id: Uuid) -> Result<ProductScaleDto>;
+        fn save(request: ProductScaleSaveRequest) -> Result<ProductScaleDto>;
+        fn update(request: ProductScaleUpdateRequest) -> Result<ProductScaleDto>;
+        fn delete(ids: Vec<Uuid>) -> Result<ProductScaleDto>;
+        fn restore(ids: Vec<Uuid>) -> Result<ProductScaleDto>;
+        fn get_for_product(product_id: impl Into<ProductId>) -> Result<ProductScaleOperationResult>;
+        fn get_for_products(
+            product_ids: Option<Vec<ProductId>>,
+            include_deleted_products: Option<bool>,
+        ) -> Result<HashMap<ProductId, Option<ProductScaleDto>>>;
+        fn get_for_products_post(
+            product_ids: Option<Vec<ProductId>>,
+            include_deleted_products: Option<bool>,
+        ) -> Result<HashMap<ProductId, Option<ProductScaleDto>>>;
+        fn set_for_product(
+            product_id: impl Into<ProductId>,
+            request: ProductProductScaleRequest,
+        ) -> Result<ProductScaleDto>;
+        fn delete_for_product(product_id: impl Into<ProductId>) -> Result<Uuid>;
+    }
+
+    reports => ReportsEndpoint {
+        fn balance_counteragents(query: CounteragentBalanceQuery) -> Result<Vec<BalanceCounteragent>>;
+        #[deprecated(note = "используйте `balance_counteragents`")]
+        fn get_balance_counteragents(
+            timestamp: impl Into<DateParam>,
+            accounts: Option<&[&str]>,
+            counteragents: Option<&[&str]>,
+            departments: Option<&[&str]>,
+        ) -> Result<Vec<BalanceCounteragent>>;
+        fn balance_stores(query: StoreBalanceQuery) -> Result<Vec<BalanceStore>>;
+        #[deprecated(note = "используйте `balance_stores`")]
+        fn get_balance_stores(
+            timestamp: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+            stores: Option<&[&str]>,
+            products: Option<&[&str]>,
+        ) -> Result<Vec<BalanceStore>>;
+        fn get_egais_marks_list(
+            fs_rar_ids: Option<&[&str]>,
+            revision_from: Option<i64>,
+        ) -> Result<EgaisMarksList>;
+        fn get_olap_columns(report_type: OlapReportType) -> Result<OlapColumns>;
+        fn get_olap_report(request: OlapReportRequest) -> Result<OlapReportResponse>;
+        fn olap_report_v1(query: OlapReportV1Query) -> Result<OlapReportResponse>;
+        #[deprecated(note = "используйте `olap_report_v1`")]
+        fn get_olap_report_v1(
+            report: OlapReportTypeV1,
+            from: impl Into<DateParam>,
+            to: impl Into<DateParam>,
+            summary: Option<bool>,
+            group_row: Option<&[&str]>,
+            group_col: Option<&[&str]>,
+            agr: Option<&[&str]>,
+        ) -> Result<OlapReportResponse>;
+        fn delivery_consolidated(query: DeliveryConsolidatedQuery) -> Result<DeliveryConsolidatedReport>;
+        #[deprecated(note = "используйте `delivery_consolidated`")]
+        fn get_delivery_consolidated(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+            writeoff_accounts: Option<&[&str]>,
+        ) -> Result<DeliveryConsolidatedReport>;
+        fn delivery_couriers(query: DeliveryCouriersQuery) -> Result<DeliveryCouriersReport>;
+        #[deprecated(note = "используйте `delivery_couriers`")]
+        fn get_delivery_couriers(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+            target_common_time: Option<i32>,
+            target_on_the_way_time: Option<i32>,
+            target_doubled_orders: Option<i32>,
+            target_tripled_orders: Option<i32>,
+            target_total_orders: Option<i32>,
+        ) -> Result<DeliveryCouriersReport>;
+        fn delivery_order_cycle(query: DeliveryOrderCycleQuery) -> Result<DeliveryOrderCycleReport>;
+        #[deprecated(note = "используйте `delivery_order_cycle`")]
+        fn get_delivery_order_cycle(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+            target_pizza_time: Option<i32>,
+            target_cutting_time: Option<i32>,
+            target_on_shelf_time: Option<i32>,
+            target_in_restaurant_time: Option<i32>,
+            target_on_the_way_time: Option<i32>,
+            target_total_time: Option<i32>,
+        ) -> Result<DeliveryOrderCycleReport>;
+        fn get_delivery_half_hour_detailed(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+        ) -> Result<DeliveryHalfHourDetailedReport>;
+        fn get_delivery_regions(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+        ) -> Result<DeliveryRegionsReport>;
+        fn delivery_loyalty(query: DeliveryLoyaltyQuery) -> Result<DeliveryLoyaltyReport>;
+        #[deprecated(note = "используйте `delivery_loyalty`")]
+        fn get_delivery_loyalty(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            departments: Option<&[&str]>,
+            metric_type: Option<&str>,
+        ) -> Result<DeliveryLoyaltyReport>;
+        fn store_operations(query: StoreOperationsQuery) -> Result<Vec<StoreReportItemDto>>;
+        #[deprecated(note = "используйте `store_operations`")]
+        fn get_store_operations(
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            stores: Option<&[&str]>,
+            document_types: Option<&[&str]>,
+            product_detalization: Option<bool>,
+            show_cost_corrections: Option<bool>,
+            preset_id: Option<&str>,
+        ) -> Result<Vec<StoreReportItemDto>>;
+        fn get_store_report_presets() -> Result<Vec<StoreReportPreset>>;
+        fn product_expense(query: ProductExpenseQuery) -> Result<Vec<DayDishValue>>;
+        #[deprecated(note = "используйте `product_expense`")]
+        fn get_product_expense(
+            department: &str,
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            hour_from: Option<i32>,
+            hour_to: Option<i32>,
+        ) -> Result<Vec<DayDishValue>>;
+        fn sales(query: SalesQuery) -> Result<Vec<DayDishValue>>;
+        #[deprecated(note = "используйте `sales`")]
+        fn get_sales(
+            department: &str,
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+            hour_from: Option<i32>,
+            hour_to: Option<i32>,
+            dish_details: Option<bool>,
+            all_revenue: Option<bool>,
+        ) -> Result<Vec<DayDishValue>>;
+        fn get_monthly_income_plan(
+            department: impl Into<DepartmentId>,
+            date_from: impl Into<DateParam>,
+            date_to: impl Into<DateParam>,
+        ) -> Result<Vec<BudgetPlanItemDto>>;
+        fn ingredient_entry(query: IngredientEntryQuery) -> Result<Vec<IngredientEntryDto>>;
+        #[deprecated(note = "используйте `ingredient_entry`")]
+        fn get_ingredient_entry(
+            department: &str,
+            date: impl Into<DateParam>,
+            product: Option<&str>,
+            product_article: Option<&str>,
+            include_subtree: Option<bool>,
+        ) -> Result<Vec<IngredientEntryDto>>;
+    }
+
+    entities => EntitiesEndpoint {
+        fn list(
+            root_types: &[EntityType],
+            include_deleted: Option<bool>,
+            revision_from: Option<i64>,
+        ) -> Result<Vec<ReferenceEntityDto>>;
+        fn list_with_extended_fields(
+            root_types: &[EntityType],
+            include_deleted: Option<bool>,
+            revision_from: Option<i64>,
+        ) -> Result<Vec<ReferenceEntity>>;
+        fn get_ids(
+            entity_type: EntityType,
+            include_deleted: Option<bool>,
+            revision_from: Option<i64>,
+        ) -> Result<Vec<Uuid>>;
+    }
+}
+
+impl ImagesEndpoint<'_> {
+    /// Загрузить изображение и записать декодированные байты в `writer`
+    ///
+    /// См. [`crate::endpoints::ImagesEndpoint::load_into`]. Возвращает количество записанных байт.
+    pub fn load_into<W>(&self, image_id: Uuid, writer: &mut W) -> Result<u64>
+    where
+        W: Write + ?Sized,
+    {
+        let mut writer = SyncWriter(writer);
+        self.client.block_on(
+            self.client
+                .as_async()
+                .images()
+                .load_into(image_id, &mut writer),
+        )
+    }
+}
+
+impl ReportsEndpoint<'_> {
+    /// OLAP-отчет v2 построчно, без чтения всего ответа в память
+    ///
+    /// См. [`crate::endpoints::ReportsEndpoint::stream_olap_report`]. Очередь запросов
+    /// клиента занята, пока итератор не будет дочитан или удален.
+    pub fn stream_olap_report(&self, request: OlapReportRequest) -> Result<OlapRows> {
+        let stream = self
+            .client
+            .block_on(self.client.as_async().reports().stream_olap_report(request))?;
+        Ok(OlapRows {
+            stream,
+            runtime: self.client.runtime.clone(),
+        })
+    }
+}
+
+/// Строки OLAP-отчета из [`ReportsEndpoint::stream_olap_report`]
+pub struct OlapRows {
+    stream: OlapRowStream,
+    runtime: Arc<Runtime>,
+}
+
+impl Iterator for OlapRows {
+    type Item = Result<OlapRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+/// `AsyncWrite` поверх `std::io::Write`
+///
+/// Запись выполняется сразу в `poll_write`: future, в котором она вызывается,
+/// выполняет `block_on` в потоке вызывающего кода, а не рабочий поток runtime.
+struct SyncWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: Write + ?Sized> AsyncWrite for SyncWriter<'_, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
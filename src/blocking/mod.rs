@@ -0,0 +1,151 @@
+//! Синхронный клиент для кода без async (фича `blocking`)
+//!
+//! [`IikoClient`] выполняет асинхронный `crate::IikoClient` на собственном runtime tokio
+//! с одним рабочим потоком, как `reqwest::blocking`. Методы endpoints те же, что
+//! у асинхронного клиента, но возвращают результат сразу:
+//!
+//! ```no_run
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::blocking::IikoClient;
+//! use iiko_server_api_sdk::{IikoConfig, ProductQuery};
+//!
+//! let client = IikoClient::new(IikoConfig::from_env()?)?;
+//! let products = client.products().find(ProductQuery::new().include_deleted(false))?;
+//! println!("{} products", products.len());
+//! client.logout()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Middleware, хранилище сессий, журнал изменений и собственный транспорт настраиваются
+//! на асинхронном клиенте, который затем оборачивается через [`IikoClient::from_async`].
+//!
+//! # Важно
+//! - Методы нельзя вызывать внутри async-кода (в задаче tokio): runtime внутри runtime
+//!   вызывает панику. В async-коде используйте `crate::IikoClient`
+//! - Клоны разделяют сессию, очередь запросов и runtime. Запросы из разных потоков
+//!   выполняются по очереди, как и у асинхронного клиента
+//! - Последний клон нельзя удалять внутри async-кода: runtime останавливается при удалении
+
+mod endpoints;
+
+pub use endpoints::*;
+
+use crate::compression::CompressionStats;
+use crate::config::IikoConfig;
+use crate::error::{IikoError, Result};
+use crate::middleware::ApiRequest;
+use crate::scheduler::{Priority, QueueStats};
+use crate::server_info::ServerInfo;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Синхронный клиент iiko Server API
+#[derive(Clone)]
+pub struct IikoClient {
+    inner: crate::IikoClient,
+    runtime: Arc<Runtime>,
+}
+
+impl IikoClient {
+    pub fn new(config: IikoConfig) -> Result<Self> {
+        Self::from_async(crate::IikoClient::new(config)?)
+    }
+
+    /// Синхронный клиент поверх уже настроенного асинхронного
+    ///
+    /// Запускает runtime с одним рабочим потоком `iiko-blocking`, на котором выполняются
+    /// фоновые задачи клиента (завершение начатой авторизации, освобождение сессий).
+    pub fn from_async(client: crate::IikoClient) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("iiko-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                IikoError::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("Failed to start tokio runtime: {}", e),
+                ))
+            })?;
+
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Асинхронный клиент с теми же сессией и очередью запросов
+    pub fn as_async(&self) -> &crate::IikoClient {
+        &self.inner
+    }
+
+    /// См. `crate::IikoClient::with_options`
+    pub fn with_options(&self, options: crate::RequestOptions) -> Self {
+        self.map(|client| client.with_options(options))
+    }
+
+    /// См. `crate::IikoClient::with_request_timeout`
+    pub fn with_request_timeout(&self, timeout: Duration) -> Self {
+        self.map(|client| client.with_request_timeout(timeout))
+    }
+
+    /// См. `crate::IikoClient::with_priority`
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        self.map(|client| client.with_priority(priority))
+    }
+
+    /// См. `crate::IikoClient::with_actor`
+    pub fn with_actor(&self, actor: impl Into<String>) -> Self {
+        self.map(|client| client.with_actor(actor))
+    }
+
+    pub fn config(&self) -> &IikoConfig {
+        self.inner.config()
+    }
+
+    pub fn queue_stats(&self) -> QueueStats {
+        self.inner.queue_stats()
+    }
+
+    /// См. `crate::IikoClient::compression_stats`
+    pub fn compression_stats(&self) -> CompressionStats {
+        self.inner.compression_stats()
+    }
+
+    /// См. `crate::IikoClient::dry_run_requests`
+    pub fn dry_run_requests(&self) -> Vec<ApiRequest> {
+        self.inner.dry_run_requests()
+    }
+
+    /// См. `crate::IikoClient::server_info`
+    pub fn server_info(&self) -> Result<ServerInfo> {
+        self.block_on(self.inner.server_info())
+    }
+
+    pub fn authenticate(&self) -> Result<String> {
+        self.block_on(self.inner.authenticate())
+    }
+
+    /// Освободить слот лицензии (`/logout`)
+    pub fn logout(&self) -> Result<String> {
+        self.block_on(self.inner.logout())
+    }
+
+    pub fn invalidate_session(&self) {
+        self.block_on(self.inner.invalidate_session())
+    }
+
+    fn map(&self, f: impl FnOnce(&crate::IikoClient) -> crate::IikoClient) -> Self {
+        Self {
+            inner: f(&self.inner),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Выполнить future асинхронного клиента в текущем потоке
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
@@ -0,0 +1,311 @@
+//! Запись и воспроизведение обменов с сервером iiko
+//!
+//! [`RecordingTransport`] пропускает запросы через вложенный транспорт и сохраняет каждую
+//! пару «запрос — ответ» в файл-кассету (JSON). [`ReplayTransport`] отдает ответы из
+//! кассеты без обращения к серверу, поэтому ошибку разбора ответа с сервера клиента
+//! можно воспроизвести локально.
+//!
+//! Перед записью из кассеты удаляются:
+//! - query-параметр `key` (ключ сессии)
+//! - `login`, `pass` и `key` в теле форм `/auth` и `/logout`
+//! - ключ сессии в ответе `/auth` и заголовки `Set-Cookie`
+//! - схема и хост сервера (сохраняется только путь URL)
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::{IikoClient, IikoConfig};
+//!
+//! // На сервере клиента
+//! let client = IikoClient::recording(IikoConfig::from_env()?, "bug-1234.json")?;
+//! client.suppliers().list(None).await?;
+//!
+//! // Локально, без сервера
+//! let config = IikoConfig::new("http://replay/resto/api", "", "");
+//! let client = IikoClient::replaying(config, "bug-1234.json")?;
+//! client.suppliers().list(None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::compression;
+use crate::error::{IikoError, Result};
+use crate::redact::{scrub_form, scrub_params};
+use crate::transport::{StatusCode, Transport, TransportRequest, TransportResponse, async_trait};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Значение, которым заменяются секреты в кассете
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Записанный обмен с сервером
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Прочитать кассету из файла
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Записать кассету в файл
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Пара «запрос — ответ»
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// Запрос без секретов
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Путь URL, например `/resto/api/suppliers`
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Тело как UTF-8 строка (невалидные последовательности заменяются)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl RecordedRequest {
+    fn from_request(request: &TransportRequest) -> Self {
+        let path = reqwest::Url::parse(&request.url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| request.url.clone());
+
+        let is_form = request
+            .header("content-type")
+            .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+        // Сжатое тело записывается распакованным, чтобы кассета не зависела от сжатия
+        let decoded = request
+            .header("content-encoding")
+            .zip(request.body.as_deref())
+            .and_then(|(encoding, body)| compression::decode_bytes(encoding, body));
+        let body = decoded
+            .as_deref()
+            .or(request.body.as_deref())
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+            .map(|body| if is_form { scrub_form(&body) } else { body });
+
+        Self {
+            method: request.method.to_string(),
+            path,
+            query: scrub_params(request.query.clone()),
+            body,
+        }
+    }
+
+    fn is_auth(&self) -> bool {
+        self.path.ends_with("/auth")
+    }
+}
+
+/// Ответ сервера
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedResponse {
+    fn from_response(request: &RecordedRequest, response: &TransportResponse) -> Self {
+        let body = if request.is_auth() && response.status.is_success() {
+            REDACTED.to_string()
+        } else {
+            response.text()
+        };
+
+        Self {
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter(|(name, _)| !name.eq_ignore_ascii_case("set-cookie"))
+                .cloned()
+                .collect(),
+            body,
+        }
+    }
+
+    fn to_response(&self) -> Result<TransportResponse> {
+        let status = StatusCode::from_u16(self.status).map_err(|e| {
+            IikoError::Transport(format!("Invalid status in cassette {}: {}", self.status, e))
+        })?;
+
+        let mut response = TransportResponse::new(status, self.body.clone());
+        response.headers = self.headers.clone();
+        Ok(response)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Транспорт, который записывает каждый обмен с сервером в кассету
+///
+/// Файл перезаписывается после каждого ответа, поэтому кассета сохраняется, даже если
+/// процесс упал на разборе ответа. Ошибки соединения не записываются.
+pub struct RecordingTransport<T> {
+    inner: T,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn new(inner: T, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Записанные на текущий момент обмены
+    pub fn cassette(&self) -> Cassette {
+        lock(&self.cassette).clone()
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn send(&self, mut request: TransportRequest) -> Result<TransportResponse> {
+        // Ответы записываются несжатыми
+        request
+            .headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("accept-encoding"));
+        let recorded = RecordedRequest::from_request(&request);
+        let response = self.inner.send(request).await?;
+
+        let json = {
+            let mut cassette = lock(&self.cassette);
+            let response = RecordedResponse::from_response(&recorded, &response);
+            cassette.interactions.push(Interaction {
+                request: recorded,
+                response,
+            });
+            serde_json::to_string_pretty(&*cassette)?
+        };
+        tokio::fs::write(&self.path, json).await?;
+
+        Ok(response)
+    }
+}
+
+/// Транспорт, который отдает ответы из кассеты
+///
+/// Запрос сопоставляется с первым еще не использованным обменом с тем же методом, путем,
+/// query-параметрами и телом (секреты сравниваются после очистки). Если подходящего
+/// обмена нет, возвращается `IikoError::Transport`.
+pub struct ReplayTransport {
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            interactions: Mutex::new(
+                cassette
+                    .interactions
+                    .into_iter()
+                    .map(|interaction| (interaction, false))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Количество обменов, которые еще не были воспроизведены
+    pub fn remaining(&self) -> usize {
+        lock(&self.interactions)
+            .iter()
+            .filter(|(_, used)| !used)
+            .count()
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let recorded = RecordedRequest::from_request(&request);
+        let mut interactions = lock(&self.interactions);
+
+        let (interaction, used) = interactions
+            .iter_mut()
+            .find(|(interaction, used)| !used && interaction.request == recorded)
+            .ok_or_else(|| {
+                IikoError::Transport(format!(
+                    "No recorded response in cassette for {} {}",
+                    recorded.method, recorded.path
+                ))
+            })?;
+
+        *used = true;
+        interaction.response.to_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Method;
+
+    #[test]
+    fn scrubs_session_key_and_credentials() {
+        let mut request =
+            TransportRequest::new(Method::POST, "https://customer.iiko.it/resto/api/auth");
+        request.headers.push((
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        ));
+        request.body = Some(b"login=admin&pass=secret".to_vec());
+
+        let recorded = RecordedRequest::from_request(&request);
+        assert_eq!(recorded.path, "/resto/api/auth");
+        assert_eq!(
+            recorded.body.as_deref(),
+            Some("login=%5BREDACTED%5D&pass=%5BREDACTED%5D")
+        );
+
+        let response = RecordedResponse::from_response(
+            &recorded,
+            &TransportResponse::new(StatusCode::OK, "session-key"),
+        );
+        assert_eq!(response.body, REDACTED);
+
+        let mut request =
+            TransportRequest::new(Method::GET, "https://customer.iiko.it/resto/api/suppliers");
+        request
+            .query
+            .push(("key".to_string(), "session-key".to_string()));
+        request
+            .query
+            .push(("revisionFrom".to_string(), "-1".to_string()));
+
+        let recorded = RecordedRequest::from_request(&request);
+        assert_eq!(
+            recorded.query,
+            [
+                ("key".to_string(), REDACTED.to_string()),
+                ("revisionFrom".to_string(), "-1".to_string())
+            ]
+        );
+    }
+}
@@ -1,80 +1,374 @@
+use crate::audit::{AuditEntry, AuditSink};
+use crate::cassette::{RecordingTransport, ReplayTransport};
+use crate::compression::{self, CompressionState, CompressionStats};
 use crate::config::IikoConfig;
-use crate::error::{IikoError, Result};
-use reqwest::Client;
-use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
+use crate::error::{self, IikoError, Result};
+use crate::middleware::{Access, ApiBody, ApiRequest, Middleware, Next, ReadMode, encode_form};
+use crate::redact;
+use crate::scheduler::{Priority, QueueStats, RequestScheduler, SchedulerPermit};
+use crate::server_info::{self, ServerInfo, ServerVersion};
+use crate::session::{self, SessionGuard};
+use crate::session_store::{self, SessionStore};
+use crate::streaming::ResponseStream;
+#[cfg(feature = "tracing")]
+use crate::telemetry;
+use crate::transport::{
+    Method, ReqwestTransport, StatusCode, StreamingResponse, Transport, TransportRequest,
+    TransportResponse,
+};
+use crate::write_mode::{self, WriteMode};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{OnceCell, RwLock};
+
+/// Параметры вызова, которые переопределяют настройки `IikoConfig`
+///
+/// Применяются ко всем запросам клиента, полученного через `IikoClient::with_options`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestOptions {
+    /// Таймаут одной попытки запроса. `None` — `IikoConfig::timeout_secs`
+    pub timeout: Option<Duration>,
+    /// Класс приоритета в очереди запросов клиента
+    pub priority: Priority,
+}
+
+impl RequestOptions {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
 
 /// Клиент для работы с iiko Server API
 ///
 /// # Важные ограничения:
 /// - Запросы выполняются последовательно (не параллельно) согласно требованиям iiko API
-/// - Каждый следующий запрос отправляется только после завершения предыдущего
+/// - Каждый следующий запрос отправляется только после завершения предыдущего. Порядок
+///   ожидающих запросов определяет приоритет (`with_priority`, см. модуль `scheduler`)
 /// - При авторизации занимается один слот лицензии - используйте `logout()` для освобождения
+/// - Если iiko отклонил ключ сессии (401 или "token expired"), клиент авторизуется заново
+///   и повторяет запрос один раз. Отключается через `IikoConfig::with_auto_reauth(false)`
+/// - Временные ошибки (сеть, 500/502/503/504) повторяются согласно `IikoConfig::retry`
+/// - Отмена вызова (drop future, `tokio::time::timeout`, `select!`) безопасна: место
+///   в очереди освобождается, а начатая авторизация завершается в фоне и сохраняет ключ
 #[derive(Clone)]
 pub struct IikoClient {
     config: Arc<IikoConfig>,
-    http_client: Client,
+    transport: Arc<dyn Transport>,
     session_id: Arc<RwLock<Option<String>>>,
-    /// Мьютекс для последовательного выполнения запросов
+    /// Ключи сессии, пережившие перезапуск процесса (см. модуль `session_store`)
+    session_store: Option<Arc<dyn SessionStore>>,
+    /// Журнал изменяющих запросов (см. модуль `audit`)
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Идентификатор пользователя для записей журнала (не разделяется между клонами)
+    actor: Option<Arc<str>>,
+    /// Цепочка middleware вокруг каждого запроса к API
+    middleware: Arc<[Arc<dyn Middleware>]>,
+    /// Очередь для последовательного выполнения запросов
     /// Согласно документации iiko: "Запросы должны выполнятся последовательно друг за другом"
-    request_mutex: Arc<Mutex<()>>,
+    scheduler: RequestScheduler,
+    /// Ответ `get_server_info.jsp`, полученный первым успешным `server_info()`
+    server_info: Arc<OnceCell<ServerInfo>>,
+    /// Версия для проверки методов. `None` — узнать не удалось, методы не проверяются
+    known_version: Arc<OnceCell<Option<ServerVersion>>>,
+    /// Счетчики сжатия ответов и запросов
+    compression: Arc<CompressionState>,
+    /// Последние запросы, не отправленные в режиме `WriteMode::DryRun`
+    dry_run_log: Arc<Mutex<VecDeque<ApiRequest>>>,
+    /// Переопределения для запросов этого экземпляра (не разделяются между клонами)
+    options: RequestOptions,
 }
 
 impl IikoClient {
     pub fn new(config: IikoConfig) -> Result<Self> {
+        let transport = Self::default_transport(&config)?;
+        Ok(Self::with_transport(config, transport))
+    }
+
+    /// Создать клиент, который записывает все запросы и ответы в кассету `path`
+    ///
+    /// Ключ сессии, логин и пароль в кассету не попадают. См. модуль `cassette`.
+    pub fn recording(config: IikoConfig, path: impl Into<PathBuf>) -> Result<Self> {
+        let transport = RecordingTransport::new(Self::default_transport(&config)?, path);
+        Ok(Self::with_transport(config, transport))
+    }
+
+    /// Создать клиент, который отдает ответы из кассеты `path` без обращения к серверу
+    ///
+    /// `base_url`, логин и пароль из `config` на воспроизведение не влияют.
+    pub fn replaying(config: IikoConfig, path: impl AsRef<Path>) -> Result<Self> {
+        let transport = ReplayTransport::from_file(path)?;
+        Ok(Self::with_transport(config, transport))
+    }
+
+    pub(crate) fn default_transport(config: &IikoConfig) -> Result<ReqwestTransport> {
         // Если timeout_secs == 0 — не ставим таймаут на уровне HTTP-клиента (ожидаем, что таймауты обрабатывает вызывающий код)
-        let http_client = if config.timeout_secs == 0 {
-            Client::builder().build().map_err(|e| {
-                IikoError::Configuration(format!("Failed to create HTTP client: {}", e))
-            })?
-        } else {
-            let timeout = std::time::Duration::from_secs(config.timeout_secs);
-            Client::builder().timeout(timeout).build().map_err(|e| {
-                IikoError::Configuration(format!("Failed to create HTTP client: {}", e))
-            })?
-        };
+        let timeout = (config.timeout_secs != 0).then(|| Duration::from_secs(config.timeout_secs));
+        ReqwestTransport::new(timeout)
+    }
 
-        Ok(Self {
+    /// Создать клиент поверх собственного транспорта
+    ///
+    /// Используется в тестах, чтобы отдавать заранее подготовленные ответы без сервера iiko,
+    /// или чтобы передать настроенный `reqwest::Client` через `ReqwestTransport::from_client`.
+    pub fn with_transport(config: IikoConfig, transport: impl Transport + 'static) -> Self {
+        Self {
             config: Arc::new(config),
-            http_client,
+            transport: Arc::new(transport),
             session_id: Arc::new(RwLock::new(None)),
-            request_mutex: Arc::new(Mutex::new(())),
-        })
+            session_store: None,
+            audit_sink: None,
+            actor: None,
+            middleware: Arc::new([]),
+            scheduler: RequestScheduler::default(),
+            server_info: Arc::new(OnceCell::new()),
+            known_version: Arc::new(OnceCell::new()),
+            compression: Arc::new(CompressionState::default()),
+            dry_run_log: Arc::new(Mutex::new(VecDeque::new())),
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Брать ключ сессии из `store` перед `/auth` и сохранять туда новые ключи
+    ///
+    /// Сохраненный ключ используется, пока iiko его принимает; отклоненный ключ удаляется
+    /// из хранилища, и клиент авторизуется заново. Применяется ко всем клонам клиента,
+    /// созданным после вызова.
+    pub fn with_session_store(mut self, store: impl SessionStore + 'static) -> Self {
+        self.session_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Передавать в `sink` запись о каждом изменяющем запросе
+    ///
+    /// Применяется ко всем клонам клиента, созданным после вызова. См. модуль `audit`.
+    pub fn with_audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Клиент с теми же сессией и очередью запросов, запросы которого записываются
+    /// в журнал от имени `actor` (логин пользователя, имя задания синхронизации)
+    pub fn with_actor(&self, actor: impl Into<String>) -> Self {
+        Self {
+            actor: Some(Arc::from(actor.into())),
+            ..self.clone()
+        }
+    }
+
+    /// Клиент с теми же сессией и очередью запросов, но с другими параметрами вызова
+    ///
+    /// # Пример
+    /// ```no_run
+    /// # async fn example(
+    /// #     client: iiko_server_api_sdk::IikoClient,
+    /// #     request: iiko_server_api_sdk::OlapReportRequest,
+    /// # ) -> iiko_server_api_sdk::Result<()> {
+    /// use iiko_server_api_sdk::RequestOptions;
+    /// use std::time::Duration;
+    ///
+    /// let slow = client.with_options(RequestOptions::default().with_timeout(Duration::from_secs(600)));
+    /// let report = slow.reports().get_olap_report(request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self {
+            options,
+            ..self.clone()
+        }
+    }
+
+    /// Клиент с таймаутом `timeout` на каждую попытку запроса (остальные параметры вызова сохраняются)
+    pub fn with_request_timeout(&self, timeout: Duration) -> Self {
+        self.with_options(self.options.with_timeout(timeout))
+    }
+
+    /// Клиент, запросы которого стоят в очереди с приоритетом `priority`
+    ///
+    /// Очередь общая для всех клонов клиента, поэтому клон с `Priority::Interactive`
+    /// обгоняет ожидающие запросы `Priority::Bulk` того же клиента.
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        self.with_options(self.options.with_priority(priority))
+    }
+
+    /// Глубина очереди и время ожидания по классам приоритета
+    pub fn queue_stats(&self) -> QueueStats {
+        self.scheduler.stats()
+    }
+
+    /// Сколько байт сэкономило сжатие ответов и запросов (см. модуль `compression`)
+    pub fn compression_stats(&self) -> CompressionStats {
+        self.compression.stats()
+    }
+
+    /// Изменяющие запросы, которые клиент не отправил в режиме `WriteMode::DryRun`
+    ///
+    /// Журнал общий для клонов клиента и хранит последние 100 запросов, от старых к новым.
+    /// Тело запроса — ровно то, что ушло бы на сервер (`ApiBody::text()`).
+    pub fn dry_run_requests(&self) -> Vec<ApiRequest> {
+        self.dry_run_log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Версия и редакция сервера iiko
+    ///
+    /// Запрашивается один раз через `get_server_info.jsp` (без авторизации) и кэшируется
+    /// для всех клонов клиента. Неудачный запрос не кэшируется.
+    pub async fn server_info(&self) -> Result<ServerInfo> {
+        self.server_info
+            .get_or_try_init(|| self.fetch_server_info())
+            .await
+            .cloned()
+    }
+
+    async fn fetch_server_info(&self) -> Result<ServerInfo> {
+        let _permit = self.scheduler.acquire(self.options.priority).await;
+
+        let mut request = TransportRequest::new(
+            Method::GET,
+            server_info::server_info_url(&self.config.base_url),
+        );
+        request
+            .query
+            .push(("encoding".to_string(), "UTF-8".to_string()));
+        request.timeout = self.config.auth_timeout().or(self.options.timeout);
+
+        let response = self.transport.send(request).await?;
+        if !response.status.is_success() {
+            return Err(IikoError::from_response(response.status, response.text()));
+        }
+        ServerInfo::from_xml(&response.text())
+    }
+
+    /// Версия сервера для проверки методов, если проверка включена и версию удалось узнать
+    ///
+    /// Окончательный отказ `server_info()` (404 или ответ, который не разбирается) кэшируется
+    /// как `None`: сервер, который не отдает `get_server_info.jsp` (например, за прокси),
+    /// не получает лишний запрос перед каждым вызовом. После временной ошибки (таймаут, 5xx)
+    /// вызов выполняется без проверки версии, а следующий вызов запрашивает версию снова.
+    async fn known_version(&self) -> Option<ServerVersion> {
+        if !self.config.check_server_version {
+            return None;
+        }
+
+        let version = self
+            .known_version
+            .get_or_try_init(async || match self.server_info().await {
+                Ok(info) => Ok(Some(info.version)),
+                Err(IikoError::NotFound(_) | IikoError::XmlDeserialization(_)) => Ok(None),
+                Err(error) => Err(error),
+            })
+            .await;
+        version.ok().copied().flatten()
+    }
+
+    /// Вернуть `IikoError::UnsupportedVersion`, если сервер старше `required`
+    pub(crate) async fn require_version(&self, required: ServerVersion) -> Result<()> {
+        match self.known_version().await {
+            Some(actual) if actual < required => {
+                Err(IikoError::UnsupportedVersion { required, actual })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Поддерживает ли сервер параметр, появившийся в версии `since`
+    ///
+    /// Если версия неизвестна, параметр считается поддерживаемым и передается.
+    pub(crate) async fn supports(&self, since: ServerVersion) -> bool {
+        self.known_version()
+            .await
+            .is_none_or(|actual| actual >= since)
+    }
+
+    /// Значение параметра, появившегося в версии `since`, или `None` для более старого сервера
+    ///
+    /// Старые серверы молча игнорируют неизвестные параметры, поэтому такой параметр
+    /// не передается вовсе. Для `None` версия сервера не запрашивается.
+    pub(crate) async fn param_since<T>(&self, since: ServerVersion, value: Option<T>) -> Option<T> {
+        match value {
+            Some(value) if self.supports(since).await => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Отправить форму (`application/x-www-form-urlencoded`) без ключа сессии
+    async fn send_form(&self, endpoint: &str, form: &[(&str, &str)]) -> Result<TransportResponse> {
+        let url = format!("{}/{}", self.config.base_url, endpoint);
+        let mut request = TransportRequest::new(Method::POST, url);
+        request.headers.push((
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        ));
+        request.body = Some(encode_form(form)?.into_bytes());
+        request.timeout = self.config.auth_timeout().or(self.options.timeout);
+
+        let response = self.transport.send(request);
+        #[cfg(feature = "tracing")]
+        let response = telemetry::session_call(endpoint, response);
+        response.await
     }
 
-    /// Внутренний метод аутентификации (без мьютекса)
-    /// Используется внутри других методов, которые уже держат мьютекс
+    /// Внутренний метод аутентификации (без очереди)
+    /// Используется внутри других методов, которые уже получили очередь
+    ///
+    /// `/auth` выполняется в отдельной задаче: если вызывающий future отменят, задача
+    /// все равно дождется ответа и сохранит ключ, и слот лицензии не будет занят ключом,
+    /// о котором клиент не знает.
     async fn authenticate_internal(&self) -> Result<String> {
+        if let Some(ref sid) = *self.session_id.read().await {
+            return Ok(sid.clone());
+        }
+
+        let client = self.clone();
+        let acquire = async move { client.acquire_session().await };
+        #[cfg(feature = "tracing")]
+        let acquire = tracing::Instrument::in_current_span(acquire);
+
+        tokio::spawn(acquire)
+            .await
+            .map_err(|e| IikoError::Authentication(format!("Authentication task failed: {}", e)))?
+    }
+
+    async fn acquire_session(&self) -> Result<String> {
         let mut session = self.session_id.write().await;
 
         if let Some(ref sid) = *session {
             return Ok(sid.clone());
         }
 
-        let url = format!("{}/auth", self.config.base_url);
+        if let Some(stored) = self.load_stored_key().await {
+            *session = Some(stored.clone());
+            self.register_session(&stored);
+            return Ok(stored);
+        }
+
+        let password_hash = self.config.credentials.password_hash();
         let form = [
             ("login", self.config.login.as_str()),
-            ("pass", self.config.password.as_str()),
+            ("pass", password_hash.as_str()),
         ];
 
-        let response = self
-            .http_client
-            .post(&url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&form)
-            .send()
-            .await?;
+        let response = self.send_form("auth", &form).await?;
 
-        let status = response.status();
+        let status = response.status;
         if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(IikoError::Authentication(format!(
-                "Authentication failed with status: {} - {}",
-                status, error_text
-            )));
+            return Err(IikoError::from_auth_response(status, response.text()));
         }
 
-        let session_id = response.text().await?.trim().to_string();
+        let session_id = response.text().trim().to_string();
         if session_id.is_empty() {
             return Err(IikoError::Authentication(
                 "Empty token in response".to_string(),
@@ -82,45 +376,124 @@ impl IikoClient {
         }
 
         *session = Some(session_id.clone());
+        self.register_session(&session_id);
+        self.save_stored_key(&session_id).await;
 
         Ok(session_id)
     }
 
-    /// Публичный метод аутентификации (защищен мьютексом)
+    fn register_session(&self, key: &str) {
+        session::register(
+            &self.config.base_url,
+            key,
+            &self.transport,
+            self.config.auth_timeout(),
+        );
+    }
+
+    fn store_scope(&self) -> String {
+        session_store::scope(&self.config.base_url, &self.config.login)
+    }
+
+    /// Ключ из `SessionStore`. Ошибка хранилища равносильна отсутствию ключа
+    async fn load_stored_key(&self) -> Option<String> {
+        let store = self.session_store.as_ref()?;
+        match store.load(&self.store_scope()).await {
+            Ok(key) => key.filter(|key| !key.trim().is_empty()),
+            Err(_error) => {
+                #[cfg(feature = "tracing")]
+                telemetry::record_store_error("load", &_error);
+                None
+            }
+        }
+    }
+
+    async fn save_stored_key(&self, key: &str) {
+        if let Some(store) = &self.session_store
+            && let Err(_error) = store.save(&self.store_scope(), key).await
+        {
+            #[cfg(feature = "tracing")]
+            telemetry::record_store_error("save", &_error);
+        }
+    }
+
+    async fn forget_stored_key(&self, key: &str) {
+        if let Some(store) = &self.session_store
+            && let Err(_error) = store.remove(&self.store_scope(), key).await
+        {
+            #[cfg(feature = "tracing")]
+            telemetry::record_store_error("remove", &_error);
+        }
+    }
+
+    /// Получить ключ сессии и вернуть guard, который освободит слот лицензии
+    ///
+    /// # Что в ответе
+    /// - `SessionGuard`, через который доступны все методы клиента. При выходе guard'а
+    ///   из области видимости (в том числе по ошибке или панике) выполняется `/logout`
+    ///
+    /// # Пример
+    /// ```no_run
+    /// # async fn example(client: iiko_server_api_sdk::IikoClient) -> iiko_server_api_sdk::Result<()> {
+    /// let session = client.session().await?;
+    /// let suppliers = session.suppliers().list(None).await?;
+    /// session.release().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn session(&self) -> Result<SessionGuard> {
+        self.authenticate().await?;
+        Ok(SessionGuard::new(self.clone()))
+    }
+
+    /// Выполнить `/logout`, только если ключ сессии уже получен
+    pub(crate) async fn release_session(&self) -> Result<()> {
+        let _permit = self.scheduler.acquire(self.options.priority).await;
+
+        let key = self.session_id.write().await.take();
+        match key {
+            Some(key) => {
+                self.forget_stored_key(&key).await;
+                session::logout_key(
+                    &self.config.base_url,
+                    &key,
+                    self.transport.as_ref(),
+                    self.config.auth_timeout(),
+                )
+                .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Публичный метод аутентификации (выполняется в очереди запросов)
     /// Используется для прямого вызова через auth().login()
     pub async fn authenticate(&self) -> Result<String> {
         // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
+        let _permit = self.scheduler.acquire(self.options.priority).await;
         self.authenticate_internal().await
     }
 
     pub async fn logout(&self) -> Result<String> {
         // Logout также должен быть последовательным
-        let _guard = self.request_mutex.lock().await;
+        let _permit = self.scheduler.acquire(self.options.priority).await;
 
         let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/logout", self.config.base_url);
 
         let form = [("key", session_id.as_str())];
 
-        let response = self
-            .http_client
-            .post(&url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&form)
-            .send()
-            .await?;
+        let response = self.send_form("logout", &form).await?;
 
-        let status = response.status();
+        let status = response.status;
         if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
             return Err(IikoError::Api(format!(
                 "Logout failed with status: {} - {}",
-                status, error_text
+                status,
+                response.text()
             )));
         }
 
-        let result = response.text().await?.trim().to_string();
+        let result = response.text().trim().to_string();
 
         // Invalidate session after successful logout
         self.invalidate_session().await;
@@ -128,122 +501,378 @@ impl IikoClient {
         Ok(result)
     }
 
-    fn handle_error_response(status: reqwest::StatusCode, error_text: String) -> IikoError {
-        match status.as_u16() {
-            400 => IikoError::BadRequest(error_text),
-            401 => IikoError::Unauthorized(error_text),
-            403 => IikoError::Forbidden(error_text),
-            404 => IikoError::NotFound(error_text),
-            409 => IikoError::BusinessLogic(error_text),
-            500 => IikoError::InternalServerError(error_text),
-            _ => IikoError::Api(format!(
-                "Request failed with status: {} - {}",
-                status, error_text
-            )),
-        }
+    /// Добавить middleware в цепочку запросов клиента (см. модуль `middleware`)
+    ///
+    /// Первое добавленное middleware — внешнее. Цепочка общая для клонов клиента,
+    /// созданных после вызова.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        let mut chain = self.middleware.to_vec();
+        chain.push(Arc::new(middleware));
+        self.middleware = chain.into();
+        self
     }
 
-    pub async fn get(&self, endpoint: &str) -> Result<String> {
-        // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
+    /// Выполнить запрос к iiko через цепочку middleware и прочитать ответ целиком
+    async fn execute(&self, request: ApiRequest) -> Result<String> {
+        let audit = match &self.audit_sink {
+            Some(sink) if request.is_mutating() => Some((sink, request.clone(), SystemTime::now())),
+            _ => None,
+        };
 
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
+        let result = async {
+            let response = Next::new(self, &self.middleware, ReadMode::Buffered)
+                .run(request)
+                .await?;
+            Ok(response.into_response().await?.text())
+        }
+        .await;
 
-        let response = self
-            .http_client
-            .get(&url)
-            .query(&[("key", session_id.as_str())])
-            .send()
-            .await?;
+        if let Some((sink, request, timestamp)) = audit {
+            self.audit(sink.as_ref(), request, timestamp, &result).await;
+        }
+        result
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
+    /// Передать запись о выполненном изменяющем запросе в журнал
+    async fn audit(
+        &self,
+        sink: &dyn AuditSink,
+        request: ApiRequest,
+        timestamp: SystemTime,
+        result: &Result<String>,
+    ) {
+        // Форму, которую не удалось закодировать, сервер не получил: запрос завершился ошибкой.
+        // Пароли сотрудников и учетные данные в журнал не попадают
+        let payload = request.body.redacted_text().ok().flatten();
+        let (response, error) = match result {
+            Ok(body) => (Some(redact::scrub_text(body)), None),
+            Err(error) => (None, Some(error.to_string())),
+        };
+        let entry = AuditEntry {
+            timestamp,
+            actor: self.actor.as_deref().map(str::to_string),
+            method: request.method.to_string(),
+            endpoint: request.endpoint,
+            params: request.params,
+            payload,
+            response,
+            error,
+            dry_run: self.config.write_mode == WriteMode::DryRun,
+        };
+
+        if let Err(_error) = sink.record(&entry).await {
+            #[cfg(feature = "tracing")]
+            telemetry::record_audit_error(&_error);
         }
+    }
 
-        Ok(response.text().await?)
+    /// Выполнить запрос и вернуть тело ответа для чтения по частям
+    ///
+    /// Очередь остается занятой, пока возвращенный `ResponseStream` не будет дочитан
+    /// или удален. Повторы по `RetryPolicy` выполняются только до получения заголовков
+    /// ответа: ошибка при чтении тела возвращается из потока.
+    async fn execute_stream(&self, request: ApiRequest) -> Result<ResponseStream> {
+        let response = Next::new(self, &self.middleware, ReadMode::Streaming)
+            .run(request)
+            .await?;
+        Ok(ResponseStream::new(response.body, None))
     }
 
-    pub async fn post_xml(&self, endpoint: &str, xml_body: &str) -> Result<String> {
-        // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
+    /// Конец цепочки middleware: выполнить запрос с учетом `RetryPolicy` из конфигурации
+    ///
+    /// Держит очередь на все время выполнения, включая паузы между повторами
+    /// и повторный логин, поэтому повторная попытка не может вклиниться между чужими запросами.
+    /// В режиме `Streaming` очередь передается вместе с телом ответа.
+    pub(crate) async fn dispatch(
+        &self,
+        request: ApiRequest,
+        mode: ReadMode,
+    ) -> Result<StreamingResponse> {
+        let result = async {
+            if request.is_mutating() {
+                match self.config.write_mode {
+                    WriteMode::Normal => {}
+                    WriteMode::ReadOnly => {
+                        return Err(IikoError::ReadOnly {
+                            method: request.method.to_string(),
+                            endpoint: request.endpoint.clone(),
+                        });
+                    }
+                    WriteMode::DryRun => return self.dry_run(&request),
+                }
+            }
+
+            match mode {
+                ReadMode::Buffered => {
+                    let (response, _permit) = self
+                        .execute_with_retries(&request, async |response| {
+                            let response = response.into_response().await?;
+                            #[cfg(feature = "tracing")]
+                            telemetry::record_response(&response);
+                            Ok(StreamingResponse::from(response))
+                        })
+                        .await?;
+                    Ok(response)
+                }
+                ReadMode::Streaming => {
+                    let (response, permit) = self
+                        .execute_with_retries(&request, async |response| Ok(response))
+                        .await?;
+                    let StreamingResponse {
+                        status,
+                        headers,
+                        body,
+                    } = response;
+                    let mut response = StreamingResponse::new(
+                        status,
+                        Box::pin(ResponseStream::new(body, Some(permit))),
+                    );
+                    response.headers = headers;
+                    Ok(response)
+                }
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let result = tracing::Instrument::instrument(
+            result,
+            telemetry::request_span(&request.method, &request.endpoint, self.options.priority),
+        );
+        result.await
+    }
 
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
+    /// Записать не отправленный запрос в журнал и вернуть синтетический ответ (см. модуль `write_mode`)
+    fn dry_run(&self, request: &ApiRequest) -> Result<StreamingResponse> {
+        let body = write_mode::synthetic_body(request);
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&[("key", session_id.as_str())])
-            .header("Content-Type", "application/xml")
-            .body(xml_body.to_string())
-            .send()
-            .await?;
+        #[cfg(feature = "tracing")]
+        telemetry::record_dry_run(request)?;
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
+        let mut log = self
+            .dry_run_log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if log.len() == write_mode::DRY_RUN_LOG_LEN {
+            log.pop_front();
         }
+        log.push_back(request.clone());
 
-        Ok(response.text().await?)
+        Ok(StreamingResponse::from(TransportResponse::new(
+            StatusCode::OK,
+            body,
+        )))
     }
 
-    pub async fn put_xml(&self, endpoint: &str, xml_body: &str) -> Result<String> {
+    /// Получить очередь и выполнять попытки, пока `RetryPolicy` разрешает повтор
+    ///
+    /// `read` разбирает успешный ответ и считается частью попытки: ошибка чтения тела
+    /// тоже повторяется. Очередь возвращается вызывающему вместе с результатом.
+    async fn execute_with_retries<T>(
+        &self,
+        request: &ApiRequest,
+        read: impl AsyncFn(StreamingResponse) -> Result<T>,
+    ) -> Result<(T, SchedulerPermit)> {
+        #[cfg(feature = "tracing")]
+        let queued = std::time::Instant::now();
         // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
-
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
-
-        let response = self
-            .http_client
-            .put(&url)
-            .query(&[("key", session_id.as_str())])
-            .header("Content-Type", "application/xml")
-            .body(xml_body.to_string())
-            .send()
-            .await?;
-
-        let status = response.status();
-        // PUT returns 200 for update, 201 for create
-        if status != 200 && status != 201 {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
+        let permit = self.scheduler.acquire(self.options.priority).await;
+
+        #[cfg(feature = "tracing")]
+        telemetry::record_queue_wait(queued);
+        #[cfg(feature = "tracing")]
+        let started = std::time::Instant::now();
+        let policy = &self.config.retry;
+        let mutating = request.is_mutating();
+        let mut attempt = 1;
+
+        loop {
+            let result = match self.send(request).await {
+                Ok(response) => read(response).await,
+                Err(error) => Err(error),
+            };
+
+            match result {
+                Err(error) if policy.should_retry(attempt, mutating, &error) => {
+                    #[cfg(feature = "tracing")]
+                    telemetry::record_retry(attempt, &error);
+                    tokio::time::sleep(policy.delay_for(attempt)).await;
+                    attempt += 1;
+                }
+                result => {
+                    #[cfg(feature = "tracing")]
+                    telemetry::record_outcome(started, &result);
+                    return result.map(|value| (value, permit));
+                }
+            }
         }
+    }
 
-        Ok(response.text().await?)
+    /// Одна попытка запроса с повторной авторизацией при истекшей сессии
+    ///
+    /// Вызывается только из `execute_with_retries`, который уже получил очередь.
+    /// Возвращает успешный ответ с непрочитанным телом.
+    async fn send(&self, api_request: &ApiRequest) -> Result<StreamingResponse> {
+        let url = format!("{}/{}", self.config.base_url, api_request.endpoint);
+        let method = &api_request.method;
+        let body = api_request
+            .body
+            .text()?
+            .map(|text| text.into_owned().into_bytes());
+        let mut gzipped = match &body {
+            Some(body) => self
+                .compression
+                .compress_request(&self.config.compression, body)?,
+            None => None,
+        };
+        let mut reauthenticated = false;
+
+        loop {
+            let session_id = self.authenticate_internal().await?;
+
+            let mut request = TransportRequest::new(method.clone(), url.as_str());
+            request.timeout = self.options.timeout;
+            request.query.push(("key".to_string(), session_id));
+            request.query.extend(api_request.params.iter().cloned());
+            request.body = gzipped.clone().or_else(|| body.clone());
+            if let Some(content_type) = api_request.body.content_type() {
+                request
+                    .headers
+                    .push(("Content-Type".to_string(), content_type.to_string()));
+            }
+            if gzipped.is_some() {
+                request
+                    .headers
+                    .push(("Content-Encoding".to_string(), "gzip".to_string()));
+            }
+            if self.config.compression.responses {
+                request.headers.push((
+                    "Accept-Encoding".to_string(),
+                    compression::ACCEPT_ENCODING.to_string(),
+                ));
+            }
+            request.headers.extend(api_request.headers.iter().cloned());
+
+            let response = self.transport.send_streaming(request).await?;
+            let response = compression::decode_response(response, self.compression.clone());
+            let status = response.status;
+            #[cfg(feature = "tracing")]
+            telemetry::record_status(status);
+
+            // PUT returns 200 for update, 201 for create
+            let accepted = if *method == Method::PUT {
+                status == StatusCode::OK || status == StatusCode::CREATED
+            } else {
+                status.is_success()
+            };
+
+            if accepted {
+                if let (Some(body), Some(gzipped)) = (&body, &gzipped) {
+                    self.compression.record_request(body.len(), gzipped.len());
+                }
+                return Ok(response);
+            }
+
+            if status == StatusCode::UNSUPPORTED_MEDIA_TYPE && gzipped.is_some() {
+                // Сервер не распаковывает запросы: повторяем без сжатия и больше не сжимаем
+                self.compression.reject_requests();
+                gzipped = None;
+                continue;
+            }
+
+            let response = response.into_response().await?;
+            #[cfg(feature = "tracing")]
+            telemetry::record_response(&response);
+            let error_text = response.text();
+
+            if self.config.auto_reauth
+                && !reauthenticated
+                && error::is_session_expired(status, &error_text)
+            {
+                // Сбрасываем ключ и повторяем запрос один раз с новой сессией
+                #[cfg(feature = "tracing")]
+                telemetry::record_reauth();
+                self.invalidate_session().await;
+                reauthenticated = true;
+                continue;
+            }
+
+            return Err(IikoError::from_keyed_response(status, error_text));
+        }
     }
 
-    pub async fn post_form(&self, endpoint: &str, form_data: &[(&str, &str)]) -> Result<String> {
-        // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
+    pub async fn get(&self, endpoint: &str) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Read,
+            Method::GET,
+            endpoint,
+            &[],
+            ApiBody::Empty,
+        ))
+        .await
+    }
 
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
+    pub async fn post_xml(&self, endpoint: &str, xml_body: &str) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Write,
+            Method::POST,
+            endpoint,
+            &[],
+            ApiBody::Xml(xml_body.to_string()),
+        ))
+        .await
+    }
 
-        let query_params = vec![("key", session_id.as_str())];
-        let form_params: Vec<(&str, &str)> = form_data.to_vec();
+    /// POST с XML телом, который только читает данные (например, фильтр событий)
+    ///
+    /// В отличие от `post_xml`, повторяется по `RetryPolicy` как обычный GET.
+    pub(crate) async fn post_xml_query(&self, endpoint: &str, xml_body: &str) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Read,
+            Method::POST,
+            endpoint,
+            &[],
+            ApiBody::Xml(xml_body.to_string()),
+        ))
+        .await
+    }
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&query_params)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&form_params)
-            .send()
-            .await?;
+    pub async fn put_xml(&self, endpoint: &str, xml_body: &str) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Write,
+            Method::PUT,
+            endpoint,
+            &[],
+            ApiBody::Xml(xml_body.to_string()),
+        ))
+        .await
+    }
 
-        let status = response.status();
-        // POST returns 200 for update, 201 for create
-        if status != 200 && status != 201 && !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
-        }
+    pub async fn post_form(&self, endpoint: &str, form_data: &[(&str, &str)]) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Write,
+            Method::POST,
+            endpoint,
+            &[],
+            ApiBody::form(form_data),
+        ))
+        .await
+    }
 
-        Ok(response.text().await?)
+    /// POST с form data, который только читает данные (списки с фильтрами)
+    ///
+    /// В отличие от `post_form`, повторяется по `RetryPolicy` как обычный GET.
+    pub(crate) async fn post_form_query(
+        &self,
+        endpoint: &str,
+        form_data: &[(&str, &str)],
+    ) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Read,
+            Method::POST,
+            endpoint,
+            &[],
+            ApiBody::form(form_data),
+        ))
+        .await
     }
 
     pub async fn post_json(
@@ -252,81 +881,90 @@ impl IikoClient {
         json_body: &str,
         query_params: &[(&str, &str)],
     ) -> Result<String> {
-        // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
-
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
-
-        let mut all_params = vec![("key", session_id.as_str())];
-        all_params.extend(query_params.iter().map(|(k, v)| (*k, *v)));
+        self.execute(ApiRequest::new(
+            Access::Write,
+            Method::POST,
+            endpoint,
+            query_params,
+            ApiBody::Json(json_body.to_string()),
+        ))
+        .await
+    }
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&all_params)
-            .header("Content-Type", "application/json")
-            .body(json_body.to_string())
-            .send()
-            .await?;
+    /// POST с JSON телом, который только читает данные (например, OLAP-отчет)
+    ///
+    /// В отличие от `post_json`, повторяется по `RetryPolicy` как обычный GET.
+    pub(crate) async fn post_json_query(
+        &self,
+        endpoint: &str,
+        json_body: &str,
+        query_params: &[(&str, &str)],
+    ) -> Result<String> {
+        self.execute(ApiRequest::new(
+            Access::Read,
+            Method::POST,
+            endpoint,
+            query_params,
+            ApiBody::Json(json_body.to_string()),
+        ))
+        .await
+    }
 
-        let status = response.status();
-        // POST returns 200 for update, 201 for create
-        if status != 200 && status != 201 && !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
-        }
+    /// POST с JSON телом, который только читает данные, с чтением ответа по частям
+    pub(crate) async fn post_json_query_stream(
+        &self,
+        endpoint: &str,
+        json_body: &str,
+        query_params: &[(&str, &str)],
+    ) -> Result<ResponseStream> {
+        self.execute_stream(ApiRequest::new(
+            Access::Read,
+            Method::POST,
+            endpoint,
+            query_params,
+            ApiBody::Json(json_body.to_string()),
+        ))
+        .await
+    }
 
-        Ok(response.text().await?)
+    /// GET с чтением ответа по частям (см. модуль `streaming`)
+    ///
+    /// Очередь запросов клиента занята, пока поток не дочитан или не удален.
+    pub async fn get_stream(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> Result<ResponseStream> {
+        self.execute_stream(ApiRequest::new(
+            Access::Read,
+            Method::GET,
+            endpoint,
+            params,
+            ApiBody::Empty,
+        ))
+        .await
     }
 
     pub async fn get_with_params(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<String> {
-        // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
-
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
-
-        let mut query_params = vec![("key", session_id.as_str())];
-        query_params.extend(params.iter().map(|(k, v)| (*k, *v)));
-
-        let response = self
-            .http_client
-            .get(&url)
-            .query(&query_params)
-            .send()
-            .await?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
-        }
-
-        Ok(response.text().await?)
+        self.execute(ApiRequest::new(
+            Access::Read,
+            Method::GET,
+            endpoint,
+            params,
+            ApiBody::Empty,
+        ))
+        .await
     }
 
     pub async fn delete(&self, endpoint: &str) -> Result<String> {
-        // Последовательное выполнение запросов согласно требованиям iiko API
-        let _guard = self.request_mutex.lock().await;
-
-        let session_id = self.authenticate_internal().await?;
-        let url = format!("{}/{}", self.config.base_url, endpoint);
-
-        let response = self
-            .http_client
-            .delete(&url)
-            .query(&[("key", session_id.as_str())])
-            .send()
-            .await?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Self::handle_error_response(status, error_text));
-        }
-
-        Ok(response.text().await?)
+        self.execute(ApiRequest::new(
+            Access::Write,
+            Method::DELETE,
+            endpoint,
+            &[],
+            ApiBody::Empty,
+        ))
+        .await
     }
 
     pub fn config(&self) -> &IikoConfig {
@@ -334,7 +972,10 @@ impl IikoClient {
     }
 
     pub async fn invalidate_session(&self) {
-        let mut session = self.session_id.write().await;
-        *session = None;
+        let key = self.session_id.write().await.take();
+        if let Some(key) = key {
+            session::unregister(&key);
+            self.forget_stored_key(&key).await;
+        }
     }
 }
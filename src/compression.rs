@@ -0,0 +1,352 @@
+//! Сжатие ответов и тел запросов
+//!
+//! Клиент запрашивает ответы в gzip или deflate (`Accept-Encoding`) и распаковывает
+//! их до разбора, в том числе при потоковом чтении OLAP-отчетов и изображений.
+//! Списки номенклатуры и OLAP-отчеты сжимаются в 10–20 раз.
+//!
+//! Тела запросов сжимаются, только если это включено: iiko (Tomcat) по умолчанию
+//! не распаковывает запросы, это умеет обратный прокси перед ним. Если сервер ответил
+//! `415 Unsupported Media Type`, запрос повторяется без сжатия, и клиент больше
+//! не сжимает тела запросов.
+//!
+//! ```no_run
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::{Compression, IikoClient, IikoConfig};
+//!
+//! let config = IikoConfig::from_env()?
+//!     .with_compression(Compression::default().with_request_gzip(64 * 1024));
+//! let client = IikoClient::new(config)?;
+//! // ...
+//! let stats = client.compression_stats();
+//! println!("сэкономлено {} байт", stats.saved_bytes());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{IikoError, Result};
+use crate::transport::StreamingResponse;
+use bytes::Bytes;
+use flate2::write::{DeflateDecoder, GzDecoder, GzEncoder, ZlibDecoder};
+use futures_util::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Значение `Accept-Encoding` в запросах клиента
+pub(crate) const ACCEPT_ENCODING: &str = "gzip, deflate";
+
+/// Настройки сжатия
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Compression {
+    /// Запрашивать сжатые ответы (по умолчанию да)
+    #[serde(default = "default_responses")]
+    pub responses: bool,
+    /// Сжимать gzip тела запросов не меньше этого размера, байт. `None` — не сжимать
+    #[serde(default)]
+    pub request_gzip_min_bytes: Option<usize>,
+}
+
+fn default_responses() -> bool {
+    true
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            responses: true,
+            request_gzip_min_bytes: None,
+        }
+    }
+}
+
+impl Compression {
+    /// Без сжатия в обе стороны
+    pub fn none() -> Self {
+        Self {
+            responses: false,
+            request_gzip_min_bytes: None,
+        }
+    }
+
+    /// Сжимать тела запросов от `min_bytes` байт (сервер должен принимать
+    /// `Content-Encoding: gzip`)
+    pub fn with_request_gzip(mut self, min_bytes: usize) -> Self {
+        self.request_gzip_min_bytes = Some(min_bytes);
+        self
+    }
+}
+
+/// Сколько байт сэкономило сжатие
+///
+/// Возвращается из `IikoClient::compression_stats()`. Учитываются только сжатые ответы
+/// и запросы; статистика общая для всех клонов клиента.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionStats {
+    /// Ответов, пришедших в gzip или deflate
+    pub compressed_responses: u64,
+    /// Получено байт сжатых ответов
+    pub response_wire_bytes: u64,
+    /// Байт после распаковки
+    pub response_bytes: u64,
+    /// Запросов, отправленных в gzip
+    pub compressed_requests: u64,
+    /// Отправлено байт сжатых тел запросов
+    pub request_wire_bytes: u64,
+    /// Байт тех же тел до сжатия
+    pub request_bytes: u64,
+}
+
+impl CompressionStats {
+    /// Сэкономлено байт в обе стороны
+    pub fn saved_bytes(&self) -> u64 {
+        self.response_bytes.saturating_sub(self.response_wire_bytes)
+            + self.request_bytes.saturating_sub(self.request_wire_bytes)
+    }
+}
+
+/// Счетчики и состояние сжатия, общие для клонов клиента
+#[derive(Debug, Default)]
+pub(crate) struct CompressionState {
+    compressed_responses: AtomicU64,
+    response_wire_bytes: AtomicU64,
+    response_bytes: AtomicU64,
+    compressed_requests: AtomicU64,
+    request_wire_bytes: AtomicU64,
+    request_bytes: AtomicU64,
+    /// Сервер ответил 415 на сжатое тело
+    requests_rejected: AtomicBool,
+}
+
+impl CompressionState {
+    pub(crate) fn stats(&self) -> CompressionStats {
+        CompressionStats {
+            compressed_responses: self.compressed_responses.load(Ordering::Relaxed),
+            response_wire_bytes: self.response_wire_bytes.load(Ordering::Relaxed),
+            response_bytes: self.response_bytes.load(Ordering::Relaxed),
+            compressed_requests: self.compressed_requests.load(Ordering::Relaxed),
+            request_wire_bytes: self.request_wire_bytes.load(Ordering::Relaxed),
+            request_bytes: self.request_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Сжать тело запроса, если оно не меньше порога и сервер не отказывался от сжатия
+    pub(crate) fn compress_request(
+        &self,
+        config: &Compression,
+        body: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        match config.request_gzip_min_bytes {
+            Some(min_bytes)
+                if body.len() >= min_bytes && !self.requests_rejected.load(Ordering::Relaxed) =>
+            {
+                Ok(Some(gzip(body)?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Сервер не принимает сжатые запросы: больше не сжимать
+    pub(crate) fn reject_requests(&self) {
+        self.requests_rejected.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_request(&self, body_len: usize, wire_len: usize) {
+        self.compressed_requests.fetch_add(1, Ordering::Relaxed);
+        self.request_bytes
+            .fetch_add(body_len as u64, Ordering::Relaxed);
+        self.request_wire_bytes
+            .fetch_add(wire_len as u64, Ordering::Relaxed);
+    }
+}
+
+fn gzip(body: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(body)?;
+    Ok(encoder.finish()?)
+}
+
+/// Распаковать тело ответа с `Content-Encoding: gzip` или `deflate`
+///
+/// Тело распаковывается по мере чтения. Заголовки `Content-Encoding` и `Content-Length`
+/// убираются, остальные ответы возвращаются как есть.
+pub(crate) fn decode_response(
+    mut response: StreamingResponse,
+    state: Arc<CompressionState>,
+) -> StreamingResponse {
+    let Some(decoder) = response
+        .header("content-encoding")
+        .and_then(Decoder::for_encoding)
+    else {
+        return response;
+    };
+
+    response.headers.retain(|(name, _)| {
+        !name.eq_ignore_ascii_case("content-encoding")
+            && !name.eq_ignore_ascii_case("content-length")
+    });
+    state.compressed_responses.fetch_add(1, Ordering::Relaxed);
+
+    let body = std::mem::replace(&mut response.body, stream::empty().boxed());
+    response.body = stream::unfold(
+        (body, Some(decoder), state),
+        |(mut body, mut decoder, state)| async move {
+            loop {
+                let active = decoder.as_mut()?;
+                let decoded = match body.next().await {
+                    Some(Ok(chunk)) => {
+                        state
+                            .response_wire_bytes
+                            .fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                        active.push(&chunk)
+                    }
+                    Some(Err(error)) => return Some((Err(error), (body, None, state))),
+                    None => decoder.take()?.finish(),
+                };
+
+                match decoded {
+                    Ok(decoded) if decoded.is_empty() => continue,
+                    Ok(decoded) => {
+                        state
+                            .response_bytes
+                            .fetch_add(decoded.len() as u64, Ordering::Relaxed);
+                        return Some((Ok(Bytes::from(decoded)), (body, decoder, state)));
+                    }
+                    Err(error) => {
+                        let error = IikoError::Io(std::io::Error::new(
+                            error.kind(),
+                            format!("Failed to decompress response: {}", error),
+                        ));
+                        return Some((Err(error), (body, None, state)));
+                    }
+                }
+            }
+        },
+    )
+    .boxed();
+    response
+}
+
+/// Распаковать тело целиком (для записи сжатых запросов в кассету)
+pub(crate) fn decode_bytes(encoding: &str, body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoder = Decoder::for_encoding(encoding)?;
+    let mut decoded = decoder.push(body).ok()?;
+    decoded.extend(decoder.finish().ok()?);
+    Some(decoded)
+}
+
+/// Потоковый распаковщик: байты подаются кусками, распакованное забирается после каждого
+enum Decoder {
+    Gzip(GzDecoder<Vec<u8>>),
+    /// `deflate` по RFC 9110 — zlib, но некоторые серверы шлют сырой deflate.
+    /// Формат определяется по первому байту
+    Deflate(Option<DeflateFormat>),
+}
+
+enum DeflateFormat {
+    Zlib(ZlibDecoder<Vec<u8>>),
+    Raw(DeflateDecoder<Vec<u8>>),
+}
+
+impl Decoder {
+    fn for_encoding(encoding: &str) -> Option<Self> {
+        match encoding.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Decoder::Gzip(GzDecoder::new(Vec::new()))),
+            "deflate" => Some(Decoder::Deflate(None)),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Decoder::Gzip(decoder) => {
+                decoder.write_all(chunk)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            Decoder::Deflate(format) => {
+                let Some(&first) = chunk.first() else {
+                    return Ok(Vec::new());
+                };
+                // Заголовок zlib: метод 8 (deflate), окно не больше 32 КБ
+                let format = format.get_or_insert_with(|| {
+                    if first & 0x0f == 8 && first >> 4 <= 7 {
+                        DeflateFormat::Zlib(ZlibDecoder::new(Vec::new()))
+                    } else {
+                        DeflateFormat::Raw(DeflateDecoder::new(Vec::new()))
+                    }
+                });
+                match format {
+                    DeflateFormat::Zlib(decoder) => {
+                        decoder.write_all(chunk)?;
+                        Ok(std::mem::take(decoder.get_mut()))
+                    }
+                    DeflateFormat::Raw(decoder) => {
+                        decoder.write_all(chunk)?;
+                        Ok(std::mem::take(decoder.get_mut()))
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Decoder::Gzip(decoder) => decoder.finish(),
+            Decoder::Deflate(None) => Ok(Vec::new()),
+            Decoder::Deflate(Some(DeflateFormat::Zlib(decoder))) => decoder.finish(),
+            Decoder::Deflate(Some(DeflateFormat::Raw(decoder))) => decoder.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{StatusCode, TransportResponse};
+    use flate2::write::{DeflateEncoder, ZlibEncoder};
+
+    async fn decode(encoding: &str, body: Vec<u8>, state: &Arc<CompressionState>) -> String {
+        let mut response = TransportResponse::new(StatusCode::OK, body);
+        response
+            .headers
+            .push(("Content-Encoding".to_string(), encoding.to_string()));
+        let response = decode_response(StreamingResponse::from(response), state.clone());
+        assert_eq!(response.header("content-encoding"), None);
+        response.into_response().await.unwrap().text()
+    }
+
+    #[tokio::test]
+    async fn decodes_gzip_zlib_and_raw_deflate() {
+        let text = "<product><name>Капучино</name></product>".repeat(100);
+        let state = Arc::new(CompressionState::default());
+
+        let gzipped = gzip(text.as_bytes()).unwrap();
+        let wire = gzipped.len() as u64;
+        assert_eq!(decode("gzip", gzipped, &state).await, text);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        zlib.write_all(text.as_bytes()).unwrap();
+        assert_eq!(
+            decode("deflate", zlib.finish().unwrap(), &state).await,
+            text
+        );
+
+        let mut raw = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        raw.write_all(text.as_bytes()).unwrap();
+        assert_eq!(decode("deflate", raw.finish().unwrap(), &state).await, text);
+
+        let stats = state.stats();
+        assert_eq!(stats.compressed_responses, 3);
+        assert_eq!(stats.response_bytes, 3 * text.len() as u64);
+        assert!(stats.response_wire_bytes > wire);
+        assert!(stats.saved_bytes() > 2 * text.len() as u64);
+
+        // Неизвестная кодировка не трогается
+        let mut response = TransportResponse::new(StatusCode::OK, "br-bytes");
+        response
+            .headers
+            .push(("Content-Encoding".to_string(), "br".to_string()));
+        let response = decode_response(StreamingResponse::from(response), state.clone());
+        assert_eq!(response.header("content-encoding"), Some("br"));
+    }
+}
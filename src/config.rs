@@ -1,11 +1,139 @@
-use serde::{Deserialize, Serialize};
+use crate::compression::Compression;
+use crate::retry::RetryPolicy;
+use crate::write_mode::WriteMode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Пароль пользователя iiko
+///
+/// `/auth` принимает SHA-1 хеш пароля в hex. `Plain` хешируется при авторизации,
+/// `Sha1Hex` передается как есть. `Debug` не выводит пароль.
+///
+/// Строка (`"..."` или `String`) преобразуется в `Sha1Hex`, как и раньше в `IikoConfig::new`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// Пароль в открытом виде
+    Plain(String),
+    /// SHA-1 хеш пароля в hex (как в `IIKO_HASHED_PASSWORD`)
+    Sha1Hex(String),
+}
+
+impl Credentials {
+    pub fn plain(password: impl Into<String>) -> Self {
+        Credentials::Plain(password.into())
+    }
+
+    pub fn sha1_hex(hash: impl Into<String>) -> Self {
+        Credentials::Sha1Hex(hash.into())
+    }
+
+    /// Значение параметра `pass` для `/auth`
+    ///
+    /// Хеш из `Sha1Hex` передается как есть, без нормализации.
+    pub fn password_hash(&self) -> String {
+        match self {
+            Credentials::Plain(password) => sha1_smol::Sha1::from(password).digest().to_string(),
+            Credentials::Sha1Hex(hash) => hash.clone(),
+        }
+    }
+}
+
+impl Default for Credentials {
+    fn default() -> Self {
+        Credentials::Sha1Hex(String::new())
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Plain(_) => f.write_str("Plain(***)"),
+            Credentials::Sha1Hex(_) => f.write_str("Sha1Hex(***)"),
+        }
+    }
+}
+
+impl From<String> for Credentials {
+    fn from(hash: String) -> Self {
+        Credentials::Sha1Hex(hash)
+    }
+}
+
+impl From<&str> for Credentials {
+    fn from(hash: &str) -> Self {
+        Credentials::Sha1Hex(hash.to_string())
+    }
+}
+
+/// В сериализованной конфигурации хранится только хеш: открытый пароль не записывается
+impl Serialize for Credentials {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.password_hash())
+    }
+}
+
+/// Принимает строку с хешем (прежний формат поля `password`) или `{"plain": "..."}` / `{"sha1Hex": "..."}`
+impl<'de> Deserialize<'de> for Credentials {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        enum Tagged {
+            #[serde(rename = "plain")]
+            Plain(String),
+            #[serde(rename = "sha1Hex")]
+            Sha1Hex(String),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Hash(String),
+            Tagged(Tagged),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Hash(hash) | Repr::Tagged(Tagged::Sha1Hex(hash)) => Credentials::Sha1Hex(hash),
+            Repr::Tagged(Tagged::Plain(password)) => Credentials::Plain(password),
+        })
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IikoConfig {
     pub base_url: String,
     pub login: String,
-    pub password: String,
+    #[serde(rename = "password")]
+    pub credentials: Credentials,
     pub timeout_secs: u64,
+    /// Таймаут `/auth` и `/logout`, сек. 0 — как у остальных запросов (`timeout_secs`)
+    #[serde(default = "default_auth_timeout_secs")]
+    pub auth_timeout_secs: u64,
+    /// Повторно авторизоваться и повторить запрос, если iiko отклонил ключ сессии
+    #[serde(default = "default_auto_reauth")]
+    pub auto_reauth: bool,
+    /// Политика повторов для временных ошибок (сеть, 500/502/503/504)
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Сверять версию сервера с требованиями методов (см. модуль `server_info`)
+    #[serde(default = "default_check_server_version")]
+    pub check_server_version: bool,
+    /// Что делать с изменяющими запросами (см. модуль `write_mode`)
+    #[serde(default)]
+    pub write_mode: WriteMode,
+    /// Сжатие ответов и тел запросов (см. модуль `compression`)
+    #[serde(default)]
+    pub compression: Compression,
+}
+
+fn default_auto_reauth() -> bool {
+    true
+}
+
+fn default_check_server_version() -> bool {
+    true
+}
+
+fn default_auth_timeout_secs() -> u64 {
+    30
 }
 
 impl Default for IikoConfig {
@@ -13,25 +141,43 @@ impl Default for IikoConfig {
         Self {
             base_url: "http://localhost:8080/resto/api".to_string(),
             login: String::new(),
-            password: String::new(),
+            credentials: Credentials::default(),
             // 0 означает отсутствие таймаута на уровне HTTP-клиента
             timeout_secs: 0,
+            auth_timeout_secs: default_auth_timeout_secs(),
+            auto_reauth: true,
+            retry: RetryPolicy::default(),
+            check_server_version: true,
+            write_mode: WriteMode::Normal,
+            compression: Compression::default(),
         }
     }
 }
 
 impl IikoConfig {
+    /// Создать конфигурацию
+    ///
+    /// # Параметры
+    /// - `password`: строка считается SHA-1 хешем пароля и передается как есть, поэтому
+    ///   прежний вызов `IikoConfig::new(url, login, password_hash)` работает без изменений;
+    ///   для пароля в открытом виде передайте `Credentials::plain("...")`
     pub fn new(
         base_url: impl Into<String>,
         login: impl Into<String>,
-        password: impl Into<String>,
+        password: impl Into<Credentials>,
     ) -> Self {
         Self {
             base_url: base_url.into(),
             login: login.into(),
-            password: password.into(),
+            credentials: password.into(),
             // 0 означает отсутствие таймаута на уровне HTTP-клиента
             timeout_secs: 0,
+            auth_timeout_secs: default_auth_timeout_secs(),
+            auto_reauth: true,
+            retry: RetryPolicy::default(),
+            check_server_version: true,
+            write_mode: WriteMode::Normal,
+            compression: Compression::default(),
         }
     }
 
@@ -40,12 +186,155 @@ impl IikoConfig {
         self
     }
 
+    /// Таймаут `/auth` и `/logout` (по умолчанию 30 секунд). 0 — как у остальных запросов
+    ///
+    /// Авторизация отвечает быстро, поэтому ее таймаут не зависит от `timeout_secs`,
+    /// который для OLAP-отчетов приходится делать большим или отключать.
+    pub fn with_auth_timeout(mut self, auth_timeout_secs: u64) -> Self {
+        self.auth_timeout_secs = auth_timeout_secs;
+        self
+    }
+
+    /// Таймаут `/auth` и `/logout`, если он задан отдельно
+    pub(crate) fn auth_timeout(&self) -> Option<std::time::Duration> {
+        (self.auth_timeout_secs != 0)
+            .then(|| std::time::Duration::from_secs(self.auth_timeout_secs))
+    }
+
+    /// Включить или отключить прозрачную переавторизацию при истекшей сессии
+    ///
+    /// При `false` клиент возвращает `IikoError::SessionExpired`, и вызывающий код
+    /// сам решает, когда вызывать `invalidate_session()`.
+    pub fn with_auto_reauth(mut self, enabled: bool) -> Self {
+        self.auto_reauth = enabled;
+        self
+    }
+
+    /// Задать политику повторов. `RetryPolicy::none()` отключает повторы полностью
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Включить или отключить проверку версии сервера перед вызовом методов
+    ///
+    /// При `false` клиент не запрашивает `get_server_info.jsp` сам, отправляет все
+    /// параметры и не возвращает `IikoError::UnsupportedVersion`.
+    pub fn with_version_check(mut self, enabled: bool) -> Self {
+        self.check_server_version = enabled;
+        self
+    }
+
+    /// Отклонять изменяющие запросы (`WriteMode::ReadOnly`) или только записывать их
+    /// в журнал (`WriteMode::DryRun`). По умолчанию `WriteMode::Normal`
+    pub fn with_write_mode(mut self, write_mode: WriteMode) -> Self {
+        self.write_mode = write_mode;
+        self
+    }
+
+    /// Настроить сжатие. По умолчанию ответы запрашиваются сжатыми, запросы не сжимаются
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// SHA-1 хеш пароля, который уходит в `/auth` (прежнее поле `password`)
+    #[deprecated(note = "используйте поле `credentials` или `credentials.password_hash()`")]
+    pub fn password(&self) -> String {
+        self.credentials.password_hash()
+    }
+
+    /// Задать пароль (`Credentials::Plain`) или его хеш (`Credentials::Sha1Hex`)
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Загрузить конфигурацию из переменных окружения
+    ///
+    /// - `IIKO_BASE_URL`, `IIKO_LOGIN`
+    /// - `IIKO_HASHED_PASSWORD` (SHA-1 хеш) или `IIKO_PASSWORD` (пароль в открытом виде).
+    ///   Если заданы обе, используется `IIKO_HASHED_PASSWORD`
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
         let base_url = std::env::var("IIKO_BASE_URL").expect("IIKO_BASE_URL is not set");
         let login = std::env::var("IIKO_LOGIN").expect("IIKO_LOGIN is not set");
-        let password =
-            std::env::var("IIKO_HASHED_PASSWORD").expect("IIKO_HASHED_PASSWORD is not set");
+        let credentials = match std::env::var("IIKO_HASHED_PASSWORD") {
+            Ok(hash) => Credentials::Sha1Hex(hash),
+            Err(_) => Credentials::Plain(
+                std::env::var("IIKO_PASSWORD")
+                    .expect("IIKO_HASHED_PASSWORD or IIKO_PASSWORD is not set"),
+            ),
+        };
+
+        Ok(Self::new(base_url, login, credentials))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_password_is_hashed_with_sha1() {
+        assert_eq!(
+            Credentials::plain("admin").password_hash(),
+            "d033e22ae348aeb5660fc2140aec35850c4da997"
+        );
+    }
+
+    #[test]
+    fn sha1_hex_is_passed_through_unchanged() {
+        assert_eq!(
+            Credentials::sha1_hex("D033E22AE348AEB5660FC2140AEC35850C4DA997").password_hash(),
+            "D033E22AE348AEB5660FC2140AEC35850C4DA997"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn hash_string_keeps_working_as_before() {
+        let config = IikoConfig::new(
+            "http://localhost/resto/api",
+            "admin",
+            "d033e22ae348aeb5660fc2140aec35850c4da997",
+        );
+
+        assert_eq!(
+            config.password(),
+            "d033e22ae348aeb5660fc2140aec35850c4da997"
+        );
+    }
+
+    #[test]
+    fn debug_does_not_print_password() {
+        let config = IikoConfig::new(
+            "http://localhost/resto/api",
+            "admin",
+            Credentials::plain("secret"),
+        );
+        let debug = format!("{:?}", config);
+
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("Plain(***)"));
+    }
+
+    #[test]
+    fn serialized_config_keeps_password_field() {
+        let config = IikoConfig::new(
+            "http://localhost/resto/api",
+            "admin",
+            Credentials::plain("admin"),
+        );
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["password"], "d033e22ae348aeb5660fc2140aec35850c4da997");
+
+        let restored: IikoConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            restored.credentials,
+            Credentials::sha1_hex("d033e22ae348aeb5660fc2140aec35850c4da997")
+        );
 
-        Ok(Self::new(base_url, login, password))
+        let plain: Credentials = serde_json::from_str(r#"{"plain":"admin"}"#).unwrap();
+        assert_eq!(plain, Credentials::plain("admin"));
     }
 }
@@ -0,0 +1,326 @@
+//! Даты в параметрах запросов
+//!
+//! Endpoints iiko ждут даты в разных форматах: `dd.MM.yyyy` в старых отчетах
+//! (`reports/sales`, `reports/storeOperations`), `yyyy-MM-dd` в v2 и техкартах,
+//! `yyyy-MM-ddTHH:mm:ss.SSS` в событиях. Методы endpoints принимают [`DateParam`]:
+//!
+//! - строку, которая передается серверу как есть (формат — в документации метода)
+//! - с фичей `chrono` — `chrono::NaiveDate` или `chrono::NaiveDateTime`, которые метод
+//!   форматирует сам
+//!
+//! ```no_run
+//! # #[cfg(feature = "chrono")]
+//! # async fn example(
+//! #     client: iiko_server_api_sdk::IikoClient,
+//! #     department: iiko_server_api_sdk::DepartmentId,
+//! # ) -> iiko_server_api_sdk::Result<()> {
+//! use chrono::NaiveDate;
+//! use iiko_server_api_sdk::{InternalTransfersQuery, SalesQuery};
+//!
+//! let from = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+//! let to = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+//!
+//! // dateFrom=01.03.2024
+//! client.reports().sales(SalesQuery::new(department, from, to)).await?;
+//! // dateFrom=2024-03-01
+//! client
+//!     .documents()
+//!     .internal_transfers(InternalTransfersQuery::between(from, to))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Важно
+//! - Дата без времени в параметре с временем передается как начало дня (`00:00:00.000`)
+//! - У даты-времени в параметре без времени время отбрасывается
+//! - Необязательные параметры имеют тип `Option<DateParam>`: `None` или `Some(date.into())`.
+//!   В объектах запросов (`AssemblyChartsQuery::date_to`) дата передается напрямую
+//! - Поля дат в DTO имеют тип [`IikoDate`] / [`IikoDateTime`]: без фичи `chrono` это
+//!   строка из ответа как есть, с фичей — `NaiveDate` / `NaiveDateTime`. Неразборчивая
+//!   дата в ответе тогда — ошибка десериализации, а не строка
+
+#[cfg(feature = "chrono")]
+use crate::error::{IikoError, Result};
+#[cfg(feature = "chrono")]
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Deserializer, Serializer, de};
+
+/// Дата в параметре метода: строка или (с фичей `chrono`) типизированное значение
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateParam(Repr);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Repr {
+    Text(String),
+    #[cfg(feature = "chrono")]
+    Date(NaiveDate),
+    #[cfg(feature = "chrono")]
+    DateTime(NaiveDateTime),
+}
+
+impl From<&str> for DateParam {
+    fn from(text: &str) -> Self {
+        Self(Repr::Text(text.to_string()))
+    }
+}
+
+impl From<String> for DateParam {
+    fn from(text: String) -> Self {
+        Self(Repr::Text(text))
+    }
+}
+
+impl From<&String> for DateParam {
+    fn from(text: &String) -> Self {
+        Self(Repr::Text(text.clone()))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveDate> for DateParam {
+    fn from(date: NaiveDate) -> Self {
+        Self(Repr::Date(date))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveDateTime> for DateParam {
+    fn from(date_time: NaiveDateTime) -> Self {
+        Self(Repr::DateTime(date_time))
+    }
+}
+
+/// Формат даты, который ждет конкретный endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DateFormat {
+    /// `dd.MM.yyyy`
+    Day,
+    /// `yyyy-MM-dd`
+    Iso,
+    /// `yyyy-MM-ddTHH:mm:ss`
+    IsoDateTime,
+    /// `yyyy-MM-ddTHH:mm:ss.SSS`
+    IsoDateTimeMillis,
+}
+
+impl DateFormat {
+    /// Значение query-параметра. Строки передаются без изменений
+    pub(crate) fn format(self, date: impl Into<DateParam>) -> String {
+        match date.into().0 {
+            Repr::Text(text) => text,
+            #[cfg(feature = "chrono")]
+            Repr::Date(date) => self.format_chrono(date.and_time(NaiveTime::MIN)),
+            #[cfg(feature = "chrono")]
+            Repr::DateTime(date_time) => self.format_chrono(date_time),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    fn format_chrono(self, date_time: NaiveDateTime) -> String {
+        let pattern = match self {
+            DateFormat::Day => "%d.%m.%Y",
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::IsoDateTime => "%Y-%m-%dT%H:%M:%S",
+            DateFormat::IsoDateTimeMillis => "%Y-%m-%dT%H:%M:%S%.3f",
+        };
+        date_time.format(pattern).to_string()
+    }
+}
+
+/// Разобрать дату-время из ответа iiko
+///
+/// Принимает `dd.MM.yyyy`, `yyyy-MM-dd`, `yyyy-MM-ddTHH:mm:ss` с долями секунды или без
+/// и с часовым поясом (`+03:00`, `Z`) или без. Часовой пояс отбрасывается: iiko пишет
+/// время сервера, как и в параметрах запросов. Дата без времени — начало дня.
+#[cfg(feature = "chrono")]
+pub fn parse_date_time(text: &str) -> Result<NaiveDateTime> {
+    let text = text.trim();
+    let local = strip_offset(text);
+
+    NaiveDateTime::parse_from_str(local, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(local, "%d.%m.%Y %H:%M:%S"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(local, "%Y-%m-%d")
+                .or_else(|_| NaiveDate::parse_from_str(local, "%d.%m.%Y"))
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_err(|_| IikoError::InvalidDate(text.to_string()))
+}
+
+/// Разобрать дату из ответа iiko (время, если есть, отбрасывается)
+#[cfg(feature = "chrono")]
+pub fn parse_date(text: &str) -> Result<NaiveDate> {
+    parse_date_time(text).map(|date_time| date_time.date())
+}
+
+/// Дата-время в DTO: строка из ответа iiko или, с фичей `chrono`, `chrono::NaiveDateTime`
+#[cfg(not(feature = "chrono"))]
+pub type IikoDateTime = String;
+
+/// Дата-время в DTO: строка из ответа iiko или, с фичей `chrono`, `chrono::NaiveDateTime`
+#[cfg(feature = "chrono")]
+pub type IikoDateTime = NaiveDateTime;
+
+/// Дата (учетный день) в DTO: строка из ответа iiko или, с фичей `chrono`, `chrono::NaiveDate`
+#[cfg(not(feature = "chrono"))]
+pub type IikoDate = String;
+
+/// Дата (учетный день) в DTO: строка из ответа iiko или, с фичей `chrono`, `chrono::NaiveDate`
+#[cfg(feature = "chrono")]
+pub type IikoDate = NaiveDate;
+
+/// Тип поля даты в DTO ([`IikoDate`] или [`IikoDateTime`])
+pub(crate) trait DtoDate: Sized + Clone + Into<DateParam> {
+    fn from_text(text: String) -> std::result::Result<Self, String>;
+}
+
+impl DtoDate for String {
+    fn from_text(text: String) -> std::result::Result<Self, String> {
+        Ok(text)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DtoDate for NaiveDate {
+    fn from_text(text: String) -> std::result::Result<Self, String> {
+        parse_date(&text).map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DtoDate for NaiveDateTime {
+    fn from_text(text: String) -> std::result::Result<Self, String> {
+        parse_date_time(&text).map_err(|error| error.to_string())
+    }
+}
+
+/// Разобрать дату DTO из текста ответа (см. [`parse_date_time`] для форматов)
+pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DtoDate,
+{
+    T::from_text(String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Как [`deserialize`], пустой элемент — `None`
+pub(crate) fn deserialize_option<'de, D, T>(
+    deserializer: D,
+) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DtoDate,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) if !text.trim().is_empty() => {
+            T::from_text(text).map(Some).map_err(de::Error::custom)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Сериализаторы дат DTO в формате, который ждет endpoint импорта.
+/// Строки записываются как есть
+macro_rules! date_serializers {
+    ($($format:ident => $value:ident, $option:ident;)*) => {
+        $(
+            pub(crate) fn $value<T, S>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+            where
+                T: DtoDate,
+                S: Serializer,
+            {
+                serializer.serialize_str(&DateFormat::$format.format(value.clone()))
+            }
+
+            pub(crate) fn $option<T, S>(
+                value: &Option<T>,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error>
+            where
+                T: DtoDate,
+                S: Serializer,
+            {
+                match value {
+                    Some(value) => $value(value, serializer),
+                    None => serializer.serialize_none(),
+                }
+            }
+        )*
+    };
+}
+
+date_serializers! {
+    Day => serialize_day, serialize_day_option;
+    Iso => serialize_iso, serialize_iso_option;
+    IsoDateTime => serialize_iso_date_time, serialize_iso_date_time_option;
+    IsoDateTimeMillis => serialize_iso_date_time_millis, serialize_iso_date_time_millis_option;
+}
+
+/// `2024-03-01T10:00:00+03:00` -> `2024-03-01T10:00:00`
+#[cfg(feature = "chrono")]
+fn strip_offset(text: &str) -> &str {
+    if let Some(local) = text.strip_suffix('Z') {
+        return local;
+    }
+    // Знак часового пояса стоит после времени, дефисы даты — раньше
+    match text.rfind(['+', '-']) {
+        Some(sign) if text[..sign].contains(':') => &text[..sign],
+        _ => text,
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    fn moment() -> NaiveDateTime {
+        day().and_hms_milli_opt(9, 15, 2, 417).unwrap()
+    }
+
+    #[test]
+    fn formats_dates_per_endpoint() {
+        assert_eq!(DateFormat::Day.format(day()), "01.03.2024");
+        assert_eq!(DateFormat::Iso.format(moment()), "2024-03-01");
+        assert_eq!(
+            DateFormat::IsoDateTimeMillis.format(day()),
+            "2024-03-01T00:00:00.000"
+        );
+        assert_eq!(
+            DateFormat::IsoDateTimeMillis.format(moment()),
+            "2024-03-01T09:15:02.417"
+        );
+    }
+
+    #[test]
+    fn passes_text_dates_through() {
+        assert_eq!(DateFormat::Day.format("2024-03-01"), "2024-03-01");
+    }
+
+    #[test]
+    fn parses_server_dates_with_and_without_offset() {
+        assert_eq!(
+            parse_date_time("2024-03-01T09:15:02.417").unwrap(),
+            moment()
+        );
+        assert_eq!(
+            parse_date_time("2024-03-01T09:15:02.417+03:00").unwrap(),
+            moment()
+        );
+        assert_eq!(parse_date("01.03.2024").unwrap(), day());
+        assert_eq!(parse_date("2024-03-01T00:00:00Z").unwrap(), day());
+    }
+
+    #[test]
+    fn rejects_unparseable_dates() {
+        assert!(matches!(
+            parse_date("вчера"),
+            Err(IikoError::InvalidDate(text)) if text == "вчера"
+        ));
+    }
+}
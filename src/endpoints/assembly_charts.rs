@@ -1,5 +1,8 @@
 use crate::client::IikoClient;
+use crate::date::{DateFormat, DateParam};
 use crate::error::Result;
+use crate::ids::{DepartmentId, ProductId};
+use crate::query::{QueryParams, query_setters};
 use crate::xml::response::assembly_charts::{
     AssemblyChartDto, AssemblyChartOperationResult, ChartResultDto,
 };
@@ -24,43 +27,20 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     /// - Если `dateTo` не задан, возвращаются все будущие техкарты
     ///
     /// # Параметры
-    /// - `date_from`: Учетный день, начиная с которого требуются техкарты (формат: yyyy-MM-dd, обязательный)
-    /// - `date_to`: Учетный день, начиная с которого техкарты не требуются (формат: yyyy-MM-dd, необязательный)
-    /// - `include_deleted_products`: Включать ли в результат техкарты для удаленных блюд (по умолчанию true)
-    /// - `include_prepared_charts`: Включать ли в результат техкарты, разложенные до конечных ингредиентов (по умолчанию false)
+    /// - `query`: период и флаги выборки (см. [`AssemblyChartsQuery`])
     ///
     /// # Примечание:
-    /// Метод списания влияет на результат при `include_prepared_charts = true`:
+    /// Метод списания влияет на результат при `include_prepared_charts(true)`:
     /// - "Списывать готовое блюдо" (DIRECT) - в preparedCharts возвращается элемент списания
     /// - "Списывать ингредиенты" (ASSEMBLE) - в preparedCharts возвращаются ингредиенты элемента списания
-    pub async fn get_all(
-        &self,
-        date_from: String,
-        date_to: Option<String>,
-        include_deleted_products: Option<bool>,
-        include_prepared_charts: Option<bool>,
-    ) -> Result<ChartResultDto> {
-        let mut params: Vec<(&str, &str)> = Vec::new();
-        params.push(("dateFrom", date_from.as_str()));
-        if let Some(ref dt) = date_to {
-            params.push(("dateTo", dt.as_str()));
-        }
-        if let Some(inc_del) = include_deleted_products {
-            params.push((
-                "includeDeletedProducts",
-                if inc_del { "true" } else { "false" },
-            ));
-        }
-        if let Some(inc_prep) = include_prepared_charts {
-            params.push((
-                "includePreparedCharts",
-                if inc_prep { "true" } else { "false" },
-            ));
-        }
-
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.get_all", level = "debug", skip_all)
+    )]
+    pub async fn all(&self, query: AssemblyChartsQuery) -> Result<ChartResultDto> {
         let response_json = self
             .client
-            .get_with_params("v2/assemblyCharts/getAll", &params)
+            .get_with_params("v2/assemblyCharts/getAll", &query.params().pairs())
             .await?;
 
         let result: ChartResultDto = serde_json::from_str(&response_json)?;
@@ -71,57 +51,83 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     ///
     /// # Важно:
     /// - По состоянию на 6.0 получение обновлений полностью работает только в iikoChain
-    /// - Получение обновлений не поддерживается для `include_deleted_products = false`
+    /// - Получение обновлений не поддерживается для `include_deleted_products(false)`
     /// - Используйте `known_revision` из предыдущего результата `getAll` или `getAllUpdate`
     ///
     /// # Параметры
     /// - `known_revision`: Значение поля knownRevision из предыдущего результата (обязательный)
-    /// - `date_from`: Учетный день, начиная с которого требуются техкарты (формат: yyyy-MM-dd, обязательный)
-    /// - `date_to`: Учетный день, начиная с которого техкарты не требуются (формат: yyyy-MM-dd, необязательный)
-    /// - `include_deleted_products`: Включать ли в результат техкарты для удаленных блюд (по умолчанию true)
-    /// - `include_prepared_charts`: Включать ли в результат техкарты, разложенные до конечных ингредиентов (по умолчанию false)
+    /// - `query`: период и флаги выборки (см. [`AssemblyChartsQuery`])
     ///
     /// # Примечание:
     /// - `deletedAssemblyChartIds` и `deletedPreparedChartIds` содержат UUID удаленных техкарт
     /// - Клиент должен забыть перечисленные техкарты и начать считать актуальными те, что действовали на даты, предшествовавшие удаленным
     /// - На iikoRMS не работает (не сообщает об удалениях, реплицированных с iikoChain)
-    pub async fn get_all_update(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.assembly_charts.get_all_update",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn all_update(
         &self,
         known_revision: i64,
-        date_from: String,
-        date_to: Option<String>,
-        include_deleted_products: Option<bool>,
-        include_prepared_charts: Option<bool>,
+        query: AssemblyChartsQuery,
     ) -> Result<ChartResultDto> {
-        let mut params: Vec<(&str, &str)> = Vec::new();
-        let rev_string = known_revision.to_string();
-        params.push(("knownRevision", rev_string.as_str()));
-        params.push(("dateFrom", date_from.as_str()));
-        if let Some(ref dt) = date_to {
-            params.push(("dateTo", dt.as_str()));
-        }
-        if let Some(inc_del) = include_deleted_products {
-            params.push((
-                "includeDeletedProducts",
-                if inc_del { "true" } else { "false" },
-            ));
-        }
-        if let Some(inc_prep) = include_prepared_charts {
-            params.push((
-                "includePreparedCharts",
-                if inc_prep { "true" } else { "false" },
-            ));
-        }
+        let mut params = QueryParams::new();
+        params
+            .push("knownRevision", known_revision.to_string())
+            .append(query.params());
 
         let response_json = self
             .client
-            .get_with_params("v2/assemblyCharts/getAllUpdate", &params)
+            .get_with_params("v2/assemblyCharts/getAllUpdate", &params.pairs())
             .await?;
 
         let result: ChartResultDto = serde_json::from_str(&response_json)?;
         Ok(result)
     }
 
+    /// Получение всех технологических карт с позиционными параметрами
+    #[deprecated(note = "используйте `all(AssemblyChartsQuery::new(date_from)...)`")]
+    pub async fn get_all(
+        &self,
+        date_from: impl Into<DateParam>,
+        date_to: Option<DateParam>,
+        include_deleted_products: Option<bool>,
+        include_prepared_charts: Option<bool>,
+    ) -> Result<ChartResultDto> {
+        self.all(AssemblyChartsQuery {
+            date_from: date_from.into(),
+            date_to,
+            include_deleted_products,
+            include_prepared_charts,
+        })
+        .await
+    }
+
+    /// Получение обновления технологических карт с позиционными параметрами
+    #[deprecated(
+        note = "используйте `all_update(known_revision, AssemblyChartsQuery::new(date_from)...)`"
+    )]
+    pub async fn get_all_update(
+        &self,
+        known_revision: i64,
+        date_from: impl Into<DateParam>,
+        date_to: Option<DateParam>,
+        include_deleted_products: Option<bool>,
+        include_prepared_charts: Option<bool>,
+    ) -> Result<ChartResultDto> {
+        let query = AssemblyChartsQuery {
+            date_from: date_from.into(),
+            date_to,
+            include_deleted_products,
+            include_prepared_charts,
+        };
+        self.all_update(known_revision, query).await
+    }
+
     /// Получение дерева актуальных технологических карт для элемента номенклатуры (getTree)
     ///
     /// # Важно:
@@ -136,15 +142,20 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     ///
     /// # Примечание:
     /// - `knownRevision` всегда -1, т.к. обновление дерева техкарт невозможно вычислить по одной ревизии
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.get_tree", level = "debug", skip_all)
+    )]
     pub async fn get_tree(
         &self,
-        date: String,
-        product_id: Uuid,
-        department_id: Option<Uuid>,
+        date: impl Into<DateParam>,
+        product_id: impl Into<ProductId>,
+        department_id: Option<DepartmentId>,
     ) -> Result<ChartResultDto> {
+        let date = DateFormat::Iso.format(date);
         let mut params: Vec<(&str, &str)> = Vec::new();
         params.push(("date", date.as_str()));
-        let product_id_str = product_id.to_string();
+        let product_id_str = product_id.into().to_string();
         params.push(("productId", product_id_str.as_str()));
         let dept_id_str = department_id.map(|id| id.to_string());
         if let Some(ref dept_id_str) = dept_id_str {
@@ -170,15 +181,24 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     /// - `date`: Учетный день (формат: yyyy-MM-dd, обязательный)
     /// - `product_id`: UUID элемента номенклатуры (блюда, модификатора, заготовки) (обязательный)
     /// - `department_id`: UUID подразделения (необязательный)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.assembly_charts.get_assembled",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_assembled(
         &self,
-        date: String,
-        product_id: Uuid,
-        department_id: Option<Uuid>,
+        date: impl Into<DateParam>,
+        product_id: impl Into<ProductId>,
+        department_id: Option<DepartmentId>,
     ) -> Result<ChartResultDto> {
+        let date = DateFormat::Iso.format(date);
         let mut params: Vec<(&str, &str)> = Vec::new();
         params.push(("date", date.as_str()));
-        let product_id_str = product_id.to_string();
+        let product_id_str = product_id.into().to_string();
         params.push(("productId", product_id_str.as_str()));
         let dept_id_str = department_id.map(|id| id.to_string());
         if let Some(ref dept_id_str) = dept_id_str {
@@ -204,15 +224,20 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     /// - `date`: Учетный день (формат: yyyy-MM-dd, обязательный)
     /// - `product_id`: UUID элемента номенклатуры (блюда, модификатора, заготовки) (обязательный)
     /// - `department_id`: UUID подразделения (необязательный)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.get_prepared", level = "debug", skip_all)
+    )]
     pub async fn get_prepared(
         &self,
-        date: String,
-        product_id: Uuid,
-        department_id: Option<Uuid>,
+        date: impl Into<DateParam>,
+        product_id: impl Into<ProductId>,
+        department_id: Option<DepartmentId>,
     ) -> Result<ChartResultDto> {
+        let date = DateFormat::Iso.format(date);
         let mut params: Vec<(&str, &str)> = Vec::new();
         params.push(("date", date.as_str()));
-        let product_id_str = product_id.to_string();
+        let product_id_str = product_id.into().to_string();
         params.push(("productId", product_id_str.as_str()));
         let dept_id_str = department_id.map(|id| id.to_string());
         if let Some(ref dept_id_str) = dept_id_str {
@@ -232,6 +257,10 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     ///
     /// # Параметры
     /// - `id`: UUID технологической карты (обязательный)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.by_id", level = "debug", skip_all)
+    )]
     pub async fn by_id(&self, id: Uuid) -> Result<AssemblyChartDto> {
         let id_str = id.to_string();
         let params = vec![("id", id_str.as_str())];
@@ -253,13 +282,17 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     /// # Параметры
     /// - `product_id`: UUID приготавливаемого элемента номенклатуры (обязательный)
     /// - `department_id`: UUID подразделения (необязательный)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.get_history", level = "debug", skip_all)
+    )]
     pub async fn get_history(
         &self,
-        product_id: Uuid,
-        department_id: Option<Uuid>,
+        product_id: impl Into<ProductId>,
+        department_id: Option<DepartmentId>,
     ) -> Result<Vec<AssemblyChartDto>> {
         let mut params: Vec<(&str, &str)> = Vec::new();
-        let product_id_str = product_id.to_string();
+        let product_id_str = product_id.into().to_string();
         params.push(("productId", product_id_str.as_str()));
         let dept_id_str = department_id.map(|id| id.to_string());
         if let Some(ref dept_id_str) = dept_id_str {
@@ -289,6 +322,10 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     /// # Ограничения:
     /// - Смешивать техкарты разных типов (COMMON/SPECIFIC) в иерархии одного элемента номенклатуры не рекомендуется
     /// - Если техкарта не содержит данных для "не своего" типа списания, результат умножения равен нулю
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.save", level = "debug", skip_all)
+    )]
     pub async fn save(&self, chart: AssemblyChartDto) -> Result<AssemblyChartOperationResult> {
         let json_body = json_to_string(&chart)?;
         let response_json = self
@@ -307,6 +344,10 @@ impl<'a> AssemblyChartsEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - UUID удаленной технологической карты
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.assembly_charts.delete", level = "debug", skip_all)
+    )]
     pub async fn delete(&self, id: Uuid) -> Result<AssemblyChartOperationResult> {
         #[derive(Serialize)]
         struct DeleteRequest {
@@ -324,3 +365,50 @@ impl<'a> AssemblyChartsEndpoint<'a> {
         Ok(result)
     }
 }
+
+/// Период и флаги выборки техкарт для [`AssemblyChartsEndpoint::all`] и
+/// [`AssemblyChartsEndpoint::all_update`]
+#[derive(Debug, Clone)]
+pub struct AssemblyChartsQuery {
+    date_from: DateParam,
+    date_to: Option<DateParam>,
+    include_deleted_products: Option<bool>,
+    include_prepared_charts: Option<bool>,
+}
+
+impl AssemblyChartsQuery {
+    /// Техкарты, действующие с учетного дня `date_from` (`yyyy-MM-dd`)
+    pub fn new(date_from: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: None,
+            include_deleted_products: None,
+            include_prepared_charts: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push("dateFrom", DateFormat::Iso.format(self.date_from.clone()))
+            .push_opt(
+                "dateTo",
+                self.date_to
+                    .clone()
+                    .map(|date_to| DateFormat::Iso.format(date_to)),
+            )
+            .push_opt("includeDeletedProducts", self.include_deleted_products)
+            .push_opt("includePreparedCharts", self.include_prepared_charts);
+        params
+    }
+}
+
+query_setters!(AssemblyChartsQuery {
+    /// Учетный день, начиная с которого техкарты не требуются (`yyyy-MM-dd`).
+    /// Если не задан, возвращаются все будущие техкарты
+    date_to: date,
+    /// Включать ли техкарты для удаленных блюд (по умолчанию true)
+    include_deleted_products: flag,
+    /// Включать ли техкарты, разложенные до конечных ингредиентов (по умолчанию false)
+    include_prepared_charts: flag,
+});
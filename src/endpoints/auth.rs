@@ -10,14 +10,26 @@ impl<'a> AuthEndpoint<'a> {
         Self { client }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.auth.login", level = "debug", skip_all)
+    )]
     pub async fn login(&self) -> Result<String> {
         self.client.authenticate().await
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.auth.logout", level = "debug", skip_all)
+    )]
     pub async fn logout(&self) -> Result<String> {
         self.client.logout().await
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.auth.invalidate_session", level = "debug", skip_all)
+    )]
     pub async fn invalidate_session(&self) {
         self.client.invalidate_session().await;
     }
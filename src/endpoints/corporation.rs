@@ -1,5 +1,6 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::ids::{DepartmentCode, DepartmentId, StoreCode};
 use crate::xml::response::{
     CorporateItemDto, CorporateItemDtoes, CorporationSettings, GroupDto, GroupDtoes, TerminalDto,
     TerminalDtoes,
@@ -15,6 +16,10 @@ impl<'a> CorporationEndpoint<'a> {
         Self { client }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.get_departments", level = "debug", skip_all)
+    )]
     pub async fn get_departments(
         &self,
         revision_from: Option<i64>,
@@ -29,6 +34,10 @@ impl<'a> CorporationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.get_stores", level = "debug", skip_all)
+    )]
     pub async fn get_stores(&self, revision_from: Option<i64>) -> Result<Vec<CorporateItemDto>> {
         let revision = revision_from.unwrap_or(-1).to_string();
         let response_xml = self
@@ -40,6 +49,10 @@ impl<'a> CorporationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.get_groups", level = "debug", skip_all)
+    )]
     pub async fn get_groups(&self, revision_from: Option<i64>) -> Result<Vec<GroupDto>> {
         let revision = revision_from.unwrap_or(-1).to_string();
         let response_xml = self
@@ -51,6 +64,10 @@ impl<'a> CorporationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.get_terminals", level = "debug", skip_all)
+    )]
     pub async fn get_terminals(&self, revision_from: Option<i64>) -> Result<Vec<TerminalDto>> {
         let revision = revision_from.unwrap_or(-1).to_string();
         let response_xml = self
@@ -62,37 +79,62 @@ impl<'a> CorporationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
-    pub async fn search_department(&self, code: &str) -> Result<Option<CorporateItemDto>> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.corporation.search_department",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn search_department(
+        &self,
+        code: impl Into<DepartmentCode>,
+    ) -> Result<Option<CorporateItemDto>> {
+        let code = code.into();
         let response_xml = self
             .client
-            .get_with_params("corporation/departments/search", &[("code", code)])
+            .get_with_params("corporation/departments/search", &[("code", code.as_str())])
             .await?;
 
         let wrapper: CorporateItemDtoes = from_str(&response_xml)?;
         Ok(wrapper.items.into_iter().next())
     }
 
-    pub async fn search_store(&self, code: &str) -> Result<Option<CorporateItemDto>> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.search_store", level = "debug", skip_all)
+    )]
+    pub async fn search_store(
+        &self,
+        code: impl Into<StoreCode>,
+    ) -> Result<Option<CorporateItemDto>> {
+        let code = code.into();
         let response_xml = self
             .client
-            .get_with_params("corporation/stores/search", &[("code", code)])
+            .get_with_params("corporation/stores/search", &[("code", code.as_str())])
             .await?;
 
         let wrapper: CorporateItemDtoes = from_str(&response_xml)?;
         Ok(wrapper.items.into_iter().next())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.search_groups", level = "debug", skip_all)
+    )]
     pub async fn search_groups(
         &self,
         name: Option<&str>,
-        department_id: Option<&str>,
+        department_id: Option<DepartmentId>,
     ) -> Result<Vec<GroupDto>> {
+        let department_id = department_id.map(|id| id.to_string());
         let mut params = Vec::new();
         if let Some(n) = name {
             params.push(("name", n));
         }
-        if let Some(d) = department_id {
-            params.push(("departmentId", d));
+        if let Some(d) = &department_id {
+            params.push(("departmentId", d.as_str()));
         }
 
         let response_xml = self
@@ -104,6 +146,10 @@ impl<'a> CorporationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.search_terminals", level = "debug", skip_all)
+    )]
     pub async fn search_terminals(
         &self,
         name: Option<&str>,
@@ -130,6 +176,10 @@ impl<'a> CorporationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.corporation.get_settings", level = "debug", skip_all)
+    )]
     pub async fn get_settings(&self) -> Result<CorporationSettings> {
         let response_json = self.client.get("v2/corporation/settings").await?;
         let settings: CorporationSettings = serde_json::from_str(&response_json)?;
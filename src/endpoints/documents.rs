@@ -1,10 +1,15 @@
 use crate::client::IikoClient;
+use crate::date::{DateFormat, DateParam};
 use crate::error::Result;
+use crate::ids::{StoreId, SupplierId};
+use crate::query::{QueryParams, query_setters};
+use crate::server_info::ServerVersion;
 use crate::xml::request::{DocumentsRequest, Request};
 use crate::xml::response::{
-    Document, DocumentValidationResult, IncomingInventoryDto, IncomingInventoryValidationResultDto,
-    IncomingInvoiceDto, InternalTransferDto, InternalTransferListResult,
-    InternalTransferOperationResult, OutgoingInvoiceDto, OutgoingInvoiceDtoes, ReturnedInvoiceDto,
+    Document, DocumentStatus, DocumentValidationResult, IncomingInventoryDto,
+    IncomingInventoryValidationResultDto, IncomingInvoiceDto, InternalTransferDto,
+    InternalTransferListResult, InternalTransferOperationResult, OutgoingInvoiceDto,
+    OutgoingInvoiceDtoes, ReturnedInvoiceDto,
 };
 use quick_xml::{de::from_str, se::to_string};
 use serde_json::to_string as json_to_string;
@@ -19,9 +24,13 @@ impl<'a> DocumentsEndpoint<'a> {
         Self { client }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.documents.get_documents", level = "debug", skip_all)
+    )]
     pub async fn get_documents(
         &self,
-        store_id: Option<String>,
+        store_id: Option<StoreId>,
         date_from: Option<String>,
         date_to: Option<String>,
     ) -> Result<Vec<Document>> {
@@ -32,7 +41,7 @@ impl<'a> DocumentsEndpoint<'a> {
         });
         let xml_body = request.to_xml()?;
 
-        let response_xml = self.client.post_xml("documents", &xml_body).await?;
+        let response_xml = self.client.post_xml_query("documents", &xml_body).await?;
         let documents: Vec<Document> = from_str(&response_xml)?;
 
         Ok(documents)
@@ -44,48 +53,77 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Endpoint: GET `/v2/documents/internalTransfer`
     ///
     /// # Параметры:
-    /// - `date_from`: начало интервала в формате `yyyy-MM-dd`, обязательный
-    /// - `date_to`: конец интервала в формате `yyyy-MM-dd`, обязательный
-    /// - `status`: опциональный статус документа (`NEW`, `PROCESSED`, `DELETED`)
-    /// - `revision_from`: опциональная ревизия для инкрементальной выгрузки, по умолчанию iiko использует `-1`
-    pub async fn list_internal_transfers(
+    /// - `query`: интервал дат `yyyy-MM-dd` и необязательные статус и ревизия
+    ///   (см. [`InternalTransfersQuery`])
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.list_internal_transfers",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn internal_transfers(
         &self,
-        date_from: impl AsRef<str>,
-        date_to: impl AsRef<str>,
-        status: Option<crate::xml::response::DocumentStatus>,
-        revision_from: Option<i64>,
+        query: InternalTransfersQuery,
     ) -> Result<InternalTransferListResult> {
-        let mut owned_params: Vec<(&str, String)> = vec![
-            ("dateFrom", date_from.as_ref().to_string()),
-            ("dateTo", date_to.as_ref().to_string()),
-        ];
-
-        if let Some(status) = status {
-            owned_params.push(("status", status.as_api_str().to_string()));
-        }
-
-        if let Some(revision_from) = revision_from {
-            owned_params.push(("revisionFrom", revision_from.to_string()));
-        }
+        self.client
+            .require_version(ServerVersion::new(7, 9, 3))
+            .await?;
 
-        let params: Vec<(&str, &str)> = owned_params
-            .iter()
-            .map(|(key, value)| (*key, value.as_str()))
-            .collect();
+        let mut params = QueryParams::new();
+        params
+            .push("dateFrom", DateFormat::Iso.format(query.date_from))
+            .push("dateTo", DateFormat::Iso.format(query.date_to))
+            .push_opt("status", query.status.map(|status| status.as_api_str()))
+            .push_opt("revisionFrom", query.revision_from);
 
         let response_json = self
             .client
-            .get_with_params("v2/documents/internalTransfer", &params)
+            .get_with_params("v2/documents/internalTransfer", &params.pairs())
             .await?;
 
         let result: InternalTransferListResult = serde_json::from_str(&response_json)?;
         Ok(result)
     }
 
+    /// Получить список внутренних перемещений с позиционными параметрами
+    #[deprecated(
+        note = "используйте `internal_transfers(InternalTransfersQuery::between(from, to)...)`"
+    )]
+    pub async fn list_internal_transfers(
+        &self,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
+        status: Option<DocumentStatus>,
+        revision_from: Option<i64>,
+    ) -> Result<InternalTransferListResult> {
+        self.internal_transfers(InternalTransfersQuery {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            status,
+            revision_from,
+        })
+        .await
+    }
+
     /// Получить внутреннее перемещение по UUID документа.
     ///
+    /// # Версия iiko: 7.9.3+
     /// # Endpoint: GET `/v2/documents/internalTransfer/byId`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.get_internal_transfer_by_id",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_internal_transfer_by_id(&self, id: Uuid) -> Result<InternalTransferDto> {
+        self.client
+            .require_version(ServerVersion::new(7, 9, 3))
+            .await?;
+
         let id = id.to_string();
         let response_json = self
             .client
@@ -98,11 +136,24 @@ impl<'a> DocumentsEndpoint<'a> {
 
     /// Получить внутренние перемещения по номеру документа.
     ///
+    /// # Версия iiko: 7.9.3+
     /// # Endpoint: GET `/v2/documents/internalTransfer/byNumber`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.get_internal_transfers_by_number",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_internal_transfers_by_number(
         &self,
         document_number: impl AsRef<str>,
     ) -> Result<Vec<InternalTransferDto>> {
+        self.client
+            .require_version(ServerVersion::new(7, 9, 3))
+            .await?;
+
         let response_json = self
             .client
             .get_with_params(
@@ -117,14 +168,27 @@ impl<'a> DocumentsEndpoint<'a> {
 
     /// Создать или отредактировать внутреннее перемещение.
     ///
+    /// # Версия iiko: 7.9.3+
     /// # Endpoint: POST `/v2/documents/internalTransfer`
     ///
     /// Если `transfer.id` задан, iiko считает запрос редактированием. По документации
     /// редактировать можно только документ в статусе `NEW`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.upsert_internal_transfer",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn upsert_internal_transfer(
         &self,
         transfer: InternalTransferDto,
     ) -> Result<InternalTransferOperationResult> {
+        self.client
+            .require_version(ServerVersion::new(7, 9, 3))
+            .await?;
+
         let json_body = json_to_string(&transfer)?;
         let response_json = self
             .client
@@ -146,7 +210,7 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Формат даты:
     /// - `dateIncoming`: dd.MM.yyyy
     /// - `dueDate`: dd.MM.yyyy
-    /// - `incomingDate`: yyyy-MM-dd (с версии 7.6.1)
+    /// - `incomingDate`: yyyy-MM-dd (с версии 7.6.1, более старому серверу не передается)
     ///
     /// # Что в ответе:
     /// - Результат валидации документа (DocumentValidationResult)
@@ -155,10 +219,23 @@ impl<'a> DocumentsEndpoint<'a> {
     /// - Content-Type: application/xml
     /// - В каждой позиции документа должно быть указано хотя бы одно из полей: `product` или `productArticle`
     /// - Если указан `defaultStore`, то в каждой позиции накладной нужно указать этот же склад
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.import_incoming_invoice",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn import_incoming_invoice(
         &self,
-        invoice: IncomingInvoiceDto,
+        mut invoice: IncomingInvoiceDto,
     ) -> Result<DocumentValidationResult> {
+        invoice.incoming_date = self
+            .client
+            .param_since(ServerVersion::new(7, 6, 1), invoice.incoming_date.take())
+            .await;
+
         // Сериализуем документ в XML
         let xml_body = to_string(&invoice)?;
 
@@ -192,6 +269,14 @@ impl<'a> DocumentsEndpoint<'a> {
     /// - Склад заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
     /// - Если заполнен в документе, в бекофисе будет отмечена галочка "Отгрузить со склада"
     /// - В каждой позиции документа должно быть указано хотя бы одно из полей: `productId` или `productArticle`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.import_outgoing_invoice",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn import_outgoing_invoice(
         &self,
         invoice: OutgoingInvoiceDto,
@@ -230,6 +315,14 @@ impl<'a> DocumentsEndpoint<'a> {
     /// - При создании накладных с проведением обязателен склад (defaultStoreId или defaultStoreCode)
     /// - Склад заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
     /// - В каждой позиции документа должно быть указано хотя бы одно из полей: `productId` или `productArticle`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.import_returned_invoice",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn import_returned_invoice(
         &self,
         invoice: ReturnedInvoiceDto,
@@ -265,10 +358,22 @@ impl<'a> DocumentsEndpoint<'a> {
     /// - Content-Type: application/xml
     /// - Склад (storeId или storeCode) - обязателен для заполнения
     /// - Для одного элемента номенклатуры можно передавать несколько строк, но статус у них должен быть одинаковым
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.import_incoming_inventory",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn import_incoming_inventory(
         &self,
         inventory: IncomingInventoryDto,
     ) -> Result<IncomingInventoryValidationResultDto> {
+        self.client
+            .require_version(ServerVersion::new(5, 1, 0))
+            .await?;
+
         // Сериализуем документ в XML
         let xml_body = to_string(&inventory)?;
 
@@ -296,10 +401,22 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Важно:
     /// - Content-Type: application/xml
     /// - Структура документа соответствует XSD Приходная накладная
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.unprocess_incoming_invoice",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn unprocess_incoming_invoice(
         &self,
         invoice: IncomingInvoiceDto,
     ) -> Result<DocumentValidationResult> {
+        self.client
+            .require_version(ServerVersion::new(7, 7, 0))
+            .await?;
+
         // Сериализуем документ в XML
         let xml_body = to_string(&invoice)?;
 
@@ -327,10 +444,22 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Важно:
     /// - Content-Type: application/xml
     /// - Структура документа соответствует XSD Расходная накладная
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.unprocess_outgoing_invoice",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn unprocess_outgoing_invoice(
         &self,
         invoice: OutgoingInvoiceDto,
     ) -> Result<DocumentValidationResult> {
+        self.client
+            .require_version(ServerVersion::new(7, 7, 0))
+            .await?;
+
         // Сериализуем документ в XML
         let xml_body = to_string(&invoice)?;
 
@@ -352,7 +481,7 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Параметры запроса:
     /// - `from`: Начальная дата в формате YYYY-MM-DD (входит в интервал)
     /// - `to`: Конечная дата в формате YYYY-MM-DD (входит в интервал, время не учитывается)
-    /// - `supplier_id`: Опциональный UUID поставщика
+    /// - `supplier_id`: Опциональный ID поставщика
     ///
     /// # Что в ответе:
     /// - Список расходных накладных (Vec<OutgoingInvoiceDto>)
@@ -360,12 +489,27 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Важно:
     /// - При запросе без поставщика возвращает все расходные накладные, попавшие в интервал
     /// - Формат даты: YYYY-MM-DD
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.export_outgoing_invoice",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn export_outgoing_invoice(
         &self,
-        from: String,
-        to: String,
-        supplier_id: Option<String>,
+        from: impl Into<DateParam>,
+        to: impl Into<DateParam>,
+        supplier_id: Option<SupplierId>,
     ) -> Result<Vec<OutgoingInvoiceDto>> {
+        self.client
+            .require_version(ServerVersion::new(5, 4, 0))
+            .await?;
+
+        let (from, to) = (DateFormat::Iso.format(from), DateFormat::Iso.format(to));
+        let supplier_id = supplier_id.map(|id| id.to_string());
+
         // Используем get_with_params для передачи параметров
         let response_xml = if let Some(ref id) = supplier_id {
             self.client
@@ -398,48 +542,137 @@ impl<'a> DocumentsEndpoint<'a> {
     /// # Endpoint: GET `/documents/export/outgoingInvoice/byNumber`
     ///
     /// # Параметры запроса:
-    /// - `number`: Номер документа (String)
-    /// - `current_year`: Только за текущий год (bool, обязательный)
-    /// - `from`: Начальная дата в формате YYYY-MM-DD (опционально, только если current_year = false)
-    /// - `to`: Конечная дата в формате YYYY-MM-DD (опционально, только если current_year = false)
+    /// - `query`: номер документа и период поиска (см. [`OutgoingInvoiceNumberQuery`]):
+    ///   только текущий год или интервал дат в формате YYYY-MM-DD
     ///
     /// # Что в ответе:
     /// - Список расходных накладных (Vec<OutgoingInvoiceDto>)
-    ///
-    /// # Важно:
-    /// - `current_year` - обязательный параметр
-    /// - При `current_year = true`: возвращает документы с указанным номером только за текущий год, параметры `from` и `to` должны отсутствовать
-    /// - При `current_year = false`: параметры `from` и `to` должны быть указаны
-    /// - Формат даты: YYYY-MM-DD
-    pub async fn export_outgoing_invoice_by_number(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.documents.export_outgoing_invoice_by_number",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn outgoing_invoices_by_number(
         &self,
-        number: String,
-        current_year: bool,
-        from: Option<String>,
-        to: Option<String>,
+        query: OutgoingInvoiceNumberQuery,
     ) -> Result<Vec<OutgoingInvoiceDto>> {
-        let mut params = vec![
-            ("number", number.as_str()),
-            ("currentYear", if current_year { "true" } else { "false" }),
-        ];
-
-        // Если current_year = false, добавляем from и to
-        if !current_year {
-            if let Some(ref from_date) = from {
-                params.push(("from", from_date.as_str()));
-            }
-            if let Some(ref to_date) = to {
-                params.push(("to", to_date.as_str()));
-            }
+        self.client
+            .require_version(ServerVersion::new(5, 4, 0))
+            .await?;
+
+        let mut params = QueryParams::new();
+        params
+            .push("number", query.number)
+            .push("currentYear", query.current_year.to_string());
+
+        // Период передается только при current_year = false
+        if !query.current_year {
+            params
+                .push_opt("from", query.from.map(|from| DateFormat::Iso.format(from)))
+                .push_opt("to", query.to.map(|to| DateFormat::Iso.format(to)));
         }
 
         let response_xml = self
             .client
-            .get_with_params("documents/export/outgoingInvoice/byNumber", &params)
+            .get_with_params("documents/export/outgoingInvoice/byNumber", &params.pairs())
             .await?;
 
         // Парсим XML ответ
         let result: OutgoingInvoiceDtoes = from_str(&response_xml)?;
         Ok(result.documents)
     }
+
+    /// Экспорт расходных накладных по номеру документа с позиционными параметрами
+    ///
+    /// # Важно:
+    /// - При `current_year = true` параметры `from` и `to` игнорируются
+    #[deprecated(
+        note = "используйте `outgoing_invoices_by_number(OutgoingInvoiceNumberQuery::current_year(number)...)`"
+    )]
+    pub async fn export_outgoing_invoice_by_number(
+        &self,
+        number: String,
+        current_year: bool,
+        from: Option<DateParam>,
+        to: Option<DateParam>,
+    ) -> Result<Vec<OutgoingInvoiceDto>> {
+        self.outgoing_invoices_by_number(OutgoingInvoiceNumberQuery {
+            number,
+            current_year,
+            from,
+            to,
+        })
+        .await
+    }
+}
+
+/// Интервал и фильтры внутренних перемещений для [`DocumentsEndpoint::internal_transfers`]
+#[derive(Debug, Clone)]
+pub struct InternalTransfersQuery {
+    date_from: DateParam,
+    date_to: DateParam,
+    status: Option<DocumentStatus>,
+    revision_from: Option<i64>,
+}
+
+impl InternalTransfersQuery {
+    /// Перемещения за интервал `yyyy-MM-dd` (обе границы обязательны)
+    pub fn between(date_from: impl Into<DateParam>, date_to: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            status: None,
+            revision_from: None,
+        }
+    }
+
+    /// Только документы в статусе (`NEW`, `PROCESSED`, `DELETED`)
+    pub fn status(mut self, status: DocumentStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+query_setters!(InternalTransfersQuery {
+    /// Ревизия для инкрементальной выгрузки (по умолчанию iiko использует `-1`)
+    revision_from: revision,
+});
+
+/// Номер и период поиска расходных накладных для
+/// [`DocumentsEndpoint::outgoing_invoices_by_number`]
+#[derive(Debug, Clone)]
+pub struct OutgoingInvoiceNumberQuery {
+    number: String,
+    current_year: bool,
+    from: Option<DateParam>,
+    to: Option<DateParam>,
+}
+
+impl OutgoingInvoiceNumberQuery {
+    /// Документы с номером `number` за текущий год
+    pub fn current_year(number: impl Into<String>) -> Self {
+        Self {
+            number: number.into(),
+            current_year: true,
+            from: None,
+            to: None,
+        }
+    }
+
+    /// Документы с номером `number` за интервал дат (YYYY-MM-DD)
+    pub fn between(
+        number: impl Into<String>,
+        from: impl Into<DateParam>,
+        to: impl Into<DateParam>,
+    ) -> Self {
+        Self {
+            number: number.into(),
+            current_year: false,
+            from: Some(from.into()),
+            to: Some(to.into()),
+        }
+    }
 }
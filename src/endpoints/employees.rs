@@ -1,9 +1,11 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::ids::{DepartmentCode, EmployeeCode, EmployeeId};
+use crate::query::{QueryParams, query_setters};
+use crate::server_info::ServerVersion;
 use crate::xml::response::employees::{Employee, Employees};
 use quick_xml::de::from_str;
 use quick_xml::se::to_string;
-use uuid::Uuid;
 
 pub struct EmployeesEndpoint<'a> {
     client: &'a IikoClient,
@@ -27,11 +29,24 @@ impl<'a> EmployeesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Список сотрудников. Все сотрудники (включая встроенные системные аккаунты), которые активны (не удалены)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.list", level = "debug", skip_all)
+    )]
     pub async fn list(
         &self,
         include_deleted: Option<bool>,
         revision_from: Option<i64>,
     ) -> Result<Vec<Employee>> {
+        let include_deleted = self
+            .client
+            .param_since(ServerVersion::new(5, 0, 0), include_deleted)
+            .await;
+        let revision_from = self
+            .client
+            .param_since(ServerVersion::new(6, 4, 0), revision_from)
+            .await;
+
         let mut param_strings = Vec::new();
         let mut params = Vec::new();
 
@@ -77,11 +92,21 @@ impl<'a> EmployeesEndpoint<'a> {
     /// # Что в ответе:
     /// - Список сотрудников указанного подразделения. Все сотрудники (включая встроенные системные аккаунты), которые активны (не удалены).
     ///   Для RMS идентично обычному списку, для Chain - только список сотрудников указанного подразделения.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.list_by_department", level = "debug", skip_all)
+    )]
     pub async fn list_by_department(
         &self,
-        department_code: &str,
+        department_code: impl Into<DepartmentCode>,
         include_deleted: Option<bool>,
     ) -> Result<Vec<Employee>> {
+        let department_code = department_code.into();
+        let include_deleted = self
+            .client
+            .param_since(ServerVersion::new(5, 0, 0), include_deleted)
+            .await;
+
         let mut param_strings = Vec::new();
         let mut params = Vec::new();
 
@@ -117,8 +142,12 @@ impl<'a> EmployeesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Сотрудник с указанным GUID
-    pub async fn get_by_id(&self, employee_uuid: Uuid) -> Result<Employee> {
-        let endpoint = format!("employees/byId/{}", employee_uuid);
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.get_by_id", level = "debug", skip_all)
+    )]
+    pub async fn get_by_id(&self, employee_uuid: impl Into<EmployeeId>) -> Result<Employee> {
+        let endpoint = format!("employees/byId/{}", employee_uuid.into());
         let response_xml = self.client.get(&endpoint).await?;
 
         let employee: Employee = from_str(&response_xml)?;
@@ -136,7 +165,12 @@ impl<'a> EmployeesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Сотрудник с указанным кодом
-    pub async fn get_by_code(&self, employee_code: &str) -> Result<Employee> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.get_by_code", level = "debug", skip_all)
+    )]
+    pub async fn get_by_code(&self, employee_code: impl Into<EmployeeCode>) -> Result<Employee> {
+        let employee_code = employee_code.into();
         let endpoint = format!("employees/byCode/{}", employee_code);
         let response_xml = self.client.get(&endpoint).await?;
 
@@ -151,15 +185,54 @@ impl<'a> EmployeesEndpoint<'a> {
     /// # Endpoint: GET `/employees/search`
     ///
     /// # Параметры запроса:
-    /// Поиск по регулярному выражению по любому из текстовых или булевых полей в dto:
-    /// - `address`, `card_number`, `cell_phone`, `client`, `code`, `email`, `employee`,
-    ///   `first_name`, `last_name`, `login`, `main_role_code`, `middle_name`, `name`,
-    ///   `note`, `phone`, `supplier`
-    /// Параметры необязательные. Если отсутствуют, вернет всех активных.
-    /// - `include_deleted`: Возвращать и действующих, и удаленных сотрудников (с версии 5.0)
+    /// Поиск по регулярному выражению по любому из текстовых или булевых полей в dto
+    /// (см. [`EmployeeSearch`]). Если ни одно поле не задано, вернет всех активных.
+    /// `include_deleted` передается серверу с версии 5.0.
     ///
     /// # Что в ответе:
     /// - Список найденных сотрудников
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.search", level = "debug", skip_all)
+    )]
+    pub async fn find(&self, search: EmployeeSearch) -> Result<Vec<Employee>> {
+        let include_deleted = self
+            .client
+            .param_since(ServerVersion::new(5, 0, 0), search.include_deleted)
+            .await;
+
+        let mut params = QueryParams::new();
+        params
+            .push_opt("address", search.address)
+            .push_opt("cardNumber", search.card_number)
+            .push_opt("cellPhone", search.cell_phone)
+            .push_opt("client", search.client)
+            .push_opt("code", search.code)
+            .push_opt("email", search.email)
+            .push_opt("employee", search.employee)
+            .push_opt("firstName", search.first_name)
+            .push_opt("lastName", search.last_name)
+            .push_opt("login", search.login)
+            .push_opt("mainRoleCode", search.main_role_code)
+            .push_opt("middleName", search.middle_name)
+            .push_opt("name", search.name)
+            .push_opt("note", search.note)
+            .push_opt("phone", search.phone)
+            .push_opt("supplier", search.supplier)
+            .push_opt("includeDeleted", include_deleted);
+
+        let response_xml = self
+            .client
+            .get_with_params("employees/search", &params.pairs())
+            .await?;
+
+        let wrapper: Employees = from_str(&response_xml)?;
+        Ok(wrapper.items)
+    }
+
+    /// Поиск сотрудника с позиционными параметрами (см. [`EmployeesEndpoint::find`])
+    #[deprecated(note = "используйте `find(EmployeeSearch::new()...)`")]
+    #[allow(clippy::too_many_arguments)]
     pub async fn search(
         &self,
         address: Option<&str>,
@@ -180,140 +253,26 @@ impl<'a> EmployeesEndpoint<'a> {
         supplier: Option<bool>,
         include_deleted: Option<bool>,
     ) -> Result<Vec<Employee>> {
-        let mut param_strings = Vec::new();
-        let mut params = Vec::new();
-
-        // Собираем все строки
-        if let Some(a) = address {
-            param_strings.push(a.to_string());
-        }
-        if let Some(cn) = card_number {
-            param_strings.push(cn.to_string());
-        }
-        if let Some(cp) = cell_phone {
-            param_strings.push(cp.to_string());
-        }
-        if let Some(c) = client {
-            param_strings.push(if c { "true" } else { "false" }.to_string());
-        }
-        if let Some(c) = code {
-            param_strings.push(c.to_string());
-        }
-        if let Some(e) = email {
-            param_strings.push(e.to_string());
-        }
-        if let Some(e) = employee {
-            param_strings.push(if e { "true" } else { "false" }.to_string());
-        }
-        if let Some(fn_val) = first_name {
-            param_strings.push(fn_val.to_string());
-        }
-        if let Some(ln) = last_name {
-            param_strings.push(ln.to_string());
-        }
-        if let Some(l) = login {
-            param_strings.push(l.to_string());
-        }
-        if let Some(mrc) = main_role_code {
-            param_strings.push(mrc.to_string());
-        }
-        if let Some(mn) = middle_name {
-            param_strings.push(mn.to_string());
-        }
-        if let Some(n) = name {
-            param_strings.push(n.to_string());
-        }
-        if let Some(n) = note {
-            param_strings.push(n.to_string());
-        }
-        if let Some(p) = phone {
-            param_strings.push(p.to_string());
-        }
-        if let Some(s) = supplier {
-            param_strings.push(if s { "true" } else { "false" }.to_string());
-        }
-        if let Some(inc_del) = include_deleted {
-            param_strings.push(if inc_del { "true" } else { "false" }.to_string());
-        }
-
-        // Создаем params, используя индексы
-        let mut idx = 0;
-        if let Some(_) = address {
-            params.push(("address", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = card_number {
-            params.push(("cardNumber", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = cell_phone {
-            params.push(("cellPhone", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = client {
-            params.push(("client", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = code {
-            params.push(("code", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = email {
-            params.push(("email", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = employee {
-            params.push(("employee", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = first_name {
-            params.push(("firstName", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = last_name {
-            params.push(("lastName", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = login {
-            params.push(("login", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = main_role_code {
-            params.push(("mainRoleCode", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = middle_name {
-            params.push(("middleName", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = name {
-            params.push(("name", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = note {
-            params.push(("note", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = phone {
-            params.push(("phone", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = supplier {
-            params.push(("supplier", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = include_deleted {
-            params.push(("includeDeleted", param_strings[idx].as_str()));
-            idx += 1;
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("employees/search", &params)
-            .await?;
-
-        let wrapper: Employees = from_str(&response_xml)?;
-        Ok(wrapper.items)
+        self.find(EmployeeSearch {
+            address: address.map(str::to_string),
+            card_number: card_number.map(str::to_string),
+            cell_phone: cell_phone.map(str::to_string),
+            client,
+            code: code.map(str::to_string),
+            email: email.map(str::to_string),
+            employee,
+            first_name: first_name.map(str::to_string),
+            last_name: last_name.map(str::to_string),
+            login: login.map(str::to_string),
+            main_role_code: main_role_code.map(str::to_string),
+            middle_name: middle_name.map(str::to_string),
+            name: name.map(str::to_string),
+            note: note.map(str::to_string),
+            phone: phone.map(str::to_string),
+            supplier,
+            include_deleted,
+        })
+        .await
     }
 
     /// Добавить или заменить сотрудника
@@ -331,12 +290,16 @@ impl<'a> EmployeesEndpoint<'a> {
     /// - Если передан id существующего сотрудника, то произойдет полное замещение всех полей сотрудника (код возврата 200 OK).
     ///   При этом если не указать какое-либо из необязательных полей, то значение этого поля сбросится.
     /// - Для обновления частичного набора полей используйте метод `update_partial`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.create_or_replace", level = "debug", skip_all)
+    )]
     pub async fn create_or_replace(
         &self,
-        employee_uuid: Uuid,
+        employee_uuid: impl Into<EmployeeId>,
         employee: &Employee,
     ) -> Result<Employee> {
-        let endpoint = format!("employees/byId/{}", employee_uuid);
+        let endpoint = format!("employees/byId/{}", employee_uuid.into());
         let xml_body = to_string(employee)?;
         let response_xml = self.client.put_xml(&endpoint, &xml_body).await?;
 
@@ -356,12 +319,16 @@ impl<'a> EmployeesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Обновленный сотрудник
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.update_partial", level = "debug", skip_all)
+    )]
     pub async fn update_partial(
         &self,
-        employee_uuid: Uuid,
+        employee_uuid: impl Into<EmployeeId>,
         employee: &Employee,
     ) -> Result<Employee> {
-        let endpoint = format!("employees/byId/{}", employee_uuid);
+        let endpoint = format!("employees/byId/{}", employee_uuid.into());
         let xml_body = to_string(employee)?;
         let response_xml = self.client.post_xml(&endpoint, &xml_body).await?;
 
@@ -381,8 +348,80 @@ impl<'a> EmployeesEndpoint<'a> {
     /// # Что в ответе:
     /// - Пустой ответ если сотрудник удален (или уже был удален).
     /// - Entity of class User not found by id (employeeUUID), если передан несуществующий guid.
-    pub async fn delete(&self, employee_uuid: Uuid) -> Result<String> {
-        let endpoint = format!("employees/byId/{}", employee_uuid);
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.employees.delete", level = "debug", skip_all)
+    )]
+    pub async fn delete(&self, employee_uuid: impl Into<EmployeeId>) -> Result<String> {
+        let endpoint = format!("employees/byId/{}", employee_uuid.into());
         self.client.delete(&endpoint).await
     }
 }
+
+/// Параметры поиска сотрудников для [`EmployeesEndpoint::find`]
+///
+/// Значения текстовых полей — регулярные выражения.
+#[derive(Debug, Clone, Default)]
+pub struct EmployeeSearch {
+    address: Option<String>,
+    card_number: Option<String>,
+    cell_phone: Option<String>,
+    client: Option<bool>,
+    code: Option<String>,
+    email: Option<String>,
+    employee: Option<bool>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    login: Option<String>,
+    main_role_code: Option<String>,
+    middle_name: Option<String>,
+    name: Option<String>,
+    note: Option<String>,
+    phone: Option<String>,
+    supplier: Option<bool>,
+    include_deleted: Option<bool>,
+}
+
+impl EmployeeSearch {
+    /// Поиск без фильтров (все активные сотрудники)
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+query_setters!(EmployeeSearch {
+    /// Адрес
+    address: text,
+    /// Номер карты
+    card_number: text,
+    /// Мобильный телефон
+    cell_phone: text,
+    /// Является клиентом
+    client: flag,
+    /// Табельный номер / код
+    code: text,
+    /// E-mail
+    email: text,
+    /// Является сотрудником
+    employee: flag,
+    /// Имя
+    first_name: text,
+    /// Фамилия
+    last_name: text,
+    /// Логин
+    login: text,
+    /// Код основной должности
+    main_role_code: text,
+    /// Отчество
+    middle_name: text,
+    /// Имя в системе
+    name: text,
+    /// Комментарий
+    note: text,
+    /// Телефон
+    phone: text,
+    /// Является поставщиком
+    supplier: flag,
+    /// Возвращать и удаленных сотрудников (с версии iiko 5.0, на старых серверах не передается)
+    include_deleted: flag,
+});
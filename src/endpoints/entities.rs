@@ -1,5 +1,6 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::server_info::ServerVersion;
 use crate::xml::response::{EntityType, ReferenceEntity, ReferenceEntityDto};
 use serde_json;
 use uuid::Uuid;
@@ -30,12 +31,20 @@ impl<'a> EntitiesEndpoint<'a> {
     ///
     /// # Примечание
     /// Параметр `format` (SHORT) начиная с версии 6.2.2 не используется, но формат вывода остался прежним.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.entities.list", level = "debug", skip_all)
+    )]
     pub async fn list(
         &self,
         root_types: &[EntityType],
         include_deleted: Option<bool>,
         revision_from: Option<i64>,
     ) -> Result<Vec<ReferenceEntityDto>> {
+        self.client
+            .require_version(ServerVersion::new(5, 0, 0))
+            .await?;
+
         let mut params: Vec<(&str, &str)> = Vec::new();
         let revision_str;
 
@@ -75,12 +84,24 @@ impl<'a> EntitiesEndpoint<'a> {
     /// # Что в ответе
     /// Возвращает справочную информацию с дополнительными полями для типов OrderType, ProductSize, TaxCategory.
     /// Для остальных типов возвращает базовую информацию.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.entities.list_with_extended_fields",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn list_with_extended_fields(
         &self,
         root_types: &[EntityType],
         include_deleted: Option<bool>,
         revision_from: Option<i64>,
     ) -> Result<Vec<ReferenceEntity>> {
+        self.client
+            .require_version(ServerVersion::new(5, 0, 0))
+            .await?;
+
         let mut params: Vec<(&str, &str)> = Vec::new();
         let revision_str;
 
@@ -123,12 +144,20 @@ impl<'a> EntitiesEndpoint<'a> {
     ///
     /// # Важно
     /// Этот эндпойнт требует версию iiko 9.1 или выше. На более старых версиях будет возвращаться 404 Not Found.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.entities.get_ids", level = "debug", skip_all)
+    )]
     pub async fn get_ids(
         &self,
         entity_type: EntityType,
         include_deleted: Option<bool>,
         revision_from: Option<i64>,
     ) -> Result<Vec<Uuid>> {
+        self.client
+            .require_version(ServerVersion::new(9, 1, 0))
+            .await?;
+
         let mut params: Vec<(&str, &str)> = Vec::new();
         let revision_str;
 
@@ -1,4 +1,5 @@
 use crate::client::IikoClient;
+use crate::date::{DateFormat, DateParam};
 use crate::error::Result;
 use crate::xml::request::events::{EventsFilter, EventsRequestData, OrderNumsFilter};
 use crate::xml::response::events::{CashSession, CashSessionsList, Event, EventsList, GroupsList};
@@ -20,19 +21,25 @@ impl<'a> EventsEndpoint<'a> {
     /// - `from_time`: Время с которого запрашиваются события (yyyy-MM-ddTHH:mm:ss.SSS)
     /// - `to_time`: Время по которое запрашиваются события (yyyy-MM-ddTHH:mm:ss.SSS)
     /// - `from_rev`: Ревизия, с которой запрашиваются события
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.events.get_events", level = "debug", skip_all)
+    )]
     pub async fn get_events(
         &self,
-        from_time: Option<&str>,
-        to_time: Option<&str>,
+        from_time: Option<DateParam>,
+        to_time: Option<DateParam>,
         from_rev: Option<i64>,
     ) -> Result<EventsList> {
+        let from_time = from_time.map(|time| DateFormat::IsoDateTimeMillis.format(time));
+        let to_time = to_time.map(|time| DateFormat::IsoDateTimeMillis.format(time));
         let mut params: Vec<(&str, &str)> = Vec::new();
         let rev_string;
 
-        if let Some(ft) = from_time {
+        if let Some(ft) = &from_time {
             params.push(("from_time", ft));
         }
-        if let Some(tt) = to_time {
+        if let Some(tt) = &to_time {
             params.push(("to_time", tt));
         }
         if let Some(rev) = from_rev {
@@ -51,6 +58,10 @@ impl<'a> EventsEndpoint<'a> {
     /// # Параметры
     /// - `event_types`: Список типов событий для фильтрации
     /// - `order_nums`: Список номеров заказов для фильтрации (опционально)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.events.get_events_by_filter", level = "debug", skip_all)
+    )]
     pub async fn get_events_by_filter(
         &self,
         event_types: Vec<String>,
@@ -62,7 +73,7 @@ impl<'a> EventsEndpoint<'a> {
         };
 
         let xml_body = to_string(&request)?;
-        let response_xml = self.client.post_xml("events", &xml_body).await?;
+        let response_xml = self.client.post_xml_query("events", &xml_body).await?;
 
         let events_list: EventsList = from_str(&response_xml)?;
         Ok(events_list)
@@ -72,6 +83,10 @@ impl<'a> EventsEndpoint<'a> {
     ///
     /// # Параметры
     /// - `events`: Список событий для сохранения
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.events.add_events", level = "debug", skip_all)
+    )]
     pub async fn add_events(&self, events: Vec<Event>) -> Result<EventsList> {
         let events_list = EventsList {
             events,
@@ -86,6 +101,10 @@ impl<'a> EventsEndpoint<'a> {
     }
 
     /// Получить дерево событий (метаданные)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.events.get_metadata", level = "debug", skip_all)
+    )]
     pub async fn get_metadata(&self) -> Result<GroupsList> {
         let response_xml = self.client.get("events/metadata").await?;
 
@@ -97,6 +116,14 @@ impl<'a> EventsEndpoint<'a> {
     ///
     /// # Параметры
     /// - `event_types`: Список типов событий для фильтрации
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.events.get_metadata_by_filter",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_metadata_by_filter(&self, event_types: Vec<String>) -> Result<GroupsList> {
         let request = EventsRequestData {
             events: EventsFilter { items: event_types },
@@ -104,7 +131,10 @@ impl<'a> EventsEndpoint<'a> {
         };
 
         let xml_body = to_string(&request)?;
-        let response_xml = self.client.post_xml("events/metadata", &xml_body).await?;
+        let response_xml = self
+            .client
+            .post_xml_query("events/metadata", &xml_body)
+            .await?;
 
         let groups_list: GroupsList = from_str(&response_xml)?;
         Ok(groups_list)
@@ -115,17 +145,23 @@ impl<'a> EventsEndpoint<'a> {
     /// # Параметры
     /// - `from_time`: Время с которого запрашиваются данные (yyyy-MM-ddTHH:mm:ss.SSS)
     /// - `to_time`: Время по которое запрашиваются данные (yyyy-MM-ddTHH:mm:ss.SSS)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.events.get_sessions", level = "debug", skip_all)
+    )]
     pub async fn get_sessions(
         &self,
-        from_time: Option<&str>,
-        to_time: Option<&str>,
+        from_time: Option<DateParam>,
+        to_time: Option<DateParam>,
     ) -> Result<Vec<CashSession>> {
+        let from_time = from_time.map(|time| DateFormat::IsoDateTimeMillis.format(time));
+        let to_time = to_time.map(|time| DateFormat::IsoDateTimeMillis.format(time));
         let mut params = Vec::new();
-        if let Some(ft) = from_time {
-            params.push(("from_time", ft));
+        if let Some(ft) = &from_time {
+            params.push(("from_time", ft.as_str()));
         }
-        if let Some(tt) = to_time {
-            params.push(("to_time", tt));
+        if let Some(tt) = &to_time {
+            params.push(("to_time", tt.as_str()));
         }
 
         let response_xml = self
@@ -1,8 +1,11 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::server_info::ServerVersion;
+use crate::streaming;
 use crate::xml::response::images::{ImageDto, ImageOperationResult, ImageSaveRequest};
 use crate::xml::response::products::{IdCodeDto, ItemsRequest};
 use serde_json::to_string as json_to_string;
+use tokio::io::AsyncWrite;
 use uuid::Uuid;
 
 pub struct ImagesEndpoint<'a> {
@@ -34,7 +37,15 @@ impl<'a> ImagesEndpoint<'a> {
     /// # Примечание:
     /// - Метод обрабатывает edge cases, когда API может вернуть пустой объект
     ///   или ответ в нестандартном формате
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.images.load", level = "debug", skip_all)
+    )]
     pub async fn load(&self, image_id: Uuid) -> Result<ImageDto> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let image_id_str = image_id.to_string();
         let params = vec![("imageId", image_id_str.as_str())];
         let response_text = self
@@ -81,6 +92,40 @@ impl<'a> ImagesEndpoint<'a> {
         }
     }
 
+    /// Выгрузка изображения (load) с записью байтов в `writer`
+    ///
+    /// # Версия iiko: 6.2
+    /// # Endpoint: GET `/v2/images/load?imageId={imageId}`
+    ///
+    /// # Параметры запроса:
+    /// - `image_id`: UUID запрашиваемого изображения (обязательный)
+    /// - `writer`: Куда записать декодированное изображение (файл, сокет, буфер)
+    ///
+    /// # Что в ответе:
+    /// - Количество записанных байт. 0, если у изображения нет данных
+    ///
+    /// # Важно:
+    /// - Base64 декодируется по мере получения ответа, изображение целиком в памяти не хранится
+    /// - При ошибке в `writer` может остаться начало изображения
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.images.load_into", level = "debug", skip_all)
+    )]
+    pub async fn load_into<W>(&self, image_id: Uuid, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
+        let image_id_str = image_id.to_string();
+        let params = vec![("imageId", image_id_str.as_str())];
+        let body = self.client.get_stream("v2/images/load", &params).await?;
+
+        streaming::write_image(body, writer).await
+    }
+
     /// Импорт изображения (save)
     ///
     /// # Версия iiko: 6.2
@@ -104,7 +149,15 @@ impl<'a> ImagesEndpoint<'a> {
     /// # Ошибки:
     /// - При превышении размера: ошибка валидации
     /// - При невалидном Base64: ошибка десериализации
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.images.save", level = "debug", skip_all)
+    )]
     pub async fn save(&self, data: String, id: Option<Uuid>) -> Result<ImageOperationResult> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let request = ImageSaveRequest { id, data };
         let json_body = json_to_string(&request)?;
         let response_json = self
@@ -135,7 +188,15 @@ impl<'a> ImagesEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если изображение не найдено: ошибка валидации
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.images.delete", level = "debug", skip_all)
+    )]
     pub async fn delete(&self, ids: Vec<Uuid>) -> Result<ImageOperationResult> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let items = ids.into_iter().map(|id| IdCodeDto { id }).collect();
         let request = ItemsRequest { items };
 
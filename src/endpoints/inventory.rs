@@ -1,5 +1,6 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::ids::StoreId;
 use crate::xml::request::InventoryRequest;
 use crate::xml::response::InventoryItem;
 use quick_xml::de::from_str;
@@ -14,11 +15,15 @@ impl<'a> InventoryEndpoint<'a> {
         Self { client }
     }
 
-    pub async fn get_inventory(&self, store_id: Option<String>) -> Result<Vec<InventoryItem>> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.inventory.get_inventory", level = "debug", skip_all)
+    )]
+    pub async fn get_inventory(&self, store_id: Option<StoreId>) -> Result<Vec<InventoryItem>> {
         let request = InventoryRequest { store_id };
         let xml_body = to_string(&request)?;
 
-        let response_xml = self.client.post_xml("inventory", &xml_body).await?;
+        let response_xml = self.client.post_xml_query("inventory", &xml_body).await?;
         let items: Vec<InventoryItem> = from_str(&response_xml)?;
 
         Ok(items)
@@ -1,5 +1,7 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::ids::ProductId;
+use crate::server_info::ServerVersion;
 use crate::xml::response::product_scales::{
     ProductProductScaleRequest, ProductScaleDto, ProductScaleOperationResult,
     ProductScaleSaveRequest, ProductScaleUpdateRequest,
@@ -29,11 +31,19 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Список шкал с размерами (Vec<ProductScaleDto>)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.list", level = "debug", skip_all)
+    )]
     pub async fn list(
         &self,
         ids: Option<Vec<Uuid>>,
         include_deleted: Option<bool>,
     ) -> Result<Vec<ProductScaleDto>> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let mut param_strings = Vec::new();
         let mut params = Vec::new();
 
@@ -83,11 +93,19 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Список шкал с размерами (Vec<ProductScaleDto>)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.list_post", level = "debug", skip_all)
+    )]
     pub async fn list_post(
         &self,
         ids: Option<Vec<Uuid>>,
         include_deleted: Option<bool>,
     ) -> Result<Vec<ProductScaleDto>> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let mut form_strings = Vec::new();
         let mut form_data = Vec::new();
 
@@ -116,7 +134,7 @@ impl<'a> ProductScalesEndpoint<'a> {
 
         let response_json = self
             .client
-            .post_form("v2/entities/productScales", &form_data)
+            .post_form_query("v2/entities/productScales", &form_data)
             .await?;
 
         let scales: Vec<ProductScaleDto> = serde_json::from_str(&response_json)?;
@@ -133,7 +151,15 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Шкала с размерами (ProductScaleDto)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.by_id", level = "debug", skip_all)
+    )]
     pub async fn by_id(&self, product_scale_id: Uuid) -> Result<ProductScaleDto> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let scale_id_str = product_scale_id.to_string();
         let endpoint = format!("v2/entities/productScales/{}", scale_id_str);
         let response_json = self.client.get(&endpoint).await?;
@@ -152,7 +178,15 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Созданная шкала с размерами (ProductScaleDto)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.save", level = "debug", skip_all)
+    )]
     pub async fn save(&self, request: ProductScaleSaveRequest) -> Result<ProductScaleDto> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let json_body = json_to_string(&request)?;
         let response_json = self
             .client
@@ -173,7 +207,15 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Отредактированная шкала с размерами (ProductScaleDto)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.update", level = "debug", skip_all)
+    )]
     pub async fn update(&self, request: ProductScaleUpdateRequest) -> Result<ProductScaleDto> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let json_body = json_to_string(&request)?;
         let response_json = self
             .client
@@ -194,7 +236,15 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Удалённая шкала с размерами (ProductScaleDto) - возвращается первая удаленная шкала
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.delete", level = "debug", skip_all)
+    )]
     pub async fn delete(&self, ids: Vec<Uuid>) -> Result<ProductScaleDto> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let items = ids.into_iter().map(|id| IdCodeDto { id }).collect();
         let request = ItemsRequest { items };
 
@@ -218,7 +268,15 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Восстановленная шкала с размерами (ProductScaleDto) - возвращается первая восстановленная шкала
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.product_scales.restore", level = "debug", skip_all)
+    )]
     pub async fn restore(&self, ids: Vec<Uuid>) -> Result<ProductScaleDto> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let items = ids.into_iter().map(|id| IdCodeDto { id }).collect();
         let request = ItemsRequest { items };
 
@@ -245,8 +303,23 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///   - `result`: "SUCCESS" или "ERROR"
     ///   - `errors`: Список ошибок валидации (null при успешной операции)
     ///   - `response`: Шкала с коэффициентами и доступностью размеров (ProductScaleDto) или null
-    pub async fn get_for_product(&self, product_id: Uuid) -> Result<ProductScaleOperationResult> {
-        let product_id_str = product_id.to_string();
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.product_scales.get_for_product",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn get_for_product(
+        &self,
+        product_id: impl Into<ProductId>,
+    ) -> Result<ProductScaleOperationResult> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
+        let product_id_str = product_id.into().to_string();
         let endpoint = format!("v2/entities/products/{}/productScale", product_id_str);
         let response_json = self.client.get(&endpoint).await?;
 
@@ -264,12 +337,24 @@ impl<'a> ProductScalesEndpoint<'a> {
     /// - `include_deleted_products`: Включать ли в результат шкалы для удалённых продуктов. По умолчанию false
     ///
     /// # Что в ответе:
-    /// - Список пар (productId : шкала) - HashMap<Uuid, Option<ProductScaleDto>>
+    /// - Список пар (productId : шкала) - HashMap<ProductId, Option<ProductScaleDto>>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.product_scales.get_for_products",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_for_products(
         &self,
-        product_ids: Option<Vec<Uuid>>,
+        product_ids: Option<Vec<ProductId>>,
         include_deleted_products: Option<bool>,
-    ) -> Result<HashMap<Uuid, Option<ProductScaleDto>>> {
+    ) -> Result<HashMap<ProductId, Option<ProductScaleDto>>> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let mut param_strings = Vec::new();
         let mut params = Vec::new();
 
@@ -310,11 +395,11 @@ impl<'a> ProductScalesEndpoint<'a> {
         let mut result: HashMap<String, Option<ProductScaleDto>> =
             serde_json::from_str(&response_json)?;
 
-        // Конвертируем ключи из String в Uuid
-        let mut converted: HashMap<Uuid, Option<ProductScaleDto>> = HashMap::new();
+        // Конвертируем ключи из String в ProductId
+        let mut converted: HashMap<ProductId, Option<ProductScaleDto>> = HashMap::new();
         for (key, value) in result.drain() {
-            if let Ok(uuid) = Uuid::parse_str(&key) {
-                converted.insert(uuid, value);
+            if let Ok(product_id) = key.parse::<ProductId>() {
+                converted.insert(product_id, value);
             }
         }
 
@@ -331,12 +416,24 @@ impl<'a> ProductScalesEndpoint<'a> {
     /// - `include_deleted_products`: Включать ли в результат шкалы для удалённых продуктов. По умолчанию false
     ///
     /// # Что в ответе:
-    /// - Список пар (productId : шкала) - HashMap<Uuid, Option<ProductScaleDto>>
+    /// - Список пар (productId : шкала) - HashMap<ProductId, Option<ProductScaleDto>>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.product_scales.get_for_products_post",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_for_products_post(
         &self,
-        product_ids: Option<Vec<Uuid>>,
+        product_ids: Option<Vec<ProductId>>,
         include_deleted_products: Option<bool>,
-    ) -> Result<HashMap<Uuid, Option<ProductScaleDto>>> {
+    ) -> Result<HashMap<ProductId, Option<ProductScaleDto>>> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
         let mut form_strings = Vec::new();
         let mut form_data = Vec::new();
 
@@ -365,18 +462,18 @@ impl<'a> ProductScalesEndpoint<'a> {
 
         let response_json = self
             .client
-            .post_form("v2/entities/products/productScales", &form_data)
+            .post_form_query("v2/entities/products/productScales", &form_data)
             .await?;
 
         // Парсим как HashMap, где ключи - строки UUID, значения - Option<ProductScaleDto>
         let mut result: HashMap<String, Option<ProductScaleDto>> =
             serde_json::from_str(&response_json)?;
 
-        // Конвертируем ключи из String в Uuid
-        let mut converted: HashMap<Uuid, Option<ProductScaleDto>> = HashMap::new();
+        // Конвертируем ключи из String в ProductId
+        let mut converted: HashMap<ProductId, Option<ProductScaleDto>> = HashMap::new();
         for (key, value) in result.drain() {
-            if let Ok(uuid) = Uuid::parse_str(&key) {
-                converted.insert(uuid, value);
+            if let Ok(product_id) = key.parse::<ProductId>() {
+                converted.insert(product_id, value);
             }
         }
 
@@ -394,12 +491,24 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Шкала с коэффициентами и доступностью размеров (ProductScaleDto)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.product_scales.set_for_product",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn set_for_product(
         &self,
-        product_id: Uuid,
+        product_id: impl Into<ProductId>,
         request: ProductProductScaleRequest,
     ) -> Result<ProductScaleDto> {
-        let product_id_str = product_id.to_string();
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
+        let product_id_str = product_id.into().to_string();
         let endpoint = format!("v2/entities/products/{}/productScale", product_id_str);
         let json_body = json_to_string(&request)?;
         let response_json = self.client.post_json(&endpoint, &json_body, &[]).await?;
@@ -418,8 +527,20 @@ impl<'a> ProductScalesEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - UUID шкалы (Uuid)
-    pub async fn delete_for_product(&self, product_id: Uuid) -> Result<Uuid> {
-        let product_id_str = product_id.to_string();
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.product_scales.delete_for_product",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn delete_for_product(&self, product_id: impl Into<ProductId>) -> Result<Uuid> {
+        self.client
+            .require_version(ServerVersion::new(6, 2, 0))
+            .await?;
+
+        let product_id_str = product_id.into().to_string();
         let endpoint = format!("v2/entities/products/{}/productScale", product_id_str);
 
         let response_text = self.client.delete(&endpoint).await?;
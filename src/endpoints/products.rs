@@ -1,12 +1,14 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::ids::{ProductArticle, ProductCategoryId, ProductGroupId, ProductId};
+use crate::query::{QueryParams, query_setters};
+use crate::server_info::ServerVersion;
 use crate::xml::response::products::{
     CategoryDeleteRequest, CategoryOperationResult, CategoryRestoreRequest, CategorySaveRequest,
     CategoryUpdateRequest, EntityDto, IdCodeDto, ItemsRequest, ProductDto, ProductGroupDto,
     ProductOperationResult, ProductsOperationResult,
 };
 use serde_json::to_string as json_to_string;
-use uuid::Uuid;
 
 pub struct ProductsEndpoint<'a> {
     client: &'a IikoClient,
@@ -20,12 +22,41 @@ impl<'a> ProductsEndpoint<'a> {
     /// Получить список продуктов (GET)
     ///
     /// # Параметры
-    /// - `include_deleted`: Включать ли удаленные элементы
-    /// - `ids`: Список ID продуктов для фильтрации
-    /// - `nums`: Список артикулов для фильтрации
-    /// - `types`: Список типов продуктов для фильтрации
-    /// - `category_ids`: Список ID категорий для фильтрации
-    /// - `parent_ids`: Список ID родительских групп для фильтрации
+    /// Фильтры — см. [`ProductQuery`]. Без фильтров возвращается вся номенклатура.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.list", level = "debug", skip_all)
+    )]
+    pub async fn find(&self, query: ProductQuery) -> Result<Vec<ProductDto>> {
+        let response_json = self
+            .client
+            .get_with_params("v2/entities/products/list", &query.params().pairs())
+            .await?;
+
+        let products: Vec<ProductDto> = serde_json::from_str(&response_json)?;
+        Ok(products)
+    }
+
+    /// Получить список продуктов (POST с form data)
+    ///
+    /// То же, что [`ProductsEndpoint::find`], но фильтры передаются в теле запроса:
+    /// длинные списки `ids` не упираются в ограничение длины URL.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.list_post", level = "debug", skip_all)
+    )]
+    pub async fn find_post(&self, query: ProductQuery) -> Result<Vec<ProductDto>> {
+        let response_json = self
+            .client
+            .post_form_query("v2/entities/products/list", &query.params().pairs())
+            .await?;
+
+        let products: Vec<ProductDto> = serde_json::from_str(&response_json)?;
+        Ok(products)
+    }
+
+    /// Получить список продуктов (GET) с позиционными параметрами
+    #[deprecated(note = "используйте `find(ProductQuery::new()...)`")]
     pub async fn list(
         &self,
         include_deleted: Option<bool>,
@@ -35,62 +66,22 @@ impl<'a> ProductsEndpoint<'a> {
         category_ids: Option<Vec<String>>,
         parent_ids: Option<Vec<String>>,
     ) -> Result<Vec<ProductDto>> {
-        let mut params: Vec<(&str, &str)> = Vec::new();
-
-        if let Some(inc_del) = include_deleted {
-            params.push(("includeDeleted", if inc_del { "true" } else { "false" }));
-        }
-
-        if let Some(ref id_list) = ids {
-            for id in id_list {
-                params.push(("ids", id.as_str()));
-            }
-        }
-
-        if let Some(ref num_list) = nums {
-            for num in num_list {
-                params.push(("nums", num.as_str()));
-            }
-        }
-
-        if let Some(ref type_list) = types {
-            for t in type_list {
-                params.push(("types", t.as_str()));
-            }
-        }
-
-        if let Some(ref cat_ids) = category_ids {
-            for cat_id in cat_ids {
-                params.push(("categoryIds", cat_id.as_str()));
-            }
-        }
-
-        if let Some(ref par_ids) = parent_ids {
-            for par_id in par_ids {
-                params.push(("parentIds", par_id.as_str()));
-            }
-        }
-
-        let response_json = self
-            .client
-            .get_with_params("v2/entities/products/list", &params)
-            .await?;
-
-        let products: Vec<ProductDto> = serde_json::from_str(&response_json)?;
-        Ok(products)
+        self.find(ProductQuery {
+            include_deleted,
+            revision_from: None,
+            ids: ids.unwrap_or_default(),
+            nums: nums.unwrap_or_default(),
+            codes: Vec::new(),
+            types: types.unwrap_or_default(),
+            category_ids: category_ids.unwrap_or_default(),
+            parent_ids: parent_ids.unwrap_or_default(),
+        })
+        .await
     }
 
-    /// Получить список продуктов (POST с form data)
-    ///
-    /// # Параметры
-    /// - `include_deleted`: Включать ли удаленные элементы
-    /// - `revision_from`: Ревизия, с которой запрашиваются элементы
-    /// - `ids`: Список ID продуктов для фильтрации
-    /// - `nums`: Список артикулов для фильтрации
-    /// - `codes`: Список кодов для фильтрации
-    /// - `types`: Список типов продуктов для фильтрации
-    /// - `category_ids`: Список ID категорий для фильтрации
-    /// - `parent_ids`: Список ID родительских групп для фильтрации
+    /// Получить список продуктов (POST с form data) с позиционными параметрами
+    #[deprecated(note = "используйте `find_post(ProductQuery::new()...)`")]
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_post(
         &self,
         include_deleted: Option<bool>,
@@ -102,61 +93,17 @@ impl<'a> ProductsEndpoint<'a> {
         category_ids: Option<Vec<String>>,
         parent_ids: Option<Vec<String>>,
     ) -> Result<Vec<ProductDto>> {
-        let mut form_params: Vec<(&str, &str)> = Vec::new();
-        let rev_string;
-
-        if let Some(inc_del) = include_deleted {
-            form_params.push(("includeDeleted", if inc_del { "true" } else { "false" }));
-        }
-
-        if let Some(rev) = revision_from {
-            rev_string = rev.to_string();
-            form_params.push(("revisionFrom", &rev_string));
-        }
-
-        if let Some(ref id_list) = ids {
-            for id in id_list {
-                form_params.push(("ids", id.as_str()));
-            }
-        }
-
-        if let Some(ref num_list) = nums {
-            for num in num_list {
-                form_params.push(("nums", num.as_str()));
-            }
-        }
-
-        if let Some(ref code_list) = codes {
-            for code in code_list {
-                form_params.push(("codes", code.as_str()));
-            }
-        }
-
-        if let Some(ref type_list) = types {
-            for t in type_list {
-                form_params.push(("types", t.as_str()));
-            }
-        }
-
-        if let Some(ref cat_ids) = category_ids {
-            for cat_id in cat_ids {
-                form_params.push(("categoryIds", cat_id.as_str()));
-            }
-        }
-
-        if let Some(ref par_ids) = parent_ids {
-            for par_id in par_ids {
-                form_params.push(("parentIds", par_id.as_str()));
-            }
-        }
-
-        let response_json = self
-            .client
-            .post_form("v2/entities/products/list", &form_params)
-            .await?;
-
-        let products: Vec<ProductDto> = serde_json::from_str(&response_json)?;
-        Ok(products)
+        self.find_post(ProductQuery {
+            include_deleted,
+            revision_from,
+            ids: ids.unwrap_or_default(),
+            nums: nums.unwrap_or_default(),
+            codes: codes.unwrap_or_default(),
+            types: types.unwrap_or_default(),
+            category_ids: category_ids.unwrap_or_default(),
+            parent_ids: parent_ids.unwrap_or_default(),
+        })
+        .await
     }
 
     /// Импорт элемента номенклатуры
@@ -165,6 +112,10 @@ impl<'a> ProductsEndpoint<'a> {
     /// - `product`: Продукт для импорта
     /// - `generate_nomenclature_code`: Генерировать ли артикул (по умолчанию true)
     /// - `generate_fast_code`: Генерировать ли код быстрого поиска (по умолчанию true)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.save", level = "debug", skip_all)
+    )]
     pub async fn save(
         &self,
         product: ProductDto,
@@ -203,6 +154,10 @@ impl<'a> ProductsEndpoint<'a> {
     /// - `product`: Продукт с обязательным `id` для обновления
     /// - `override_fast_code`: Перегенерировать ли код быстрого поиска (по умолчанию false)
     /// - `override_nomenclature_code`: Перегенерировать ли артикул (по умолчанию false)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.update", level = "debug", skip_all)
+    )]
     pub async fn update(
         &self,
         product: ProductDto,
@@ -242,8 +197,15 @@ impl<'a> ProductsEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если продукт уже удален, вернется ошибка: "Could not delete already deleted products: [uuid]"
-    pub async fn delete(&self, ids: Vec<Uuid>) -> Result<ProductsOperationResult> {
-        let items = ids.into_iter().map(|id| IdCodeDto { id }).collect();
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.delete", level = "debug", skip_all)
+    )]
+    pub async fn delete(&self, ids: Vec<ProductId>) -> Result<ProductsOperationResult> {
+        let items = ids
+            .into_iter()
+            .map(|id| IdCodeDto { id: id.into() })
+            .collect();
         let request = ItemsRequest { items };
 
         let json_body = json_to_string(&request)?;
@@ -271,11 +233,20 @@ impl<'a> ProductsEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если продукт не был удален, вернется ошибка: "Could not restore not deleted products: [uuid]"
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.restore", level = "debug", skip_all)
+    )]
     pub async fn restore(
         &self,
-        ids: Vec<Uuid>,
+        ids: Vec<ProductId>,
         override_nomenclature_code: Option<bool>,
     ) -> Result<ProductsOperationResult> {
+        let override_nomenclature_code = self
+            .client
+            .param_since(ServerVersion::new(6, 4, 0), override_nomenclature_code)
+            .await;
+
         let mut params: Vec<(&str, &str)> = Vec::new();
         if let Some(ovr_num) = override_nomenclature_code {
             params.push((
@@ -284,7 +255,10 @@ impl<'a> ProductsEndpoint<'a> {
             ));
         }
 
-        let items = ids.into_iter().map(|id| IdCodeDto { id }).collect();
+        let items = ids
+            .into_iter()
+            .map(|id| IdCodeDto { id: id.into() })
+            .collect();
         let request = ItemsRequest { items };
 
         let json_body = json_to_string(&request)?;
@@ -298,6 +272,10 @@ impl<'a> ProductsEndpoint<'a> {
     }
 
     /// Получить список групп продуктов
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.list_groups", level = "debug", skip_all)
+    )]
     pub async fn list_groups(&self, include_deleted: Option<bool>) -> Result<Vec<ProductGroupDto>> {
         let mut params: Vec<(&str, &str)> = Vec::new();
         if let Some(inc_del) = include_deleted {
@@ -319,6 +297,10 @@ impl<'a> ProductsEndpoint<'a> {
     /// - `include_deleted`: Включать ли в результат удаленные элементы (по умолчанию false)
     /// - `ids`: Список UUID категорий для фильтрации
     /// - `revision_from`: Номер ревизии, начиная с которой фильтровать (по умолчанию -1)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.list_categories", level = "debug", skip_all)
+    )]
     pub async fn list_categories(
         &self,
         include_deleted: Option<bool>,
@@ -354,6 +336,14 @@ impl<'a> ProductsEndpoint<'a> {
     /// - `include_deleted`: Включать ли в результат удаленные элементы (по умолчанию false)
     /// - `ids`: Список UUID категорий для фильтрации
     /// - `revision_from`: Номер ревизии, начиная с которой фильтровать (по умолчанию -1)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.products.list_categories_post",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn list_categories_post(
         &self,
         include_deleted: Option<bool>,
@@ -376,7 +366,7 @@ impl<'a> ProductsEndpoint<'a> {
 
         let response_json = self
             .client
-            .post_form("v2/entities/products/category/list", &form_params)
+            .post_form_query("v2/entities/products/category/list", &form_params)
             .await?;
 
         let categories: Vec<EntityDto> = serde_json::from_str(&response_json)?;
@@ -390,6 +380,10 @@ impl<'a> ProductsEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если имя не указано или состоит только из пробелов: "Category name is not specified or consist of whitespaces"
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.save_category", level = "debug", skip_all)
+    )]
     pub async fn save_category(&self, name: String) -> Result<CategoryOperationResult> {
         let request = CategorySaveRequest { name };
         let json_body = json_to_string(&request)?;
@@ -410,8 +404,19 @@ impl<'a> ProductsEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если имя не указано или состоит только из пробелов: "Category name is not specified or consist of whitespaces"
-    pub async fn update_category(&self, id: Uuid, name: String) -> Result<CategoryOperationResult> {
-        let request = CategoryUpdateRequest { id, name };
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.update_category", level = "debug", skip_all)
+    )]
+    pub async fn update_category(
+        &self,
+        id: impl Into<ProductCategoryId>,
+        name: String,
+    ) -> Result<CategoryOperationResult> {
+        let request = CategoryUpdateRequest {
+            id: id.into(),
+            name,
+        };
         let json_body = json_to_string(&request)?;
         let response_json = self
             .client
@@ -433,8 +438,15 @@ impl<'a> ProductsEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если категория уже удалена: "Could not delete already deleted product category: [uuid]"
-    pub async fn delete_category(&self, id: Uuid) -> Result<CategoryOperationResult> {
-        let request = CategoryDeleteRequest { id };
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.delete_category", level = "debug", skip_all)
+    )]
+    pub async fn delete_category(
+        &self,
+        id: impl Into<ProductCategoryId>,
+    ) -> Result<CategoryOperationResult> {
+        let request = CategoryDeleteRequest { id: id.into() };
         let json_body = json_to_string(&request)?;
         let response_json = self
             .client
@@ -456,8 +468,15 @@ impl<'a> ProductsEndpoint<'a> {
     ///
     /// # Ошибки:
     /// - Если категория не была удалена: "Could not restore not deleted product category: [uuid]"
-    pub async fn restore_category(&self, id: Uuid) -> Result<CategoryOperationResult> {
-        let request = CategoryRestoreRequest { id };
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.products.restore_category", level = "debug", skip_all)
+    )]
+    pub async fn restore_category(
+        &self,
+        id: impl Into<ProductCategoryId>,
+    ) -> Result<CategoryOperationResult> {
+        let request = CategoryRestoreRequest { id: id.into() };
         let json_body = json_to_string(&request)?;
         let response_json = self
             .client
@@ -468,3 +487,58 @@ impl<'a> ProductsEndpoint<'a> {
         Ok(result)
     }
 }
+
+/// Фильтры списка номенклатуры для [`ProductsEndpoint::find`] и [`ProductsEndpoint::find_post`]
+///
+/// Методы со списками можно вызывать несколько раз: значения добавляются.
+#[derive(Debug, Clone, Default)]
+pub struct ProductQuery {
+    include_deleted: Option<bool>,
+    revision_from: Option<i64>,
+    ids: Vec<String>,
+    nums: Vec<String>,
+    codes: Vec<String>,
+    types: Vec<String>,
+    category_ids: Vec<String>,
+    parent_ids: Vec<String>,
+}
+
+impl ProductQuery {
+    /// Вся номенклатура без фильтров
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push_opt("includeDeleted", self.include_deleted)
+            .push_opt("revisionFrom", self.revision_from)
+            .push_all("ids", &self.ids)
+            .push_all("nums", &self.nums)
+            .push_all("codes", &self.codes)
+            .push_all("types", &self.types)
+            .push_all("categoryIds", &self.category_ids)
+            .push_all("parentIds", &self.parent_ids);
+        params
+    }
+}
+
+query_setters!(ProductQuery {
+    /// Включать ли удаленные элементы
+    include_deleted: flag,
+    /// Ревизия, с которой запрашиваются элементы
+    revision_from: revision,
+    /// ID продуктов
+    ids: list<ProductId>,
+    /// Артикулы
+    nums: list<ProductArticle>,
+    /// Коды быстрого набора
+    codes: list,
+    /// Типы продуктов (`GOODS`, `DISH`, ...)
+    types: list,
+    /// ID пользовательских категорий
+    category_ids: list<ProductCategoryId>,
+    /// ID родительских групп
+    parent_ids: list<ProductGroupId>,
+});
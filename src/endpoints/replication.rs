@@ -1,9 +1,9 @@
 use crate::client::IikoClient;
 use crate::error::Result;
+use crate::ids::DepartmentId;
 use crate::xml::response::{ReplicationStatus, ServerType};
 use quick_xml::de::from_str;
 use serde::Deserialize;
-use uuid::Uuid;
 
 pub struct ReplicationEndpoint<'a> {
     client: &'a IikoClient,
@@ -14,6 +14,10 @@ impl<'a> ReplicationEndpoint<'a> {
         Self { client }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.replication.get_statuses", level = "debug", skip_all)
+    )]
     pub async fn get_statuses(&self) -> Result<Vec<ReplicationStatus>> {
         let response_xml = self.client.get("replication/statuses").await?;
 
@@ -21,14 +25,29 @@ impl<'a> ReplicationEndpoint<'a> {
         Ok(wrapper.items)
     }
 
-    pub async fn get_status_by_department(&self, department_id: Uuid) -> Result<ReplicationStatus> {
-        let endpoint = format!("replication/byDepartmentId/{}/status", department_id);
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.replication.get_status_by_department",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn get_status_by_department(
+        &self,
+        department_id: impl Into<DepartmentId>,
+    ) -> Result<ReplicationStatus> {
+        let endpoint = format!("replication/byDepartmentId/{}/status", department_id.into());
         let response_xml = self.client.get(&endpoint).await?;
 
         let status: ReplicationStatus = from_str(&response_xml)?;
         Ok(status)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.replication.get_server_type", level = "debug", skip_all)
+    )]
     pub async fn get_server_type(&self) -> Result<ServerType> {
         let response_xml = self.client.get("replication/serverType").await?;
 
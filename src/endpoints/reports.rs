@@ -1,10 +1,15 @@
 use crate::client::IikoClient;
+use crate::date::{DateFormat, DateParam};
 use crate::error::Result;
+use crate::ids::{AccountId, DepartmentId, ProductArticle, ProductId, StoreId};
+use crate::query::{QueryParams, query_setters};
+use crate::server_info::ServerVersion;
+use crate::streaming::OlapRowStream;
+use crate::xml::deserialize_list;
 use crate::xml::response::reports::{
-    BudgetPlanItemDto, DayDishValue, DayDishValues, DeliveryConsolidatedReport,
-    DeliveryCouriersReport, DeliveryHalfHourDetailedReport, DeliveryLoyaltyReport,
-    DeliveryOrderCycleReport, DeliveryRegionsReport, IngredientEntryDto, StoreReportItemDto,
-    StoreReportPreset,
+    BudgetPlanItemDto, DayDishValue, DeliveryConsolidatedReport, DeliveryCouriersReport,
+    DeliveryHalfHourDetailedReport, DeliveryLoyaltyReport, DeliveryOrderCycleReport,
+    DeliveryRegionsReport, IngredientEntryDto, StoreReportItemDto, StoreReportPreset,
 };
 use crate::xml::response::{
     BalanceCounteragent, BalanceStore, EgaisMarksList, OlapColumns, OlapReportRequest,
@@ -28,98 +33,101 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/v2/reports/balance/counteragents`
     ///
     /// # Параметры
-    /// - `timestamp`: учетная дата-время отчета в формате yyyy-MM-dd'T'HH:mm:ss (обязательный)
-    /// - `account`: ID счета для фильтрации (необязательный, можно указать несколько)
-    /// - `counteragent`: ID контрагента для фильтрации (необязательный, можно указать несколько)
-    /// - `department`: ID подразделения для фильтрации (необязательный, можно указать несколько)
+    /// - `query`: учетная дата-время и фильтры по счетам, контрагентам и подразделениям (см. [`CounteragentBalanceQuery`])
     ///
     /// # Что в ответе
     /// Возвращает денежные балансы по указанным счетам, контрагентам и подразделениям на заданную учетную дату-время.
-    pub async fn get_balance_counteragents(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_balance_counteragents",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn balance_counteragents(
         &self,
-        timestamp: &str,
-        accounts: Option<&[&str]>,
-        counteragents: Option<&[&str]>,
-        departments: Option<&[&str]>,
+        query: CounteragentBalanceQuery,
     ) -> Result<Vec<BalanceCounteragent>> {
-        let mut params = vec![("timestamp", timestamp)];
-
-        if let Some(accs) = accounts {
-            for account in accs {
-                params.push(("account", account));
-            }
-        }
-
-        if let Some(ctrs) = counteragents {
-            for counteragent in ctrs {
-                params.push(("counteragent", counteragent));
-            }
-        }
-
-        if let Some(depts) = departments {
-            for department in depts {
-                params.push(("department", department));
-            }
-        }
+        self.client
+            .require_version(ServerVersion::new(5, 2, 0))
+            .await?;
 
         let response_json = self
             .client
-            .get_with_params("v2/reports/balance/counteragents", &params)
+            .get_with_params("v2/reports/balance/counteragents", &query.params().pairs())
             .await?;
 
         let balances: Vec<BalanceCounteragent> = serde_json::from_str(&response_json)?;
         Ok(balances)
     }
 
+    /// Балансы по счетам, контрагентам и подразделениям с позиционными параметрами
+    #[deprecated(
+        note = "используйте `balance_counteragents(CounteragentBalanceQuery::at(timestamp)...)`"
+    )]
+    pub async fn get_balance_counteragents(
+        &self,
+        timestamp: impl Into<DateParam>,
+        accounts: Option<&[&str]>,
+        counteragents: Option<&[&str]>,
+        departments: Option<&[&str]>,
+    ) -> Result<Vec<BalanceCounteragent>> {
+        self.balance_counteragents(CounteragentBalanceQuery {
+            timestamp: timestamp.into(),
+            accounts: owned(accounts),
+            counteragents: owned(counteragents),
+            departments: owned(departments),
+        })
+        .await
+    }
+
     /// Остатки на складах
     ///
     /// Версия iiko: 5.2
     /// Endpoint: GET `/v2/reports/balance/stores`
     ///
     /// # Параметры
-    /// - `timestamp`: учетная дата-время отчета в формате yyyy-MM-dd'T'HH:mm:ss (обязательный)
-    /// - `department`: ID подразделения для фильтрации (необязательный, можно указать несколько)
-    /// - `store`: ID склада для фильтрации (необязательный, можно указать несколько)
-    /// - `product`: ID элемента номенклатуры для фильтрации (необязательный, можно указать несколько)
+    /// - `query`: учетная дата-время и фильтры по подразделениям, складам и товарам (см. [`StoreBalanceQuery`])
     ///
     /// # Что в ответе
     /// Возвращает количественные (amount) и денежные (sum) остатки товаров (product) на складах (store) на заданную учетную дату-время.
-    pub async fn get_balance_stores(
-        &self,
-        timestamp: &str,
-        departments: Option<&[&str]>,
-        stores: Option<&[&str]>,
-        products: Option<&[&str]>,
-    ) -> Result<Vec<BalanceStore>> {
-        let mut params = vec![("timestamp", timestamp)];
-
-        if let Some(depts) = departments {
-            for department in depts {
-                params.push(("department", department));
-            }
-        }
-
-        if let Some(strs) = stores {
-            for store in strs {
-                params.push(("store", store));
-            }
-        }
-
-        if let Some(prods) = products {
-            for product in prods {
-                params.push(("product", product));
-            }
-        }
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_balance_stores", level = "debug", skip_all)
+    )]
+    pub async fn balance_stores(&self, query: StoreBalanceQuery) -> Result<Vec<BalanceStore>> {
+        self.client
+            .require_version(ServerVersion::new(5, 2, 0))
+            .await?;
 
         let response_json = self
             .client
-            .get_with_params("v2/reports/balance/stores", &params)
+            .get_with_params("v2/reports/balance/stores", &query.params().pairs())
             .await?;
 
         let balances: Vec<BalanceStore> = serde_json::from_str(&response_json)?;
         Ok(balances)
     }
 
+    /// Остатки на складах с позиционными параметрами
+    #[deprecated(note = "используйте `balance_stores(StoreBalanceQuery::at(timestamp)...)`")]
+    pub async fn get_balance_stores(
+        &self,
+        timestamp: impl Into<DateParam>,
+        departments: Option<&[&str]>,
+        stores: Option<&[&str]>,
+        products: Option<&[&str]>,
+    ) -> Result<Vec<BalanceStore>> {
+        self.balance_stores(StoreBalanceQuery {
+            timestamp: timestamp.into(),
+            departments: owned(departments),
+            stores: owned(stores),
+            products: owned(products),
+        })
+        .await
+    }
+
     /// Отчет по балансу на 3 регистре ЕГАИС (акцизные марки)
     ///
     /// Получение обновлений состояния на 3 регистре
@@ -133,11 +141,19 @@ impl<'a> ReportsEndpoint<'a> {
     ///
     /// # Что в ответе
     /// Возвращает данные по балансу акцизных марок на 3 регистре ЕГАИС.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_egais_marks_list", level = "debug", skip_all)
+    )]
     pub async fn get_egais_marks_list(
         &self,
         fs_rar_ids: Option<&[&str]>,
         revision_from: Option<i64>,
     ) -> Result<EgaisMarksList> {
+        self.client
+            .require_version(ServerVersion::new(7, 4, 0))
+            .await?;
+
         let mut params: Vec<(&str, &str)> = Vec::new();
         let revision_str;
 
@@ -172,6 +188,10 @@ impl<'a> ReportsEndpoint<'a> {
     /// # Что в ответе
     /// Возвращает JSON структуру списка полей с информацией по возможностям фильтрации, агрегации и группировки.
     /// Устаревшие поля (deprecated) не выводятся.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_olap_columns", level = "debug", skip_all)
+    )]
     pub async fn get_olap_columns(&self, report_type: OlapReportType) -> Result<OlapColumns> {
         let report_type_str = match report_type {
             OlapReportType::Sales => "SALES",
@@ -206,31 +226,76 @@ impl<'a> ReportsEndpoint<'a> {
     /// - Рекомендуется использовать не более 7 полей
     /// - Используйте `build_summary=false` если не нужны общие результаты
     /// - Начиная с версии 5.5, каждый OLAP-запрос должен содержать фильтр по дате
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_olap_report", level = "debug", skip_all)
+    )]
     pub async fn get_olap_report(&self, request: OlapReportRequest) -> Result<OlapReportResponse> {
         let json_body = serde_json::to_string(&request)?;
 
         let response_json = self
             .client
-            .post_json("v2/reports/olap", &json_body, &[])
+            .post_json_query("v2/reports/olap", &json_body, &[])
             .await?;
 
         let report: OlapReportResponse = serde_json::from_str(&response_json)?;
         Ok(report)
     }
 
+    /// Получить строки OLAP-отчета потоком, не загружая весь ответ в память
+    ///
+    /// Версия iiko: 4.1
+    /// Endpoint: POST `/v2/reports/olap`
+    ///
+    /// # Параметры
+    /// - `request`: Запрос на получение OLAP-отчета
+    ///
+    /// # Что в ответе
+    /// Поток строк `data` в порядке ответа сервера. Итоги (`summary`) не возвращаются,
+    /// поэтому для больших отчетов стоит передавать `build_summary = false`.
+    ///
+    /// # Важно
+    /// - Пока поток не дочитан или не удален, остальные запросы клиента ждут в очереди
+    /// - Повторы по `RetryPolicy` выполняются только до начала ответа; обрыв соединения
+    ///   во время чтения возвращается из потока как ошибка
+    ///
+    /// # Пример
+    /// ```no_run
+    /// # async fn example(
+    /// #     client: iiko_server_api_sdk::IikoClient,
+    /// #     request: iiko_server_api_sdk::OlapReportRequest,
+    /// # ) -> iiko_server_api_sdk::Result<()> {
+    /// use futures_util::TryStreamExt;
+    ///
+    /// let mut rows = client.reports().stream_olap_report(request).await?;
+    /// while let Some(row) = rows.try_next().await? {
+    ///     println!("{:?}", row.get("DishSumInt"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.stream_olap_report", level = "debug", skip_all)
+    )]
+    pub async fn stream_olap_report(&self, request: OlapReportRequest) -> Result<OlapRowStream> {
+        let json_body = serde_json::to_string(&request)?;
+
+        let body = self
+            .client
+            .post_json_query_stream("v2/reports/olap", &json_body, &[])
+            .await?;
+
+        Ok(OlapRowStream::new(body))
+    }
+
     /// Получить данные OLAP-отчета (старый API)
     ///
     /// Версия iiko: 3.9
     /// Endpoint: GET `/reports/olap`
     ///
     /// # Параметры
-    /// - `report`: Тип отчета (SALES, TRANSACTIONS, DELIVERIES, STOCK)
-    /// - `from`: Начальная дата в формате DD.MM.YYYY (например, "01.12.2014")
-    /// - `to`: Конечная дата в формате DD.MM.YYYY (например, "18.12.2014")
-    /// - `summary`: Вычислять ли итоговые значения (по умолчанию true, с версии 9.1.2 по умолчанию false)
-    /// - `group_row`: Поля для группировки по строкам (можно указать несколько)
-    /// - `group_col`: Поля для группировки по столбцам (можно указать несколько, необязательно)
-    /// - `agr`: Поля для агрегации (можно указать несколько)
+    /// - `query`: тип отчета, период и поля группировки и агрегации (см. [`OlapReportV1Query`])
     ///
     /// # Что в ответе
     /// Возвращает данные отчета с промежуточными и общими итогами (если summary = true).
@@ -243,62 +308,61 @@ impl<'a> ReportsEndpoint<'a> {
     ///
     /// # Пример
     /// ```no_run
-    /// use iiko_server_api_sdk::{IikoClient, OlapReportTypeV1};
+    /// use iiko_server_api_sdk::{IikoClient, OlapReportTypeV1, OlapReportV1Query};
     ///
-    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// let client = IikoClient::new(...)?;
+    /// # async fn example(client: IikoClient) -> Result<(), Box<dyn std::error::Error>> {
     /// let reports = client.reports();
     ///
-    /// let result = reports.get_olap_report_v1(
-    ///     OlapReportTypeV1::Sales,
-    ///     "01.12.2014",
-    ///     "18.12.2014",
-    ///     Some(true),
-    ///     Some(&["WaiterName", "OpenTime"]),
-    ///     None,
-    ///     Some(&["fullSum", "OrderNum"]),
-    /// ).await?;
+    /// let result = reports
+    ///     .olap_report_v1(
+    ///         OlapReportV1Query::new(OlapReportTypeV1::Sales, "01.12.2014", "18.12.2014")
+    ///             .summary(true)
+    ///             .group_rows(["WaiterName", "OpenTime"])
+    ///             .aggregates(["fullSum", "OrderNum"]),
+    ///     )
+    ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_olap_report_v1", level = "debug", skip_all)
+    )]
+    pub async fn olap_report_v1(&self, query: OlapReportV1Query) -> Result<OlapReportResponse> {
+        let response_json = self
+            .client
+            .get_with_params("reports/olap", &query.params().pairs())
+            .await?;
+
+        let report: OlapReportResponse = serde_json::from_str(&response_json)?;
+        Ok(report)
+    }
+
+    /// Получить данные OLAP-отчета (старый API) с позиционными параметрами
+    #[deprecated(
+        note = "используйте `olap_report_v1(OlapReportV1Query::new(report, from, to)...)`"
+    )]
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_olap_report_v1(
         &self,
         report: OlapReportTypeV1,
-        from: &str,
-        to: &str,
+        from: impl Into<DateParam>,
+        to: impl Into<DateParam>,
         summary: Option<bool>,
         group_row: Option<&[&str]>,
         group_col: Option<&[&str]>,
         agr: Option<&[&str]>,
     ) -> Result<OlapReportResponse> {
-        let mut params = vec![("report", report.as_str()), ("from", from), ("to", to)];
-
-        if let Some(sum) = summary {
-            params.push(("summary", if sum { "true" } else { "false" }));
-        }
-
-        if let Some(rows) = group_row {
-            for row in rows {
-                params.push(("groupRow", row));
-            }
-        }
-
-        if let Some(cols) = group_col {
-            for col in cols {
-                params.push(("groupCol", col));
-            }
-        }
-
-        if let Some(agr_fields) = agr {
-            for field in agr_fields {
-                params.push(("agr", field));
-            }
-        }
-
-        let response_json = self.client.get_with_params("reports/olap", &params).await?;
-
-        let report: OlapReportResponse = serde_json::from_str(&response_json)?;
-        Ok(report)
+        self.olap_report_v1(OlapReportV1Query {
+            report,
+            from: from.into(),
+            to: to.into(),
+            summary,
+            group_rows: owned(group_row),
+            group_cols: owned(group_col),
+            aggregates: owned(agr),
+        })
+        .await
     }
 
     // ============================================================================
@@ -311,64 +375,91 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/delivery/consolidated`
     ///
     /// # Параметры
-    /// - `department`: Подразделения (код или ID) в формате `{code="005"}` или `{id="guid"}` (можно указать несколько)
-    /// - `date_from`: Дата начала отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `date_to`: Дата окончания отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `writeoff_accounts`: Список счетов списания (код или ID) в формате `{code="5.14"}` (можно указать несколько)
+    /// - `query`: период, подразделения и счета списания (см. [`DeliveryConsolidatedQuery`])
     ///
     /// # Что в ответе
     /// Возвращает сводный отчет по доставке с метриками по датам.
-    pub async fn get_delivery_consolidated(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_delivery_consolidated",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn delivery_consolidated(
         &self,
-        date_from: &str,
-        date_to: &str,
-        departments: Option<&[&str]>,
-        writeoff_accounts: Option<&[&str]>,
+        query: DeliveryConsolidatedQuery,
     ) -> Result<DeliveryConsolidatedReport> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
-
-        if let Some(depts) = departments {
-            for dept in depts {
-                params.push(("department", dept));
-            }
-        }
-
-        if let Some(accounts) = writeoff_accounts {
-            for account in accounts {
-                params.push(("writeoffAccounts", account));
-            }
-        }
-
         let response_xml = self
             .client
-            .get_with_params("reports/delivery/consolidated", &params)
+            .get_with_params("reports/delivery/consolidated", &query.params().pairs())
             .await?;
 
         let report: DeliveryConsolidatedReport = from_str(&response_xml)?;
         Ok(report)
     }
 
+    /// Сводный отчет по доставке с позиционными параметрами
+    #[deprecated(
+        note = "используйте `delivery_consolidated(DeliveryConsolidatedQuery::between(from, to)...)`"
+    )]
+    pub async fn get_delivery_consolidated(
+        &self,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
+        departments: Option<&[&str]>,
+        writeoff_accounts: Option<&[&str]>,
+    ) -> Result<DeliveryConsolidatedReport> {
+        self.delivery_consolidated(DeliveryConsolidatedQuery {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: owned(departments),
+            writeoff_accounts: owned(writeoff_accounts),
+        })
+        .await
+    }
+
     /// Отчет по курьерам
     ///
     /// Версия iiko: неизвестна
     /// Endpoint: GET `/reports/delivery/couriers`
     ///
     /// # Параметры
-    /// - `department`: Подразделения (код или ID) в формате `{code="005"}` или `{id="guid"}` (можно указать несколько)
-    /// - `date_from`: Дата начала отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `date_to`: Дата окончания отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `target_common_time`: Целевое значение общего времени в минутах (по умолчанию 30)
-    /// - `target_on_the_way_time`: Целевое значение времени в пути в минутах (по умолчанию 0)
-    /// - `target_doubled_orders`: Целевое количество сдвоенных заказов за день (по умолчанию 0)
-    /// - `target_tripled_orders`: Целевое количество строенных заказов за день (по умолчанию 0)
-    /// - `target_total_orders`: Целевое количество заказов за день (по умолчанию 0)
+    /// - `query`: период, подразделения и целевые значения метрик (см. [`DeliveryCouriersQuery`])
     ///
     /// # Что в ответе
     /// Возвращает отчет по курьерам с метриками производительности.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_delivery_couriers",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn delivery_couriers(
+        &self,
+        query: DeliveryCouriersQuery,
+    ) -> Result<DeliveryCouriersReport> {
+        let response_xml = self
+            .client
+            .get_with_params("reports/delivery/couriers", &query.params().pairs())
+            .await?;
+
+        let report: DeliveryCouriersReport = from_str(&response_xml)?;
+        Ok(report)
+    }
+
+    /// Отчет по курьерам с позиционными параметрами
+    #[deprecated(
+        note = "используйте `delivery_couriers(DeliveryCouriersQuery::between(from, to)...)`"
+    )]
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_delivery_couriers(
         &self,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         departments: Option<&[&str]>,
         target_common_time: Option<i32>,
         target_on_the_way_time: Option<i32>,
@@ -376,52 +467,17 @@ impl<'a> ReportsEndpoint<'a> {
         target_tripled_orders: Option<i32>,
         target_total_orders: Option<i32>,
     ) -> Result<DeliveryCouriersReport> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
-
-        if let Some(depts) = departments {
-            for dept in depts {
-                params.push(("department", dept));
-            }
-        }
-
-        let target_common_time_str;
-        let target_on_the_way_time_str;
-        let target_doubled_orders_str;
-        let target_tripled_orders_str;
-        let target_total_orders_str;
-
-        if let Some(time) = target_common_time {
-            target_common_time_str = time.to_string();
-            params.push(("targetCommonTime", &target_common_time_str));
-        }
-
-        if let Some(time) = target_on_the_way_time {
-            target_on_the_way_time_str = time.to_string();
-            params.push(("targetOnTheWayTime", &target_on_the_way_time_str));
-        }
-
-        if let Some(orders) = target_doubled_orders {
-            target_doubled_orders_str = orders.to_string();
-            params.push(("targetDoubledOrders", &target_doubled_orders_str));
-        }
-
-        if let Some(orders) = target_tripled_orders {
-            target_tripled_orders_str = orders.to_string();
-            params.push(("targetTripledOrders", &target_tripled_orders_str));
-        }
-
-        if let Some(orders) = target_total_orders {
-            target_total_orders_str = orders.to_string();
-            params.push(("targetTotalOrders", &target_total_orders_str));
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("reports/delivery/couriers", &params)
-            .await?;
-
-        let report: DeliveryCouriersReport = from_str(&response_xml)?;
-        Ok(report)
+        self.delivery_couriers(DeliveryCouriersQuery {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: owned(departments),
+            target_common_time,
+            target_on_the_way_time,
+            target_doubled_orders,
+            target_tripled_orders,
+            target_total_orders,
+        })
+        .await
     }
 
     /// Отчет по циклу заказа
@@ -430,22 +486,40 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/delivery/orderCycle`
     ///
     /// # Параметры
-    /// - `department`: Подразделения (код или ID) в формате `{code="005"}` или `{id="guid"}` (можно указать несколько)
-    /// - `date_from`: Дата начала отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `date_to`: Дата окончания отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `target_pizza_time`: Целевое значение времени на столе Пицца в минутах (по умолчанию 0)
-    /// - `target_cutting_time`: Целевое значение времени на столе нарезки в минутах (по умолчанию 0)
-    /// - `target_on_shelf_time`: Целевое значение времени на стеллаже оперативности в минутах (по умолчанию 0)
-    /// - `target_in_restaurant_time`: Целевое значение времени в ресторане в минутах (по умолчанию 0)
-    /// - `target_on_the_way_time`: Целевое значение времени в пути в минутах (по умолчанию 0)
-    /// - `target_total_time`: Целевое значение общего времени доставки в минутах (по умолчанию 0)
+    /// - `query`: период, подразделения и целевые значения времени этапов (см. [`DeliveryOrderCycleQuery`])
     ///
     /// # Что в ответе
     /// Возвращает отчет по циклу заказа с временными метриками.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_delivery_order_cycle",
+            level = "debug",
+            skip_all
+        )
+    )]
+    pub async fn delivery_order_cycle(
+        &self,
+        query: DeliveryOrderCycleQuery,
+    ) -> Result<DeliveryOrderCycleReport> {
+        let response_xml = self
+            .client
+            .get_with_params("reports/delivery/orderCycle", &query.params().pairs())
+            .await?;
+
+        let report: DeliveryOrderCycleReport = from_str(&response_xml)?;
+        Ok(report)
+    }
+
+    /// Отчет по циклу заказа с позиционными параметрами
+    #[deprecated(
+        note = "используйте `delivery_order_cycle(DeliveryOrderCycleQuery::between(from, to)...)`"
+    )]
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_delivery_order_cycle(
         &self,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         departments: Option<&[&str]>,
         target_pizza_time: Option<i32>,
         target_cutting_time: Option<i32>,
@@ -454,58 +528,18 @@ impl<'a> ReportsEndpoint<'a> {
         target_on_the_way_time: Option<i32>,
         target_total_time: Option<i32>,
     ) -> Result<DeliveryOrderCycleReport> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
-
-        if let Some(depts) = departments {
-            for dept in depts {
-                params.push(("department", dept));
-            }
-        }
-
-        let target_pizza_time_str;
-        let target_cutting_time_str;
-        let target_on_shelf_time_str;
-        let target_in_restaurant_time_str;
-        let target_on_the_way_time_str;
-        let target_total_time_str;
-
-        if let Some(time) = target_pizza_time {
-            target_pizza_time_str = time.to_string();
-            params.push(("targetPizzaTime", &target_pizza_time_str));
-        }
-
-        if let Some(time) = target_cutting_time {
-            target_cutting_time_str = time.to_string();
-            params.push(("targetCuttingTime", &target_cutting_time_str));
-        }
-
-        if let Some(time) = target_on_shelf_time {
-            target_on_shelf_time_str = time.to_string();
-            params.push(("targetOnShelfTime", &target_on_shelf_time_str));
-        }
-
-        if let Some(time) = target_in_restaurant_time {
-            target_in_restaurant_time_str = time.to_string();
-            params.push(("targetInRestaurantTime", &target_in_restaurant_time_str));
-        }
-
-        if let Some(time) = target_on_the_way_time {
-            target_on_the_way_time_str = time.to_string();
-            params.push(("targetOnTheWayTime", &target_on_the_way_time_str));
-        }
-
-        if let Some(time) = target_total_time {
-            target_total_time_str = time.to_string();
-            params.push(("targetTotalTime", &target_total_time_str));
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("reports/delivery/orderCycle", &params)
-            .await?;
-
-        let report: DeliveryOrderCycleReport = from_str(&response_xml)?;
-        Ok(report)
+        self.delivery_order_cycle(DeliveryOrderCycleQuery {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: owned(departments),
+            target_pizza_time,
+            target_cutting_time,
+            target_on_shelf_time,
+            target_in_restaurant_time,
+            target_on_the_way_time,
+            target_total_time,
+        })
+        .await
     }
 
     /// Получасовой детальный отчет по доставке
@@ -520,13 +554,28 @@ impl<'a> ReportsEndpoint<'a> {
     ///
     /// # Что в ответе
     /// Возвращает получасовой детальный отчет по доставке с разбивкой по типам доставки.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_delivery_half_hour_detailed",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_delivery_half_hour_detailed(
         &self,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         departments: Option<&[&str]>,
     ) -> Result<DeliveryHalfHourDetailedReport> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
+        let (date_from, date_to) = (
+            DateFormat::Day.format(date_from),
+            DateFormat::Day.format(date_to),
+        );
+        let mut params = vec![
+            ("dateFrom", date_from.as_str()),
+            ("dateTo", date_to.as_str()),
+        ];
 
         if let Some(depts) = departments {
             for dept in depts {
@@ -555,13 +604,24 @@ impl<'a> ReportsEndpoint<'a> {
     ///
     /// # Что в ответе
     /// Возвращает отчет по регионам доставки с метриками по каждому региону.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_delivery_regions", level = "debug", skip_all)
+    )]
     pub async fn get_delivery_regions(
         &self,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         departments: Option<&[&str]>,
     ) -> Result<DeliveryRegionsReport> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
+        let (date_from, date_to) = (
+            DateFormat::Day.format(date_from),
+            DateFormat::Day.format(date_to),
+        );
+        let mut params = vec![
+            ("dateFrom", date_from.as_str()),
+            ("dateTo", date_to.as_str()),
+        ];
 
         if let Some(depts) = departments {
             for dept in depts {
@@ -584,41 +644,47 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/delivery/loyalty`
     ///
     /// # Параметры
-    /// - `department`: Подразделения (код или ID) в формате `{code="005"}` или `{id="guid"}` (можно указать несколько)
-    /// - `date_from`: Дата начала отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `date_to`: Дата окончания отчета в формате DD.MM.YYYY или YYYY-MM-DD
-    /// - `metric_type`: Тип метрики (AVERAGE, MINIMUM, MAXIMUM)
+    /// - `query`: период, подразделения и тип метрики (см. [`DeliveryLoyaltyQuery`])
     ///
     /// # Что в ответе
     /// Возвращает отчет по лояльности доставки с метриками по датам и регионам.
-    pub async fn get_delivery_loyalty(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_delivery_loyalty", level = "debug", skip_all)
+    )]
+    pub async fn delivery_loyalty(
         &self,
-        date_from: &str,
-        date_to: &str,
-        departments: Option<&[&str]>,
-        metric_type: Option<&str>,
+        query: DeliveryLoyaltyQuery,
     ) -> Result<DeliveryLoyaltyReport> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
-
-        if let Some(depts) = departments {
-            for dept in depts {
-                params.push(("department", dept));
-            }
-        }
-
-        if let Some(mt) = metric_type {
-            params.push(("metricType", mt));
-        }
-
         let response_xml = self
             .client
-            .get_with_params("reports/delivery/loyalty", &params)
+            .get_with_params("reports/delivery/loyalty", &query.params().pairs())
             .await?;
 
         let report: DeliveryLoyaltyReport = from_str(&response_xml)?;
         Ok(report)
     }
 
+    /// Отчет по лояльности доставки с позиционными параметрами
+    #[deprecated(
+        note = "используйте `delivery_loyalty(DeliveryLoyaltyQuery::between(from, to)...)`"
+    )]
+    pub async fn get_delivery_loyalty(
+        &self,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
+        departments: Option<&[&str]>,
+        metric_type: Option<&str>,
+    ) -> Result<DeliveryLoyaltyReport> {
+        self.delivery_loyalty(DeliveryLoyaltyQuery {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: owned(departments),
+            metric_type: metric_type.map(str::to_string),
+        })
+        .await
+    }
+
     // ============================================================================
     // Отчеты по складским операциям и другие отчеты
     // ============================================================================
@@ -629,68 +695,51 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/storeOperations`
     ///
     /// # Параметры
-    /// - `date_from`: Начальная дата в формате DD.MM.YYYY
-    /// - `date_to`: Конечная дата в формате DD.MM.YYYY
-    /// - `stores`: Список складов (GUID) (можно указать несколько)
-    /// - `document_types`: Типы документов (можно указать несколько)
-    /// - `product_detalization`: Если true, отчет включает информацию по товарам, но не включает дату. Если false - отчет включает каждый документ одной строкой
-    /// - `show_cost_corrections`: Включать ли коррекции себестоимости
-    /// - `preset_id`: ID преднастроенного отчета (GUID). Если указан, все настройки, кроме дат, игнорируются
+    /// - `query`: период, склады, типы документов и настройки отчета (см. [`StoreOperationsQuery`])
     ///
     /// # Что в ответе
     /// Возвращает список элементов отчета по складским операциям.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_store_operations", level = "debug", skip_all)
+    )]
+    pub async fn store_operations(
+        &self,
+        query: StoreOperationsQuery,
+    ) -> Result<Vec<StoreReportItemDto>> {
+        let response_xml = self
+            .client
+            .get_with_params("reports/storeOperations", &query.params().pairs())
+            .await?;
+
+        deserialize_list(&response_xml, "storeReportItemDtoes", "storeReportItemDto")
+    }
+
+    /// Отчет по складским операциям с позиционными параметрами
+    #[deprecated(
+        note = "используйте `store_operations(StoreOperationsQuery::between(from, to)...)`"
+    )]
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_store_operations(
         &self,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         stores: Option<&[&str]>,
         document_types: Option<&[&str]>,
         product_detalization: Option<bool>,
         show_cost_corrections: Option<bool>,
         preset_id: Option<&str>,
     ) -> Result<Vec<StoreReportItemDto>> {
-        let mut params = vec![("dateFrom", date_from), ("dateTo", date_to)];
-
-        if let Some(strs) = stores {
-            for store in strs {
-                params.push(("stores", store));
-            }
-        }
-
-        if let Some(doc_types) = document_types {
-            for doc_type in doc_types {
-                params.push(("documentTypes", doc_type));
-            }
-        }
-
-        if let Some(det) = product_detalization {
-            params.push(("productDetalization", if det { "true" } else { "false" }));
-        }
-
-        if let Some(corr) = show_cost_corrections {
-            params.push(("showCostCorrections", if corr { "true" } else { "false" }));
-        }
-
-        if let Some(preset) = preset_id {
-            params.push(("presetId", preset));
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("reports/storeOperations", &params)
-            .await?;
-
-        // XML может быть списком элементов или одним элементом
-        let items: Vec<StoreReportItemDto> =
-            match from_str::<Vec<StoreReportItemDto>>(&response_xml) {
-                Ok(list) => list,
-                Err(_) => {
-                    // Пробуем как один элемент
-                    let item: StoreReportItemDto = from_str(&response_xml)?;
-                    vec![item]
-                }
-            };
-        Ok(items)
+        self.store_operations(StoreOperationsQuery {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            stores: owned(stores),
+            document_types: owned(document_types),
+            product_detalization,
+            show_cost_corrections,
+            preset: preset_id.map(str::to_string),
+        })
+        .await
     }
 
     /// Пресеты отчетов по складским операциям
@@ -700,20 +749,18 @@ impl<'a> ReportsEndpoint<'a> {
     ///
     /// # Что в ответе
     /// Возвращает список пресетов отчетов по складским операциям.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_store_report_presets",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_store_report_presets(&self) -> Result<Vec<StoreReportPreset>> {
         let response_xml = self.client.get("reports/storeReportPresets").await?;
 
-        // XML может быть списком элементов или одним элементом
-        let presets: Vec<StoreReportPreset> =
-            match from_str::<Vec<StoreReportPreset>>(&response_xml) {
-                Ok(list) => list,
-                Err(_) => {
-                    // Пробуем как один элемент
-                    let preset: StoreReportPreset = from_str(&response_xml)?;
-                    vec![preset]
-                }
-            };
-        Ok(presets)
+        deserialize_list(&response_xml, "storeReportPresets", "storeReportPreset")
     }
 
     /// Расход продуктов по продажам
@@ -722,11 +769,7 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/productExpense`
     ///
     /// # Параметры запроса
-    /// - `department`: Подразделение (GUID)
-    /// - `date_from`: Начальная дата в формате DD.MM.YYYY
-    /// - `date_to`: Конечная дата в формате DD.MM.YYYY
-    /// - `hour_from`: Час начала интервала выборки в сутках (по умолчанию -1, все время)
-    /// - `hour_to`: Час окончания интервала выборки в сутках (по умолчанию -1, все время)
+    /// - `query`: подразделение, период и часы выборки (см. [`ProductExpenseQuery`])
     ///
     /// # Что в ответе
     /// Структура dayDishValue (см. XSD Расход продуктов по продажам)
@@ -734,61 +777,39 @@ impl<'a> ReportsEndpoint<'a> {
     /// - `productId`: ID продукта
     /// - `productName`: Название продукта
     /// - `value`: Значение (количество) в формате decimal
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_product_expense", level = "debug", skip_all)
+    )]
+    pub async fn product_expense(&self, query: ProductExpenseQuery) -> Result<Vec<DayDishValue>> {
+        let response_xml = self
+            .client
+            .get_with_params("reports/productExpense", &query.params().pairs())
+            .await?;
+
+        deserialize_list(&response_xml, "dayDishValues", "dayDishValue")
+    }
+
+    /// Расход продуктов по продажам с позиционными параметрами
+    #[deprecated(
+        note = "используйте `product_expense(ProductExpenseQuery::new(department, from, to)...)`"
+    )]
     pub async fn get_product_expense(
         &self,
         department: &str,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         hour_from: Option<i32>,
         hour_to: Option<i32>,
     ) -> Result<Vec<DayDishValue>> {
-        let mut params = vec![
-            ("department", department),
-            ("dateFrom", date_from),
-            ("dateTo", date_to),
-        ];
-
-        let hour_from_str;
-        let hour_to_str;
-
-        if let Some(hf) = hour_from {
-            hour_from_str = hf.to_string();
-            params.push(("hourFrom", &hour_from_str));
-        }
-
-        if let Some(ht) = hour_to {
-            hour_to_str = ht.to_string();
-            params.push(("hourTo", &hour_to_str));
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("reports/productExpense", &params)
-            .await?;
-
-        // Логируем сырой XML-ответ для отладки
-        eprintln!(
-            "iiko productExpense raw XML response: department={}, date_from={}, date_to={}, hour_from={:?}, hour_to={:?}, body={}",
-            department, date_from, date_to, hour_from, hour_to, response_xml
-        );
-
-        // XML может быть:
-        // - списком элементов внутри обертки <dayDishValues>
-        // - списком элементов без обертки
-        // - одним элементом <dayDishValue>
-        let items: Vec<DayDishValue> =
-            // Пытаемся сначала распарсить обертку <dayDishValues>...</dayDishValues>
-            if let Ok(wrapper) = from_str::<DayDishValues>(&response_xml) {
-                wrapper.items
-            } else if let Ok(list) = from_str::<Vec<DayDishValue>>(&response_xml) {
-                // Падаем обратно на "голый" список элементов
-                list
-            } else {
-                // И в самом крайнем случае пробуем один элемент
-                let item: DayDishValue = from_str(&response_xml)?;
-                vec![item]
-            };
-        Ok(items)
+        self.product_expense(ProductExpenseQuery {
+            department: department.to_string(),
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            hour_from,
+            hour_to,
+        })
+        .await
     }
 
     /// Отчет по выручке
@@ -797,68 +818,46 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/sales`
     ///
     /// # Параметры
-    /// - `department`: Подразделение (GUID)
-    /// - `date_from`: Начальная дата в формате DD.MM.YYYY
-    /// - `date_to`: Конечная дата в формате DD.MM.YYYY
-    /// - `hour_from`: Час начала интервала выборки в сутках (по умолчанию -1, все время)
-    /// - `hour_to`: Час окончания интервала выборки в сутках (по умолчанию -1, все время)
-    /// - `dish_details`: Включать ли разбивку по блюдам (по умолчанию false)
-    /// - `all_revenue`: Фильтрация по типам оплат (true - все типы, false - только выручка, по умолчанию true)
+    /// - `query`: подразделение, период, часы выборки и детализация (см. [`SalesQuery`])
     ///
     /// # Что в ответе
     /// Возвращает список элементов отчета по выручке.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_sales", level = "debug", skip_all)
+    )]
+    pub async fn sales(&self, query: SalesQuery) -> Result<Vec<DayDishValue>> {
+        let response_xml = self
+            .client
+            .get_with_params("reports/sales", &query.params().pairs())
+            .await?;
+
+        deserialize_list(&response_xml, "dayDishValues", "dayDishValue")
+    }
+
+    /// Отчет по выручке с позиционными параметрами
+    #[deprecated(note = "используйте `sales(SalesQuery::new(department, from, to)...)`")]
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_sales(
         &self,
         department: &str,
-        date_from: &str,
-        date_to: &str,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
         hour_from: Option<i32>,
         hour_to: Option<i32>,
         dish_details: Option<bool>,
         all_revenue: Option<bool>,
     ) -> Result<Vec<DayDishValue>> {
-        let mut params = vec![
-            ("department", department),
-            ("dateFrom", date_from),
-            ("dateTo", date_to),
-        ];
-
-        let hour_from_str;
-        let hour_to_str;
-
-        if let Some(hf) = hour_from {
-            hour_from_str = hf.to_string();
-            params.push(("hourFrom", &hour_from_str));
-        }
-
-        if let Some(ht) = hour_to {
-            hour_to_str = ht.to_string();
-            params.push(("hourTo", &hour_to_str));
-        }
-
-        if let Some(dd) = dish_details {
-            params.push(("dishDetails", if dd { "true" } else { "false" }));
-        }
-
-        if let Some(ar) = all_revenue {
-            params.push(("allRevenue", if ar { "true" } else { "false" }));
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("reports/sales", &params)
-            .await?;
-
-        // XML может быть списком элементов или одним элементом
-        let items: Vec<DayDishValue> = match from_str::<Vec<DayDishValue>>(&response_xml) {
-            Ok(list) => list,
-            Err(_) => {
-                // Пробуем как один элемент
-                let item: DayDishValue = from_str(&response_xml)?;
-                vec![item]
-            }
-        };
-        Ok(items)
+        self.sales(SalesQuery {
+            department: department.to_string(),
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            hour_from,
+            hour_to,
+            dish_details,
+            all_revenue,
+        })
+        .await
     }
 
     /// План по выручке за день
@@ -873,16 +872,29 @@ impl<'a> ReportsEndpoint<'a> {
     ///
     /// # Что в ответе
     /// Возвращает список элементов плана по выручке за день.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "iiko.reports.get_monthly_income_plan",
+            level = "debug",
+            skip_all
+        )
+    )]
     pub async fn get_monthly_income_plan(
         &self,
-        department: &str,
-        date_from: &str,
-        date_to: &str,
+        department: impl Into<DepartmentId>,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
     ) -> Result<Vec<BudgetPlanItemDto>> {
+        let department = department.into().to_string();
+        let (date_from, date_to) = (
+            DateFormat::Day.format(date_from),
+            DateFormat::Day.format(date_to),
+        );
         let params = vec![
-            ("department", department),
-            ("dateFrom", date_from),
-            ("dateTo", date_to),
+            ("department", department.as_str()),
+            ("dateFrom", date_from.as_str()),
+            ("dateTo", date_to.as_str()),
         ];
 
         let response_xml = self
@@ -890,17 +902,7 @@ impl<'a> ReportsEndpoint<'a> {
             .get_with_params("reports/monthlyIncomePlan", &params)
             .await?;
 
-        // XML может быть списком элементов или одним элементом
-        let items: Vec<BudgetPlanItemDto> = match from_str::<Vec<BudgetPlanItemDto>>(&response_xml)
-        {
-            Ok(list) => list,
-            Err(_) => {
-                // Пробуем как один элемент
-                let item: BudgetPlanItemDto = from_str(&response_xml)?;
-                vec![item]
-            }
-        };
-        Ok(items)
+        deserialize_list(&response_xml, "budgetPlanItemDtoes", "budgetPlanItemDto")
     }
 
     /// Отчет о вхождении товара в блюдо
@@ -909,51 +911,592 @@ impl<'a> ReportsEndpoint<'a> {
     /// Endpoint: GET `/reports/ingredientEntry`
     ///
     /// # Параметры
-    /// - `department`: Подразделение (GUID)
-    /// - `date`: На какую дату в формате DD.MM.YYYY
-    /// - `product`: ID продукта (GUID) (приоритет поиска: productArticle, product)
-    /// - `product_article`: Артикул продукта (приоритет поиска: productArticle, product)
-    /// - `include_subtree`: Включать ли в отчет строки поддеревьев (по умолчанию false)
+    /// - `query`: подразделение, дата и продукт (по ID или артикулу) (см. [`IngredientEntryQuery`])
     ///
     /// # Что в ответе
     /// Возвращает список элементов отчета о вхождении товара в блюдо.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.reports.get_ingredient_entry", level = "debug", skip_all)
+    )]
+    pub async fn ingredient_entry(
+        &self,
+        query: IngredientEntryQuery,
+    ) -> Result<Vec<IngredientEntryDto>> {
+        let response_xml = self
+            .client
+            .get_with_params("reports/ingredientEntry", &query.params().pairs())
+            .await?;
+
+        deserialize_list(&response_xml, "ingredientEntryDtoes", "ingredientEntryDto")
+    }
+
+    /// Отчет о вхождении товара в блюдо с позиционными параметрами
+    #[deprecated(
+        note = "используйте `ingredient_entry(IngredientEntryQuery::new(department, date)...)`"
+    )]
     pub async fn get_ingredient_entry(
         &self,
         department: &str,
-        date: &str,
+        date: impl Into<DateParam>,
         product: Option<&str>,
         product_article: Option<&str>,
         include_subtree: Option<bool>,
     ) -> Result<Vec<IngredientEntryDto>> {
-        let mut params = vec![("department", department), ("date", date)];
+        self.ingredient_entry(IngredientEntryQuery {
+            department: department.to_string(),
+            date: date.into(),
+            product: product.map(str::to_string),
+            product_article: product_article.map(str::to_string),
+            include_subtree,
+        })
+        .await
+    }
+}
+
+/// Срез из прежних позиционных сигнатур как список построителя
+fn owned(values: Option<&[&str]>) -> Vec<String> {
+    values
+        .unwrap_or_default()
+        .iter()
+        .map(|value| value.to_string())
+        .collect()
+}
+
+/// Балансы по счетам для [`ReportsEndpoint::balance_counteragents`]
+#[derive(Debug, Clone)]
+pub struct CounteragentBalanceQuery {
+    timestamp: DateParam,
+    accounts: Vec<String>,
+    counteragents: Vec<String>,
+    departments: Vec<String>,
+}
 
-        if let Some(prod) = product {
-            params.push(("product", prod));
+impl CounteragentBalanceQuery {
+    /// Балансы на учетную дату-время (`yyyy-MM-dd'T'HH:mm:ss`)
+    pub fn at(timestamp: impl Into<DateParam>) -> Self {
+        Self {
+            timestamp: timestamp.into(),
+            accounts: Vec::new(),
+            counteragents: Vec::new(),
+            departments: Vec::new(),
         }
+    }
 
-        if let Some(art) = product_article {
-            params.push(("productArticle", art));
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push(
+                "timestamp",
+                DateFormat::IsoDateTime.format(self.timestamp.clone()),
+            )
+            .push_all("account", &self.accounts)
+            .push_all("counteragent", &self.counteragents)
+            .push_all("department", &self.departments);
+        params
+    }
+}
+
+query_setters!(CounteragentBalanceQuery {
+    /// ID счетов
+    accounts: list<AccountId>,
+    /// ID контрагентов
+    counteragents: list,
+    /// ID подразделений
+    departments: list<DepartmentId>,
+});
+
+/// Остатки на складах для [`ReportsEndpoint::balance_stores`]
+#[derive(Debug, Clone)]
+pub struct StoreBalanceQuery {
+    timestamp: DateParam,
+    departments: Vec<String>,
+    stores: Vec<String>,
+    products: Vec<String>,
+}
+
+impl StoreBalanceQuery {
+    /// Остатки на учетную дату-время (`yyyy-MM-dd'T'HH:mm:ss`)
+    pub fn at(timestamp: impl Into<DateParam>) -> Self {
+        Self {
+            timestamp: timestamp.into(),
+            departments: Vec::new(),
+            stores: Vec::new(),
+            products: Vec::new(),
         }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push(
+                "timestamp",
+                DateFormat::IsoDateTime.format(self.timestamp.clone()),
+            )
+            .push_all("department", &self.departments)
+            .push_all("store", &self.stores)
+            .push_all("product", &self.products);
+        params
+    }
+}
 
-        if let Some(sub) = include_subtree {
-            params.push(("includeSubtree", if sub { "true" } else { "false" }));
+query_setters!(StoreBalanceQuery {
+    /// ID подразделений
+    departments: list<DepartmentId>,
+    /// ID складов
+    stores: list<StoreId>,
+    /// ID элементов номенклатуры
+    products: list<ProductId>,
+});
+
+/// OLAP-отчет старого API для [`ReportsEndpoint::olap_report_v1`]
+#[derive(Debug, Clone)]
+pub struct OlapReportV1Query {
+    report: OlapReportTypeV1,
+    from: DateParam,
+    to: DateParam,
+    summary: Option<bool>,
+    group_rows: Vec<String>,
+    group_cols: Vec<String>,
+    aggregates: Vec<String>,
+}
+
+impl OlapReportV1Query {
+    /// Отчет `report` за период `DD.MM.YYYY`
+    pub fn new(
+        report: OlapReportTypeV1,
+        from: impl Into<DateParam>,
+        to: impl Into<DateParam>,
+    ) -> Self {
+        Self {
+            report,
+            from: from.into(),
+            to: to.into(),
+            summary: None,
+            group_rows: Vec::new(),
+            group_cols: Vec::new(),
+            aggregates: Vec::new(),
         }
+    }
 
-        let response_xml = self
-            .client
-            .get_with_params("reports/ingredientEntry", &params)
-            .await?;
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push("report", self.report.as_str())
+            .push("from", DateFormat::Day.format(self.from.clone()))
+            .push("to", DateFormat::Day.format(self.to.clone()))
+            .push_opt("summary", self.summary)
+            .push_all("groupRow", &self.group_rows)
+            .push_all("groupCol", &self.group_cols)
+            .push_all("agr", &self.aggregates);
+        params
+    }
+}
+
+query_setters!(OlapReportV1Query {
+    /// Вычислять ли итоги (по умолчанию true, с версии 9.1.2 — false)
+    summary: flag,
+    /// Поля группировки по строкам (`groupRow`)
+    group_rows: list,
+    /// Поля группировки по столбцам (`groupCol`)
+    group_cols: list,
+    /// Поля агрегации (`agr`)
+    aggregates: list,
+});
+
+/// Сводный отчет по доставке для [`ReportsEndpoint::delivery_consolidated`]
+#[derive(Debug, Clone)]
+pub struct DeliveryConsolidatedQuery {
+    date_from: DateParam,
+    date_to: DateParam,
+    departments: Vec<String>,
+    writeoff_accounts: Vec<String>,
+}
 
-        // XML может быть списком элементов или одним элементом
-        let items: Vec<IngredientEntryDto> =
-            match from_str::<Vec<IngredientEntryDto>>(&response_xml) {
-                Ok(list) => list,
-                Err(_) => {
-                    // Пробуем как один элемент
-                    let item: IngredientEntryDto = from_str(&response_xml)?;
-                    vec![item]
-                }
-            };
-        Ok(items)
+impl DeliveryConsolidatedQuery {
+    /// Отчет за период (`DD.MM.YYYY` или `YYYY-MM-DD`)
+    pub fn between(date_from: impl Into<DateParam>, date_to: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: Vec::new(),
+            writeoff_accounts: Vec::new(),
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = delivery_params(&self.date_from, &self.date_to, &self.departments);
+        params.push_all("writeoffAccounts", &self.writeoff_accounts);
+        params
+    }
+}
+
+query_setters!(DeliveryConsolidatedQuery {
+    /// Подразделения в формате `{code="005"}` или `{id="guid"}`
+    departments: list,
+    /// Счета списания в формате `{code="5.14"}`
+    writeoff_accounts: list,
+});
+
+/// Отчет по курьерам для [`ReportsEndpoint::delivery_couriers`]
+#[derive(Debug, Clone)]
+pub struct DeliveryCouriersQuery {
+    date_from: DateParam,
+    date_to: DateParam,
+    departments: Vec<String>,
+    target_common_time: Option<i32>,
+    target_on_the_way_time: Option<i32>,
+    target_doubled_orders: Option<i32>,
+    target_tripled_orders: Option<i32>,
+    target_total_orders: Option<i32>,
+}
+
+impl DeliveryCouriersQuery {
+    /// Отчет за период (`DD.MM.YYYY` или `YYYY-MM-DD`)
+    pub fn between(date_from: impl Into<DateParam>, date_to: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: Vec::new(),
+            target_common_time: None,
+            target_on_the_way_time: None,
+            target_doubled_orders: None,
+            target_tripled_orders: None,
+            target_total_orders: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = delivery_params(&self.date_from, &self.date_to, &self.departments);
+        params
+            .push_opt("targetCommonTime", self.target_common_time)
+            .push_opt("targetOnTheWayTime", self.target_on_the_way_time)
+            .push_opt("targetDoubledOrders", self.target_doubled_orders)
+            .push_opt("targetTripledOrders", self.target_tripled_orders)
+            .push_opt("targetTotalOrders", self.target_total_orders);
+        params
     }
 }
+
+query_setters!(DeliveryCouriersQuery {
+    /// Подразделения в формате `{code="005"}` или `{id="guid"}`
+    departments: list,
+    /// Целевое общее время в минутах (по умолчанию 30)
+    target_common_time: number,
+    /// Целевое время в пути в минутах (по умолчанию 0)
+    target_on_the_way_time: number,
+    /// Целевое количество сдвоенных заказов за день (по умолчанию 0)
+    target_doubled_orders: number,
+    /// Целевое количество строенных заказов за день (по умолчанию 0)
+    target_tripled_orders: number,
+    /// Целевое количество заказов за день (по умолчанию 0)
+    target_total_orders: number,
+});
+
+/// Отчет по циклу заказа для [`ReportsEndpoint::delivery_order_cycle`]
+#[derive(Debug, Clone)]
+pub struct DeliveryOrderCycleQuery {
+    date_from: DateParam,
+    date_to: DateParam,
+    departments: Vec<String>,
+    target_pizza_time: Option<i32>,
+    target_cutting_time: Option<i32>,
+    target_on_shelf_time: Option<i32>,
+    target_in_restaurant_time: Option<i32>,
+    target_on_the_way_time: Option<i32>,
+    target_total_time: Option<i32>,
+}
+
+impl DeliveryOrderCycleQuery {
+    /// Отчет за период (`DD.MM.YYYY` или `YYYY-MM-DD`)
+    pub fn between(date_from: impl Into<DateParam>, date_to: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: Vec::new(),
+            target_pizza_time: None,
+            target_cutting_time: None,
+            target_on_shelf_time: None,
+            target_in_restaurant_time: None,
+            target_on_the_way_time: None,
+            target_total_time: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = delivery_params(&self.date_from, &self.date_to, &self.departments);
+        params
+            .push_opt("targetPizzaTime", self.target_pizza_time)
+            .push_opt("targetCuttingTime", self.target_cutting_time)
+            .push_opt("targetOnShelfTime", self.target_on_shelf_time)
+            .push_opt("targetInRestaurantTime", self.target_in_restaurant_time)
+            .push_opt("targetOnTheWayTime", self.target_on_the_way_time)
+            .push_opt("targetTotalTime", self.target_total_time);
+        params
+    }
+}
+
+query_setters!(DeliveryOrderCycleQuery {
+    /// Подразделения в формате `{code="005"}` или `{id="guid"}`
+    departments: list,
+    /// Целевое время на столе «Пицца» в минутах (по умолчанию 0)
+    target_pizza_time: number,
+    /// Целевое время на столе нарезки в минутах (по умолчанию 0)
+    target_cutting_time: number,
+    /// Целевое время на стеллаже оперативности в минутах (по умолчанию 0)
+    target_on_shelf_time: number,
+    /// Целевое время в ресторане в минутах (по умолчанию 0)
+    target_in_restaurant_time: number,
+    /// Целевое время в пути в минутах (по умолчанию 0)
+    target_on_the_way_time: number,
+    /// Целевое общее время доставки в минутах (по умолчанию 0)
+    target_total_time: number,
+});
+
+/// Отчет по лояльности доставки для [`ReportsEndpoint::delivery_loyalty`]
+#[derive(Debug, Clone)]
+pub struct DeliveryLoyaltyQuery {
+    date_from: DateParam,
+    date_to: DateParam,
+    departments: Vec<String>,
+    metric_type: Option<String>,
+}
+
+impl DeliveryLoyaltyQuery {
+    /// Отчет за период (`DD.MM.YYYY` или `YYYY-MM-DD`)
+    pub fn between(date_from: impl Into<DateParam>, date_to: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            departments: Vec::new(),
+            metric_type: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = delivery_params(&self.date_from, &self.date_to, &self.departments);
+        params.push_opt("metricType", self.metric_type.as_deref());
+        params
+    }
+}
+
+query_setters!(DeliveryLoyaltyQuery {
+    /// Подразделения в формате `{code="005"}` или `{id="guid"}`
+    departments: list,
+    /// Тип метрики (`AVERAGE`, `MINIMUM`, `MAXIMUM`)
+    metric_type: text,
+});
+
+/// Общие параметры отчетов по доставке
+fn delivery_params(
+    date_from: &DateParam,
+    date_to: &DateParam,
+    departments: &[String],
+) -> QueryParams {
+    let mut params = QueryParams::new();
+    params
+        .push("dateFrom", DateFormat::Day.format(date_from.clone()))
+        .push("dateTo", DateFormat::Day.format(date_to.clone()))
+        .push_all("department", departments);
+    params
+}
+
+/// Отчет по складским операциям для [`ReportsEndpoint::store_operations`]
+#[derive(Debug, Clone)]
+pub struct StoreOperationsQuery {
+    date_from: DateParam,
+    date_to: DateParam,
+    stores: Vec<String>,
+    document_types: Vec<String>,
+    product_detalization: Option<bool>,
+    show_cost_corrections: Option<bool>,
+    preset: Option<String>,
+}
+
+impl StoreOperationsQuery {
+    /// Отчет за период `DD.MM.YYYY`
+    pub fn between(date_from: impl Into<DateParam>, date_to: impl Into<DateParam>) -> Self {
+        Self {
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            stores: Vec::new(),
+            document_types: Vec::new(),
+            product_detalization: None,
+            show_cost_corrections: None,
+            preset: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push("dateFrom", DateFormat::Day.format(self.date_from.clone()))
+            .push("dateTo", DateFormat::Day.format(self.date_to.clone()))
+            .push_all("stores", &self.stores)
+            .push_all("documentTypes", &self.document_types)
+            .push_opt("productDetalization", self.product_detalization)
+            .push_opt("showCostCorrections", self.show_cost_corrections)
+            .push_opt("presetId", self.preset.as_deref());
+        params
+    }
+}
+
+query_setters!(StoreOperationsQuery {
+    /// Склады
+    stores: list<StoreId>,
+    /// Типы документов
+    document_types: list,
+    /// true — строки по товарам без даты, false — каждый документ одной строкой
+    product_detalization: flag,
+    /// Включать ли коррекции себестоимости
+    show_cost_corrections: flag,
+    /// ID преднастроенного отчета (GUID). Если задан, все настройки, кроме дат, игнорируются
+    preset: text,
+});
+
+/// Расход продуктов по продажам для [`ReportsEndpoint::product_expense`]
+#[derive(Debug, Clone)]
+pub struct ProductExpenseQuery {
+    department: String,
+    date_from: DateParam,
+    date_to: DateParam,
+    hour_from: Option<i32>,
+    hour_to: Option<i32>,
+}
+
+impl ProductExpenseQuery {
+    /// Расход подразделения за период `DD.MM.YYYY`
+    pub fn new(
+        department: impl Into<DepartmentId>,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
+    ) -> Self {
+        Self {
+            department: department.into().to_string(),
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            hour_from: None,
+            hour_to: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = department_params(&self.department, &self.date_from, &self.date_to);
+        params
+            .push_opt("hourFrom", self.hour_from)
+            .push_opt("hourTo", self.hour_to);
+        params
+    }
+}
+
+query_setters!(ProductExpenseQuery {
+    /// Час начала интервала выборки в сутках (по умолчанию -1, все время)
+    hour_from: number,
+    /// Час окончания интервала выборки в сутках (по умолчанию -1, все время)
+    hour_to: number,
+});
+
+/// Отчет по выручке для [`ReportsEndpoint::sales`]
+#[derive(Debug, Clone)]
+pub struct SalesQuery {
+    department: String,
+    date_from: DateParam,
+    date_to: DateParam,
+    hour_from: Option<i32>,
+    hour_to: Option<i32>,
+    dish_details: Option<bool>,
+    all_revenue: Option<bool>,
+}
+
+impl SalesQuery {
+    /// Выручка подразделения за период `DD.MM.YYYY`
+    pub fn new(
+        department: impl Into<DepartmentId>,
+        date_from: impl Into<DateParam>,
+        date_to: impl Into<DateParam>,
+    ) -> Self {
+        Self {
+            department: department.into().to_string(),
+            date_from: date_from.into(),
+            date_to: date_to.into(),
+            hour_from: None,
+            hour_to: None,
+            dish_details: None,
+            all_revenue: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = department_params(&self.department, &self.date_from, &self.date_to);
+        params
+            .push_opt("hourFrom", self.hour_from)
+            .push_opt("hourTo", self.hour_to)
+            .push_opt("dishDetails", self.dish_details)
+            .push_opt("allRevenue", self.all_revenue);
+        params
+    }
+}
+
+query_setters!(SalesQuery {
+    /// Час начала интервала выборки в сутках (по умолчанию -1, все время)
+    hour_from: number,
+    /// Час окончания интервала выборки в сутках (по умолчанию -1, все время)
+    hour_to: number,
+    /// Включать ли разбивку по блюдам (по умолчанию false)
+    dish_details: flag,
+    /// true — все типы оплат, false — только выручка (по умолчанию true)
+    all_revenue: flag,
+});
+
+/// Общие параметры отчетов по подразделению за период
+fn department_params(department: &str, date_from: &DateParam, date_to: &DateParam) -> QueryParams {
+    let mut params = QueryParams::new();
+    params
+        .push("department", department)
+        .push("dateFrom", DateFormat::Day.format(date_from.clone()))
+        .push("dateTo", DateFormat::Day.format(date_to.clone()));
+    params
+}
+
+/// Вхождение товара в блюдо для [`ReportsEndpoint::ingredient_entry`]
+#[derive(Debug, Clone)]
+pub struct IngredientEntryQuery {
+    department: String,
+    date: DateParam,
+    product: Option<String>,
+    product_article: Option<String>,
+    include_subtree: Option<bool>,
+}
+
+impl IngredientEntryQuery {
+    /// Отчет по подразделению на дату `DD.MM.YYYY`
+    pub fn new(department: impl Into<DepartmentId>, date: impl Into<DateParam>) -> Self {
+        Self {
+            department: department.into().to_string(),
+            date: date.into(),
+            product: None,
+            product_article: None,
+            include_subtree: None,
+        }
+    }
+
+    fn params(&self) -> QueryParams {
+        let mut params = QueryParams::new();
+        params
+            .push("department", self.department.as_str())
+            .push("date", DateFormat::Day.format(self.date.clone()))
+            .push_opt("product", self.product.as_deref())
+            .push_opt("productArticle", self.product_article.as_deref())
+            .push_opt("includeSubtree", self.include_subtree);
+        params
+    }
+}
+
+query_setters!(IngredientEntryQuery {
+    /// ID продукта. Артикул имеет приоритет
+    product: text<ProductId>,
+    /// Артикул продукта
+    product_article: text<ProductArticle>,
+    /// Включать ли строки поддеревьев (по умолчанию false)
+    include_subtree: flag,
+});
@@ -1,8 +1,12 @@
 use crate::client::IikoClient;
+use crate::date::{DateFormat, DateParam};
 use crate::error::Result;
+use crate::ids::SupplierCode;
+use crate::query::{QueryParams, query_setters};
+use crate::server_info::ServerVersion;
+use crate::xml::deserialize_list;
 use crate::xml::response::suppliers::{Supplier, SupplierPriceListItemDto, Suppliers};
 use quick_xml::de::from_str;
-use serde::Deserialize;
 
 pub struct SuppliersEndpoint<'a> {
     client: &'a IikoClient,
@@ -24,7 +28,16 @@ impl<'a> SuppliersEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Список всех поставщиков (XML структура employees)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.suppliers.list", level = "debug", skip_all)
+    )]
     pub async fn list(&self, revision_from: Option<i32>) -> Result<Vec<Supplier>> {
+        let revision_from = self
+            .client
+            .param_since(ServerVersion::new(6, 4, 0), revision_from)
+            .await;
+
         let mut param_strings = Vec::new();
         let mut params = Vec::new();
 
@@ -51,21 +64,42 @@ impl<'a> SuppliersEndpoint<'a> {
     /// # Endpoint: GET `/suppliers/search`
     ///
     /// # Параметры запроса:
-    /// Поиск по id поставщика не производится.
-    /// Возможно произвести поиск по следующим полям:
-    /// - `name` - поле Имя в системе
-    /// - `code` - поле Таб.номер/Код
-    /// - `phone` - поле Телефон
-    /// - `cell_phone` - поле Мобильный телефон
-    /// - `first_name` - поле Имя
-    /// - `middle_name` - поле Отчество
-    /// - `last_name` - поле Фамилия
-    /// - `email` - поле e-mail
-    /// - `card_number` - поле Номер карты
-    /// - `taxpayer_id_number` - поле ИНН
+    /// Поиск по id поставщика не производится. Поля поиска — см. [`SupplierSearch`].
     ///
     /// # Что в ответе:
     /// - Список найденных поставщиков (XML структура employees)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.suppliers.search", level = "debug", skip_all)
+    )]
+    pub async fn find(&self, search: SupplierSearch) -> Result<Vec<Supplier>> {
+        let mut params = QueryParams::new();
+        params
+            .push_opt("name", search.name)
+            .push_opt("code", search.code)
+            .push_opt("phone", search.phone)
+            .push_opt("cellPhone", search.cell_phone)
+            .push_opt("firstName", search.first_name)
+            .push_opt("middleName", search.middle_name)
+            .push_opt("lastName", search.last_name)
+            .push_opt("email", search.email)
+            .push_opt("cardNumber", search.card_number)
+            .push_opt("taxpayerIdNumber", search.taxpayer_id_number);
+
+        let response_xml = self
+            .client
+            .get_with_params("suppliers/search", &params.pairs())
+            .await?;
+
+        // Парсим XML ответ
+        // XML формат: <employees><employee>...</employee></employees>
+        let wrapper: Suppliers = from_str(&response_xml)?;
+        Ok(wrapper.items)
+    }
+
+    /// Поиск поставщика с позиционными параметрами (см. [`SuppliersEndpoint::find`])
+    #[deprecated(note = "используйте `find(SupplierSearch::new()...)`")]
+    #[allow(clippy::too_many_arguments)]
     pub async fn search(
         &self,
         name: Option<&str>,
@@ -79,93 +113,19 @@ impl<'a> SuppliersEndpoint<'a> {
         card_number: Option<&str>,
         taxpayer_id_number: Option<&str>,
     ) -> Result<Vec<Supplier>> {
-        let mut param_strings = Vec::new();
-        let mut params = Vec::new();
-
-        // Сначала собираем все строки
-        if let Some(n) = name {
-            param_strings.push(n.to_string());
-        }
-        if let Some(c) = code {
-            param_strings.push(c.to_string());
-        }
-        if let Some(p) = phone {
-            param_strings.push(p.to_string());
-        }
-        if let Some(cp) = cell_phone {
-            param_strings.push(cp.to_string());
-        }
-        if let Some(fn_val) = first_name {
-            param_strings.push(fn_val.to_string());
-        }
-        if let Some(mn) = middle_name {
-            param_strings.push(mn.to_string());
-        }
-        if let Some(ln) = last_name {
-            param_strings.push(ln.to_string());
-        }
-        if let Some(e) = email {
-            param_strings.push(e.to_string());
-        }
-        if let Some(cn) = card_number {
-            param_strings.push(cn.to_string());
-        }
-        if let Some(tin) = taxpayer_id_number {
-            param_strings.push(tin.to_string());
-        }
-
-        // Теперь создаем params, используя индексы
-        let mut idx = 0;
-        if let Some(_) = name {
-            params.push(("name", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = code {
-            params.push(("code", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = phone {
-            params.push(("phone", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = cell_phone {
-            params.push(("cellPhone", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = first_name {
-            params.push(("firstName", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = middle_name {
-            params.push(("middleName", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = last_name {
-            params.push(("lastName", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = email {
-            params.push(("email", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = card_number {
-            params.push(("cardNumber", param_strings[idx].as_str()));
-            idx += 1;
-        }
-        if let Some(_) = taxpayer_id_number {
-            params.push(("taxpayerIdNumber", param_strings[idx].as_str()));
-            idx += 1;
-        }
-
-        let response_xml = self
-            .client
-            .get_with_params("suppliers/search", &params)
-            .await?;
-
-        // Парсим XML ответ
-        // XML формат: <employees><employee>...</employee></employees>
-        let wrapper: Suppliers = from_str(&response_xml)?;
-        Ok(wrapper.items)
+        self.find(SupplierSearch {
+            name: name.map(str::to_string),
+            code: code.map(str::to_string),
+            phone: phone.map(str::to_string),
+            cell_phone: cell_phone.map(str::to_string),
+            first_name: first_name.map(str::to_string),
+            middle_name: middle_name.map(str::to_string),
+            last_name: last_name.map(str::to_string),
+            email: email.map(str::to_string),
+            card_number: card_number.map(str::to_string),
+            taxpayer_id_number: taxpayer_id_number.map(str::to_string),
+        })
+        .await
     }
 
     /// Получение прайс-листа поставщика
@@ -180,17 +140,22 @@ impl<'a> SuppliersEndpoint<'a> {
     ///
     /// # Что в ответе:
     /// - Структура supplierPriceListItemDto (XML)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "iiko.suppliers.get_pricelist", level = "debug", skip_all)
+    )]
     pub async fn get_pricelist(
         &self,
-        code: &str,
-        date: Option<&str>,
+        code: impl Into<SupplierCode>,
+        date: Option<DateParam>,
     ) -> Result<Vec<SupplierPriceListItemDto>> {
+        let date = date.map(|date| DateFormat::Day.format(date));
         let mut params = Vec::new();
-        if let Some(date) = date {
-            params.push(("date", date));
+        if let Some(date) = &date {
+            params.push(("date", date.as_str()));
         }
 
-        let endpoint = format!("suppliers/{}/pricelist", code);
+        let endpoint = format!("suppliers/{}/pricelist", code.into());
         let response_xml = if params.is_empty() {
             self.client.get(&endpoint).await?
         } else {
@@ -201,28 +166,57 @@ impl<'a> SuppliersEndpoint<'a> {
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct SupplierPriceListEnvelope {
-    #[serde(rename = "supplierPriceListItemDto", default)]
-    items: Vec<SupplierPriceListItemDto>,
+fn parse_supplier_pricelist_response(xml: &str) -> Result<Vec<SupplierPriceListItemDto>> {
+    // iiko встречается как с wrapper-элементом, так и с одиночным supplierPriceListItemDto;
+    // элементы под другим корнем тоже читаются.
+    deserialize_list(xml, "supplierPriceList", "supplierPriceListItemDto")
 }
 
-fn parse_supplier_pricelist_response(xml: &str) -> Result<Vec<SupplierPriceListItemDto>> {
-    // iiko встречается как с wrapper-элементом, так и с одиночным supplierPriceListItemDto.
-    let has_supplier_pricelist_wrapper = xml.contains("<supplierPriceList>")
-        || xml.contains("<supplierPriceList ")
-        || xml.contains("</supplierPriceList>");
+/// Параметры поиска поставщиков для [`SuppliersEndpoint::find`]
+#[derive(Debug, Clone, Default)]
+pub struct SupplierSearch {
+    name: Option<String>,
+    code: Option<String>,
+    phone: Option<String>,
+    cell_phone: Option<String>,
+    first_name: Option<String>,
+    middle_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+    card_number: Option<String>,
+    taxpayer_id_number: Option<String>,
+}
 
-    if let Ok(wrapper) = from_str::<SupplierPriceListEnvelope>(xml) {
-        if has_supplier_pricelist_wrapper || !wrapper.items.is_empty() {
-            return Ok(wrapper.items);
-        }
+impl SupplierSearch {
+    /// Поиск без фильтров
+    pub fn new() -> Self {
+        Self::default()
     }
-
-    let item: SupplierPriceListItemDto = from_str(xml)?;
-    Ok(vec![item])
 }
 
+query_setters!(SupplierSearch {
+    /// Поле «Имя в системе»
+    name: text,
+    /// Поле «Таб.номер/Код»
+    code: text,
+    /// Поле «Телефон»
+    phone: text,
+    /// Поле «Мобильный телефон»
+    cell_phone: text,
+    /// Поле «Имя»
+    first_name: text,
+    /// Поле «Отчество»
+    middle_name: text,
+    /// Поле «Фамилия»
+    last_name: text,
+    /// Поле «e-mail»
+    email: text,
+    /// Поле «Номер карты»
+    card_number: text,
+    /// Поле «ИНН»
+    taxpayer_id_number: text,
+});
+
 #[cfg(test)]
 mod tests {
     use super::parse_supplier_pricelist_response;
@@ -284,7 +278,24 @@ mod tests {
 
         assert_eq!(items.len(), 1);
         assert_eq!(items[0].native_product_code.as_deref(), Some("N-003"));
-        assert_eq!(items[0].cost_price, Some(450.75));
+        assert_eq!(items[0].cost_price, Some("450.75".parse().unwrap()));
+    }
+
+    #[test]
+    fn parses_supplier_pricelist_under_other_root() {
+        let xml = r#"
+            <supplierPriceListItemDtoes>
+                <supplierPriceListItemDto>
+                    <nativeProductCode>N-004</nativeProductCode>
+                    <costPrice>10</costPrice>
+                </supplierPriceListItemDto>
+            </supplierPriceListItemDtoes>
+        "#;
+
+        let items = parse_supplier_pricelist_response(xml).expect("other root should parse");
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].native_product_code.as_deref(), Some("N-004"));
     }
 
     #[test]
@@ -1,13 +1,59 @@
+use crate::retry::RetryOn;
+use crate::server_info::ServerVersion;
+use crate::transport::StatusCode;
+use crate::xml::response::DocumentValidationResult;
 use thiserror::Error;
 
+/// Фрагменты текста ошибки, по которым iiko сообщает об истекшем ключе сессии
+const SESSION_EXPIRED_MARKERS: &[&str] = &["token is expired", "token expired", "invalid token"];
+
+/// Тексты iiko о нехватке слотов лицензии
+///
+/// "License enhancement is required: no connections available for module 28008806"
+const LICENSE_MARKERS: &[&str] = &[
+    "license enhancement is required",
+    "no connections available for module",
+];
+
+/// Фрагменты текста ошибки, по которым iiko сообщает, что метод не поддерживается версией сервера
+const UNSUPPORTED_VERSION_MARKERS: &[&str] = &[
+    "not supported in this version",
+    "not supported by this version",
+    "unsupported api version",
+    "не поддерживается в данной версии",
+    "не поддерживается этой версией",
+];
+
 #[derive(Error, Debug)]
 pub enum IikoError {
     #[error("HTTP request failed: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("Transport error: {0}")]
+    Transport(String),
+
     #[error("XML deserialization error: {0}")]
     XmlDeserialization(#[from] quick_xml::DeError),
 
+    /// Элемент списка в XML-ответе не разобран (см. `xml::deserialize_list`)
+    #[error("XML list item <{element}> #{index}, field `{field}`: {message}")]
+    XmlListItem {
+        element: String,
+        /// Номер элемента в списке, с нуля
+        index: usize,
+        /// Путь к полю внутри элемента, `.` — сам элемент
+        field: String,
+        message: String,
+    },
+
+    /// В XML-ответе со списком нет элементов, а корень — не обертка (см. `xml::deserialize_list`)
+    #[error("XML list: no <{element}> items and unexpected root <{root}>, expected <{wrapper}>")]
+    XmlListRoot {
+        wrapper: String,
+        element: String,
+        root: String,
+    },
+
     #[error("XML serialization error: {0}")]
     XmlSerialization(#[from] quick_xml::SeError),
 
@@ -20,9 +66,70 @@ pub enum IikoError {
     #[error("Invalid configuration: {0}")]
     Configuration(String),
 
+    /// Все слоты лицензии заняты ("License enhancement is required")
+    ///
+    /// Освободите сессии через `logout()` / `session::release_all()` или дождитесь,
+    /// пока ключи истекут на сервере.
+    #[error("License limit reached: {message}")]
+    LicenseExhausted {
+        /// Модуль лицензии из текста ошибки, например `28008806`
+        module_id: Option<String>,
+        message: String,
+    },
+
+    /// iiko отклонил ключ сессии: истек таймаут, сервер перезагружен или выполнен `/logout`
+    #[error("Session key expired or invalid: {0}")]
+    SessionExpired(String),
+
+    /// Метод не поддерживается версией сервера iiko
+    #[error("Not supported by this iiko server version: {message}")]
+    UnsupportedApiVersion {
+        /// Версия сервера из текста ошибки, если iiko ее указал
+        server_version: Option<String>,
+        message: String,
+    },
+
+    /// Версия сервера ниже той, с которой появился метод. Запрос на сервер не отправлялся
+    ///
+    /// Версия сервера берется из `IikoClient::server_info()`.
+    #[error("Requires iiko server {required} or newer, server version is {actual}")]
+    UnsupportedVersion {
+        required: ServerVersion,
+        actual: ServerVersion,
+    },
+
+    /// Изменяющий запрос в режиме `WriteMode::ReadOnly`. Запрос на сервер не отправлялся
+    #[error("Read-only client refused {method} {endpoint}: the request modifies data")]
+    ReadOnly { method: String, endpoint: String },
+
+    /// Дата в ответе сервера не разобрана (фича `chrono`)
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+
+    /// Объект не найден по коду ("Entity of class User not found by code 42")
+    #[error("{entity} not found by code {code}")]
+    EntityNotFoundByCode {
+        /// Класс объекта в терминах iiko: `User`, `Supplier`, `Product`
+        entity: String,
+        code: String,
+    },
+
+    /// Документ не прошел проверку на сервере (`documentValidationResult` с `valid = false`)
+    #[error("Document validation failed: {message}")]
+    DocumentValidation {
+        document_number: Option<String>,
+        /// Номер, который предлагает iiko, если текущий нарушает уникальность
+        suggested_number: Option<String>,
+        message: String,
+        /// Подробности, например позиции, уходящие в отрицательный остаток
+        additional_info: Option<String>,
+    },
+
     #[error("API error (400 Bad Request): {0}")]
     BadRequest(String),
 
+    /// 401 на запрос без ключа сессии (например, `get_server_info.jsp` за прокси).
+    /// Отклоненный ключ сессии клиент возвращает как `SessionExpired`
     #[error("API error (401 Unauthorized): {0}")]
     Unauthorized(String),
 
@@ -38,6 +145,15 @@ pub enum IikoError {
     #[error("API error (500 Internal Server Error): {0}")]
     InternalServerError(String),
 
+    #[error("API error (502 Bad Gateway): {0}")]
+    BadGateway(String),
+
+    #[error("API error (503 Service Unavailable): {0}")]
+    ServiceUnavailable(String),
+
+    #[error("API error (504 Gateway Timeout): {0}")]
+    GatewayTimeout(String),
+
     #[error("API error: {0}")]
     Api(String),
 
@@ -45,4 +161,326 @@ pub enum IikoError {
     Io(#[from] std::io::Error),
 }
 
+impl IikoError {
+    /// Может ли тот же запрос завершиться успешно, если повторить его позже
+    ///
+    /// Сетевые ошибки, таймауты и 5xx — те же классы [`RetryOn`], что повторяет
+    /// `RetryPolicy::default()`. Ошибки запроса (400, 404, 409, валидация документа)
+    /// повтор не исправит. Истекший ключ сессии клиент обновляет сам (`auto_reauth`),
+    /// а слоты лицензии за время повтора не освобождаются.
+    pub fn is_retryable(&self) -> bool {
+        RetryOn::classify(self).is_some()
+    }
+
+    /// Ошибка связана с авторизацией: логин, ключ сессии, права или лицензия
+    pub fn is_auth(&self) -> bool {
+        matches!(
+            self,
+            IikoError::Authentication(_)
+                | IikoError::SessionExpired(_)
+                | IikoError::LicenseExhausted { .. }
+                | IikoError::Unauthorized(_)
+                | IikoError::Forbidden(_)
+        )
+    }
+
+    /// Ошибка по неуспешному ответу iiko на запрос без ключа сессии
+    ///
+    /// Сначала распознаются типичные тексты iiko (лицензия, ключ сессии, версия сервера,
+    /// объект не найден по коду, `documentValidationResult`), иначе ошибка выбирается по статусу.
+    pub(crate) fn from_response(status: StatusCode, error_text: String) -> Self {
+        Self::from_status(status, error_text, false)
+    }
+
+    /// Ошибка по неуспешному ответу на запрос с ключом сессии: 401 — `SessionExpired`
+    pub(crate) fn from_keyed_response(status: StatusCode, error_text: String) -> Self {
+        Self::from_status(status, error_text, true)
+    }
+
+    fn from_status(status: StatusCode, error_text: String, key_sent: bool) -> Self {
+        if let Some(error) = Self::recognize(status, &error_text, key_sent) {
+            return error;
+        }
+
+        match status.as_u16() {
+            400 => IikoError::BadRequest(error_text),
+            401 => IikoError::Unauthorized(error_text),
+            403 => IikoError::Forbidden(error_text),
+            404 => IikoError::NotFound(error_text),
+            409 => IikoError::BusinessLogic(error_text),
+            500 => IikoError::InternalServerError(error_text),
+            502 => IikoError::BadGateway(error_text),
+            503 => IikoError::ServiceUnavailable(error_text),
+            504 => IikoError::GatewayTimeout(error_text),
+            _ => IikoError::Api(format!(
+                "Request failed with status: {} - {}",
+                status, error_text
+            )),
+        }
+    }
+
+    /// Ошибка по неуспешному ответу `/auth`
+    pub(crate) fn from_auth_response(status: StatusCode, error_text: String) -> Self {
+        license_exhausted(status, &error_text).unwrap_or_else(|| {
+            IikoError::Authentication(format!(
+                "Authentication failed with status: {} - {}",
+                status, error_text
+            ))
+        })
+    }
+
+    fn recognize(status: StatusCode, error_text: &str, key_sent: bool) -> Option<Self> {
+        if let Some(error) = document_validation(error_text) {
+            return Some(error);
+        }
+        if let Some(error) = license_exhausted(status, error_text) {
+            return Some(error);
+        }
+        let session_expired = if key_sent {
+            is_session_expired(status, error_text)
+        } else {
+            contains_any(error_text, SESSION_EXPIRED_MARKERS)
+        };
+        if session_expired {
+            return Some(IikoError::SessionExpired(error_text.to_string()));
+        }
+        if let Some(error) = unsupported_version(error_text) {
+            return Some(error);
+        }
+        entity_not_found_by_code(error_text)
+    }
+}
+
+/// Признак того, что iiko отклонил запрос с ключом сессии из-за протухшего ключа
+///
+/// Сервер отвечает 401, если ключ истек по таймауту или сервер был перезагружен.
+/// Некоторые версии iiko вместо этого отдают текст вида "Token is expired or invalid".
+pub(crate) fn is_session_expired(status: StatusCode, error_text: &str) -> bool {
+    status == StatusCode::UNAUTHORIZED || contains_any(error_text, SESSION_EXPIRED_MARKERS)
+}
+
+fn contains_any(text: &str, markers: &[&str]) -> bool {
+    let text = text.to_lowercase();
+    markers.iter().any(|marker| text.contains(marker))
+}
+
+/// Первая последовательность цифр после `marker` (без учета регистра)
+fn number_after(text: &str, marker: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+    let start = lower.find(marker)? + marker.len();
+    let digits: String = lower[start..]
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    (!digits.is_empty()).then_some(digits)
+}
+
+/// Нехватка лицензий. 5xx остаются ошибками сервера, даже если текст похож
+fn license_exhausted(status: StatusCode, error_text: &str) -> Option<IikoError> {
+    let matches = !status.is_server_error() && contains_any(error_text, LICENSE_MARKERS);
+    matches.then(|| IikoError::LicenseExhausted {
+        module_id: number_after(error_text, "module"),
+        message: error_text.trim().to_string(),
+    })
+}
+
+fn unsupported_version(error_text: &str) -> Option<IikoError> {
+    if !contains_any(error_text, UNSUPPORTED_VERSION_MARKERS) {
+        return None;
+    }
+
+    // Первое слово вида 7.4 или 8.1.2
+    let server_version = error_text
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|word| word.trim_matches('.'))
+        .find(|word| word.contains('.') && word.split('.').all(|part| !part.is_empty()))
+        .map(str::to_string);
+
+    Some(IikoError::UnsupportedApiVersion {
+        server_version,
+        message: error_text.trim().to_string(),
+    })
+}
+
+/// "Entity of class User not found by code 42" или "Supplier with code 42 not found"
+fn entity_not_found_by_code(error_text: &str) -> Option<IikoError> {
+    let text = error_text.trim();
+
+    if let Some((head, code)) = text.split_once(" not found by code ") {
+        let entity = head.strip_prefix("Entity of class ").unwrap_or(head);
+        return Some(IikoError::EntityNotFoundByCode {
+            entity: entity.trim().to_string(),
+            code: code.trim().to_string(),
+        });
+    }
+
+    let (entity, rest) = text.split_once(" with code ")?;
+    let code = rest.strip_suffix(" not found")?;
+    Some(IikoError::EntityNotFoundByCode {
+        entity: entity.trim().to_string(),
+        code: code.trim().to_string(),
+    })
+}
+
+fn document_validation(error_text: &str) -> Option<IikoError> {
+    let text = error_text.trim_start();
+    let xml = text
+        .strip_prefix("<?xml")
+        .and_then(|declaration| declaration.split_once("?>"))
+        .map_or(text, |(_, rest)| rest.trim_start());
+    if !xml.starts_with("<documentValidationResult") {
+        return None;
+    }
+
+    let result: DocumentValidationResult = quick_xml::de::from_str(xml).ok()?;
+    if result.valid {
+        return None;
+    }
+    Some(result.into())
+}
+
+impl From<DocumentValidationResult> for IikoError {
+    fn from(result: DocumentValidationResult) -> Self {
+        IikoError::DocumentValidation {
+            document_number: result.document_number,
+            suggested_number: result.other_suggested_number,
+            message: result
+                .error_message
+                .unwrap_or_else(|| "Document is not valid".to_string()),
+            additional_info: result.additional_info,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, IikoError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_license_and_session_errors() {
+        let error = IikoError::from_auth_response(
+            StatusCode::FORBIDDEN,
+            "License enhancement is required: no connections available for module 28008806"
+                .to_string(),
+        );
+        assert!(matches!(
+            &error,
+            IikoError::LicenseExhausted { module_id: Some(id), .. } if id == "28008806"
+        ));
+        assert!(error.is_auth() && !error.is_retryable());
+
+        let error = IikoError::from_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Token is expired or invalid".to_string(),
+        );
+        assert!(matches!(error, IikoError::SessionExpired(_)));
+
+        let error =
+            IikoError::from_auth_response(StatusCode::UNAUTHORIZED, "Wrong password".to_string());
+        assert!(matches!(error, IikoError::Authentication(_)));
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn keeps_server_errors_with_license_like_text() {
+        for text in [
+            "Не удалось проверить лицензию",
+            "Could not get JDBC Connection: no connections available",
+        ] {
+            let error = IikoError::from_response(StatusCode::FORBIDDEN, text.to_string());
+            assert!(matches!(error, IikoError::Forbidden(_)), "{}", text);
+        }
+
+        let error = IikoError::from_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "License enhancement is required".to_string(),
+        );
+        assert!(matches!(error, IikoError::ServiceUnavailable(_)));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn maps_unauthorized_without_session_key() {
+        let error = IikoError::from_response(StatusCode::UNAUTHORIZED, String::new());
+        assert!(matches!(error, IikoError::Unauthorized(_)));
+        assert!(error.is_auth() && !error.is_retryable());
+
+        let error = IikoError::from_keyed_response(StatusCode::UNAUTHORIZED, String::new());
+        assert!(matches!(error, IikoError::SessionExpired(_)));
+    }
+
+    #[test]
+    fn recognizes_entity_and_version_errors() {
+        let error = IikoError::from_response(
+            StatusCode::BAD_REQUEST,
+            "Entity of class User not found by code 42".to_string(),
+        );
+        assert!(matches!(
+            &error,
+            IikoError::EntityNotFoundByCode { entity, code } if entity == "User" && code == "42"
+        ));
+        assert!(!error.is_retryable() && !error.is_auth());
+
+        let error = IikoError::from_response(
+            StatusCode::NOT_FOUND,
+            "Supplier with code S-7 not found".to_string(),
+        );
+        assert!(matches!(
+            &error,
+            IikoError::EntityNotFoundByCode { entity, code } if entity == "Supplier" && code == "S-7"
+        ));
+
+        let error = IikoError::from_response(
+            StatusCode::BAD_REQUEST,
+            "Method is not supported in this version (7.2.3)".to_string(),
+        );
+        assert!(matches!(
+            &error,
+            IikoError::UnsupportedApiVersion { server_version: Some(v), .. } if v == "7.2.3"
+        ));
+
+        let error = IikoError::from_response(StatusCode::NOT_FOUND, "Unknown path".to_string());
+        assert!(matches!(error, IikoError::NotFound(_)));
+    }
+
+    #[test]
+    fn recognizes_document_validation_failure() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<documentValidationResult>
+    <valid>false</valid>
+    <warning>false</warning>
+    <documentNumber>IN-17</documentNumber>
+    <errorMessage>Негативный остаток</errorMessage>
+    <additionalInfo>Молоко: -2 л</additionalInfo>
+</documentValidationResult>"#;
+
+        let error = IikoError::from_response(StatusCode::CONFLICT, body.to_string());
+        assert!(matches!(
+            &error,
+            IikoError::DocumentValidation { document_number: Some(number), message, additional_info: Some(_), .. }
+                if number == "IN-17" && message == "Негативный остаток"
+        ));
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn detects_expired_session_by_status() {
+        assert!(is_session_expired(StatusCode::UNAUTHORIZED, ""));
+        assert!(!is_session_expired(StatusCode::FORBIDDEN, ""));
+    }
+
+    #[test]
+    fn detects_expired_session_by_body() {
+        assert!(is_session_expired(
+            StatusCode::FORBIDDEN,
+            "Token is expired or invalid"
+        ));
+        assert!(!is_session_expired(
+            StatusCode::CONFLICT,
+            "Could not delete already deleted products"
+        ));
+    }
+}
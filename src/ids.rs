@@ -0,0 +1,283 @@
+//! Идентификаторы и коды сущностей iiko
+//!
+//! ID подразделений, складов, товаров, групп и категорий номенклатуры, поставщиков, счетов
+//! и сотрудников — разные типы поверх `Uuid`, поэтому ID склада нельзя передать туда, где
+//! ждут ID товара. Коды (артикул товара, код подразделения) — типы поверх строки.
+//!
+//! ```
+//! use iiko_server_api_sdk::ids::{ProductArticle, ProductId, StoreId};
+//! use uuid::Uuid;
+//!
+//! let product = ProductId::from(Uuid::nil());
+//! let store: StoreId = "1239d270-1bbe-f64f-b7ea-5f00518ef508".parse().unwrap();
+//! let article = ProductArticle::from("00042");
+//!
+//! assert_eq!(store.to_string(), "1239d270-1bbe-f64f-b7ea-5f00518ef508");
+//! assert_eq!(article.as_str(), "00042");
+//! assert_eq!(Uuid::from(product), Uuid::nil());
+//! ```
+//!
+//! # Важно
+//! - В XML и JSON типы записываются так же, как раньше `Uuid` и строки: ID — строкой
+//!   GUID, код — текстом
+//! - Методы, которые раньше принимали `Uuid`, принимают `impl Into<ProductId>` и т.п.,
+//!   поэтому `Uuid` по-прежнему подходит. Строку в ID переводит `str::parse`
+//! - Коды разыменовываются в `str`: `supplier.code.as_str()`, `dto.num.as_deref()`
+
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// ID сущности поверх `Uuid`
+macro_rules! uuid_id {
+    ($($(#[$doc:meta])* $name:ident;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(
+                Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+                serde::Serialize, serde::Deserialize,
+            )]
+            #[serde(transparent)]
+            pub struct $name(Uuid);
+
+            impl $name {
+                pub const fn from_uuid(uuid: Uuid) -> Self {
+                    Self(uuid)
+                }
+
+                pub const fn as_uuid(&self) -> &Uuid {
+                    &self.0
+                }
+            }
+
+            impl From<Uuid> for $name {
+                fn from(uuid: Uuid) -> Self {
+                    Self(uuid)
+                }
+            }
+
+            impl From<$name> for Uuid {
+                fn from(id: $name) -> Self {
+                    id.0
+                }
+            }
+
+            impl PartialEq<Uuid> for $name {
+                fn eq(&self, other: &Uuid) -> bool {
+                    self.0 == *other
+                }
+            }
+
+            impl FromStr for $name {
+                type Err = uuid::Error;
+
+                fn from_str(text: &str) -> Result<Self, Self::Err> {
+                    Uuid::parse_str(text.trim()).map(Self)
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+/// Код или артикул сущности поверх строки
+macro_rules! text_code {
+    ($($(#[$doc:meta])* $name:ident;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(
+                Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default,
+                serde::Serialize, serde::Deserialize,
+            )]
+            #[serde(transparent)]
+            pub struct $name(String);
+
+            impl $name {
+                pub fn new(code: impl Into<String>) -> Self {
+                    Self(code.into())
+                }
+
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+
+                pub fn into_string(self) -> String {
+                    self.0
+                }
+            }
+
+            impl From<&str> for $name {
+                fn from(code: &str) -> Self {
+                    Self(code.to_string())
+                }
+            }
+
+            impl From<String> for $name {
+                fn from(code: String) -> Self {
+                    Self(code)
+                }
+            }
+
+            impl From<&String> for $name {
+                fn from(code: &String) -> Self {
+                    Self(code.clone())
+                }
+            }
+
+            impl From<&$name> for $name {
+                fn from(code: &$name) -> Self {
+                    code.clone()
+                }
+            }
+
+            impl Deref for $name {
+                type Target = str;
+
+                fn deref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl PartialEq<str> for $name {
+                fn eq(&self, other: &str) -> bool {
+                    self.0 == other
+                }
+            }
+
+            impl PartialEq<&str> for $name {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0 == *other
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+        )*
+    };
+}
+
+uuid_id! {
+    /// ID элемента номенклатуры (товара, блюда, заготовки, модификатора)
+    ProductId;
+    /// ID группы номенклатуры (`parent` элемента)
+    ProductGroupId;
+    /// ID пользовательской категории номенклатуры
+    ProductCategoryId;
+    /// ID склада
+    StoreId;
+    /// ID подразделения
+    DepartmentId;
+    /// ID поставщика (поставщик в iiko — сотрудник с признаком `supplier`)
+    SupplierId;
+    /// ID счета
+    AccountId;
+    /// ID сотрудника
+    EmployeeId;
+}
+
+text_code! {
+    /// Артикул элемента номенклатуры (`num`)
+    ProductArticle;
+    /// Код подразделения
+    DepartmentCode;
+    /// Код склада
+    StoreCode;
+    /// Код поставщика (табельный номер)
+    SupplierCode;
+    /// Код сотрудника (табельный номер)
+    EmployeeCode;
+}
+
+/// Необязательный ID из текста элемента XML: пустой элемент — `None`
+///
+/// Поля, которые раньше были строками, принимали `<storeId/>`; для них пустой
+/// текст по-прежнему означает отсутствие значения, а не ошибку разбора.
+pub(crate) fn deserialize_optional<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) if !text.trim().is_empty() => text
+            .parse()
+            .map(Some)
+            .map_err(|error| serde::de::Error::custom(format!("{}: {}", error, text.trim()))),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Line {
+        product_id: ProductId,
+        #[serde(default, deserialize_with = "deserialize_optional")]
+        store_id: Option<StoreId>,
+        num: Option<ProductArticle>,
+    }
+
+    const PRODUCT: &str = "4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f";
+
+    fn line(rest: &str) -> Result<Line, quick_xml::DeError> {
+        quick_xml::de::from_str(&format!(
+            "<line><productId>{PRODUCT}</productId>{rest}</line>"
+        ))
+    }
+
+    #[test]
+    fn reads_uuid_ids_and_keeps_leading_zeros_in_codes() {
+        let line = line("<num>00042</num>").unwrap();
+
+        assert_eq!(line.product_id.to_string(), PRODUCT);
+        assert_eq!(line.num.as_deref(), Some("00042"));
+    }
+
+    #[test]
+    fn reads_empty_optional_id_as_none() {
+        let line = line("<storeId/>").unwrap();
+
+        assert_eq!(line.store_id, None);
+    }
+
+    #[test]
+    fn writes_ids_and_codes_as_strings() {
+        let line = line("<storeId/><num>00042</num>").unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&line).unwrap(),
+            serde_json::json!({
+                "productId": PRODUCT,
+                "storeId": null,
+                "num": "00042",
+            })
+        );
+    }
+
+    #[test]
+    fn names_invalid_uuid_in_error() {
+        let error = line("<storeId>main</storeId>").unwrap_err();
+
+        assert!(error.to_string().contains("main"));
+    }
+}
@@ -1,12 +1,64 @@
+pub mod audit;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cassette;
 pub mod client;
+pub mod compression;
 pub mod config;
+pub mod date;
 pub mod endpoints;
 pub mod error;
+pub mod ids;
+pub mod middleware;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+pub mod money;
+pub mod pool;
+mod query;
+mod redact;
+pub mod retry;
+pub mod scheduler;
+pub mod server_info;
+pub mod session;
+pub mod session_store;
+pub mod streaming;
+#[cfg(feature = "tracing")]
+mod telemetry;
+pub mod transport;
+pub mod write_mode;
 pub mod xml;
 
-pub use client::IikoClient;
-pub use config::IikoConfig;
+pub use audit::{AuditEntry, AuditSink, JsonLinesAuditSink};
+pub use cassette::{Cassette, RecordingTransport, ReplayTransport};
+pub use client::{IikoClient, RequestOptions};
+pub use compression::{Compression, CompressionStats};
+pub use config::{Credentials, IikoConfig};
+pub use date::{DateParam, IikoDate, IikoDateTime};
+pub use endpoints::{
+    AssemblyChartsQuery, CounteragentBalanceQuery, DeliveryConsolidatedQuery,
+    DeliveryCouriersQuery, DeliveryLoyaltyQuery, DeliveryOrderCycleQuery, EmployeeSearch,
+    IngredientEntryQuery, InternalTransfersQuery, OlapReportV1Query, OutgoingInvoiceNumberQuery,
+    ProductExpenseQuery, ProductQuery, SalesQuery, StoreBalanceQuery, StoreOperationsQuery,
+    SupplierSearch,
+};
 pub use error::{IikoError, Result};
+pub use ids::{
+    AccountId, DepartmentCode, DepartmentId, EmployeeCode, EmployeeId, ProductArticle,
+    ProductCategoryId, ProductGroupId, ProductId, StoreCode, StoreId, SupplierCode, SupplierId,
+};
+pub use middleware::{ApiBody, ApiRequest, Middleware, Next};
+pub use money::Amount;
+pub use pool::{IikoClientPool, PooledClient, ServerHealth};
+pub use retry::{RetryOn, RetryPolicy};
+pub use scheduler::{Priority, PriorityStats, QueueStats};
+pub use server_info::{ServerInfo, ServerVersion};
+pub use session::SessionGuard;
+pub use session_store::{FileSessionStore, MemorySessionStore, SessionStore};
+pub use streaming::{OlapRow, OlapRowStream, ResponseStream};
+pub use transport::{
+    ReqwestTransport, StreamingResponse, Transport, TransportRequest, TransportResponse,
+};
+pub use write_mode::WriteMode;
 
 // Re-export commonly used types
 pub use xml::response::{
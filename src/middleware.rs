@@ -0,0 +1,230 @@
+//! Цепочка middleware вокруг запросов `IikoClient`
+//!
+//! Middleware видит каждый запрос к API (метод, endpoint, параметры, тело) до отправки
+//! и каждый ответ или ошибку после. Оно может изменить запрос (добавить заголовок),
+//! изменить ответ, вызвать `next` несколько раз или не вызывать вовсе и вернуть свой ответ.
+//!
+//! Middleware вызываются в порядке добавления через `IikoClient::with_middleware`: первое
+//! добавленное — внешнее. Внутри цепочки запрос встает в очередь, получает ключ сессии
+//! и повторяется по `RetryPolicy`; `/auth`, `/logout` и `get_server_info.jsp` через
+//! цепочку не проходят.
+//!
+//! Ответ в цепочке — [`StreamingResponse`]. У обычных методов тело уже прочитано
+//! и лежит одним куском, у потоковых (`stream_olap_report`, `load_into`, `get_stream`)
+//! читается по мере получения и держит очередь запросов, пока не будет дочитано.
+//!
+//! ```no_run
+//! use iiko_server_api_sdk::middleware::{ApiRequest, Middleware, Next};
+//! use iiko_server_api_sdk::transport::async_trait;
+//! use iiko_server_api_sdk::{IikoClient, IikoConfig, Result, StreamingResponse};
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//!
+//! struct Tenant {
+//!     id: String,
+//!     calls: AtomicU64,
+//! }
+//!
+//! #[async_trait]
+//! impl Middleware for Tenant {
+//!     async fn handle(&self, mut request: ApiRequest, next: Next<'_>) -> Result<StreamingResponse> {
+//!         self.calls.fetch_add(1, Ordering::Relaxed);
+//!         request.headers.push(("X-Tenant".to_string(), self.id.clone()));
+//!         next.run(request).await
+//!     }
+//! }
+//!
+//! # fn example() -> Result<()> {
+//! let client = IikoClient::new(IikoConfig::default())?.with_middleware(Tenant {
+//!     id: "cafe-17".to_string(),
+//!     calls: AtomicU64::new(0),
+//! });
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::IikoClient;
+use crate::error::{IikoError, Result};
+use crate::transport::{Method, StreamingResponse, async_trait};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Меняет ли запрос данные на сервере. От этого зависит, повторяется ли он по `RetryPolicy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Access {
+    Read,
+    Write,
+}
+
+/// Тело запроса к API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiBody {
+    Empty,
+    Xml(String),
+    Json(String),
+    /// `application/x-www-form-urlencoded`
+    Form(Vec<(String, String)>),
+}
+
+impl ApiBody {
+    pub fn content_type(&self) -> Option<&'static str> {
+        match self {
+            ApiBody::Empty => None,
+            ApiBody::Xml(_) => Some("application/xml"),
+            ApiBody::Json(_) => Some("application/json"),
+            ApiBody::Form(_) => Some("application/x-www-form-urlencoded"),
+        }
+    }
+
+    pub(crate) fn form(form: &[(&str, &str)]) -> Self {
+        ApiBody::Form(
+            form.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    /// Тело в том виде, в котором оно уйдет на сервер (форма — закодированной строкой)
+    pub fn text(&self) -> Result<Option<Cow<'_, str>>> {
+        Ok(match self {
+            ApiBody::Empty => None,
+            ApiBody::Xml(text) | ApiBody::Json(text) => Some(Cow::Borrowed(text)),
+            ApiBody::Form(form) => Some(Cow::Owned(encode_form(form)?)),
+        })
+    }
+
+    /// Тело для журналов и событий `tracing`: как [`ApiBody::text`], но без паролей
+    /// (`pass` в форме, `<password>` в XML, `"password"` в JSON)
+    pub(crate) fn redacted_text(&self) -> Result<Option<String>> {
+        Ok(match self {
+            ApiBody::Empty => None,
+            ApiBody::Xml(text) | ApiBody::Json(text) => Some(crate::redact::scrub_text(text)),
+            ApiBody::Form(form) => Some(encode_form(&crate::redact::scrub_params(form.clone()))?),
+        })
+    }
+}
+
+pub(crate) fn encode_form(form: &[(impl AsRef<str>, impl AsRef<str>)]) -> Result<String> {
+    let form: Vec<(&str, &str)> = form
+        .iter()
+        .map(|(name, value)| (name.as_ref(), value.as_ref()))
+        .collect();
+    serde_urlencoded::to_string(form)
+        .map_err(|e| IikoError::Configuration(format!("Failed to encode form data: {}", e)))
+}
+
+/// Запрос к API, проходящий через цепочку middleware
+///
+/// Ключ сессии в `params` не входит: он добавляется после цепочки.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ApiRequest {
+    pub method: Method,
+    /// Путь относительно `IikoConfig::base_url`, например `v2/entities/products/save`
+    pub endpoint: String,
+    /// Query-параметры
+    pub params: Vec<(String, String)>,
+    /// Дополнительные заголовки HTTP-запроса
+    pub headers: Vec<(String, String)>,
+    pub body: ApiBody,
+    access: Access,
+}
+
+impl ApiRequest {
+    pub(crate) fn new(
+        access: Access,
+        method: Method,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        body: ApiBody,
+    ) -> Self {
+        Self {
+            method,
+            endpoint: endpoint.to_string(),
+            params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            headers: Vec::new(),
+            body,
+            access,
+        }
+    }
+
+    /// Меняет ли запрос данные на сервере
+    ///
+    /// POST, который только читает данные (OLAP-отчет, списки с фильтрами), не считается
+    /// изменяющим, хотя метод у него POST.
+    pub fn is_mutating(&self) -> bool {
+        self.access == Access::Write
+    }
+
+    /// Первое значение query-параметра
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Обработчик в цепочке запросов клиента
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Обработать запрос. Чтобы передать его дальше, вызовите `next.run(request)`
+    async fn handle(&self, request: ApiRequest, next: Next<'_>) -> Result<StreamingResponse>;
+}
+
+/// Middleware, которое нужно читать после добавления в клиент (счетчики, журналы)
+#[async_trait]
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    async fn handle(&self, request: ApiRequest, next: Next<'_>) -> Result<StreamingResponse> {
+        (**self).handle(request, next).await
+    }
+}
+
+/// Как клиент читает ответ после цепочки
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReadMode {
+    /// Тело читается целиком внутри попытки: ошибка чтения повторяется по `RetryPolicy`
+    Buffered,
+    /// Тело отдается потоком вместе с очередью запросов
+    Streaming,
+}
+
+/// Оставшаяся часть цепочки
+///
+/// `Next` можно копировать, чтобы отправить запрос повторно.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a IikoClient,
+    middleware: &'a [Arc<dyn Middleware>],
+    mode: ReadMode,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        client: &'a IikoClient,
+        middleware: &'a [Arc<dyn Middleware>],
+        mode: ReadMode,
+    ) -> Self {
+        Self {
+            client,
+            middleware,
+            mode,
+        }
+    }
+
+    /// Передать запрос следующему middleware или отправить его на сервер
+    pub async fn run(self, request: ApiRequest) -> Result<StreamingResponse> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => {
+                let next = Next {
+                    middleware: rest,
+                    ..self
+                };
+                first.handle(request, next).await
+            }
+            None => self.client.dispatch(request, self.mode).await,
+        }
+    }
+}
@@ -0,0 +1,768 @@
+//! Локальный фейковый сервер iiko для офлайн-тестов
+//!
+//! Доступен при включенной фиче `mock-server`. Сервер слушает `127.0.0.1` на случайном
+//! порту, отдает фикстуры из `fixtures/` и хранит состояние для операций записи:
+//! сохраненный продукт появляется в следующем `products().find(...)`, импортированная
+//! накладная — в `imported_documents()`.
+//!
+//! Покрываемые пути (относительно `/resto/api`, кроме `get_server_info.jsp`):
+//! - `/resto/get_server_info.jsp` — версия из `set_server_version` (по умолчанию `9.1.8011.0`)
+//! - `auth`, `logout` — выдача и освобождение ключей с учетом слотов лицензии
+//! - `v2/entities/products/*` — список (GET/POST), save, update, delete, restore,
+//!   группы и пользовательские категории
+//! - `suppliers`, `suppliers/{code}/pricelist`
+//! - `employees`, `employees/byCode/{code}`, `employees/byId/{id}` (GET/PUT/POST/DELETE)
+//! - `corporation/departments`, `corporation/stores`, `replication/serverType`
+//! - `v2/reports/olap` — строки фикстуры с выбранными полями
+//! - `documents/import/*` — любые документы, результат валидации всегда успешный
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::ProductQuery;
+//! use iiko_server_api_sdk::mock_server::MockIikoServer;
+//!
+//! let server = MockIikoServer::start().await?;
+//! let client = server.client()?;
+//!
+//! let products = client.products().find(ProductQuery::new()).await?;
+//! assert!(!products.is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::IikoClient;
+use crate::config::IikoConfig;
+use crate::ids::{ProductCategoryId, ProductId};
+use crate::xml::response::{
+    CategoryDeleteRequest, CategoryOperationResult, CategorySaveRequest, CategoryUpdateRequest,
+    DocumentValidationResult, Employee, Employees, EntityDto, ErrorDto, ItemsRequest,
+    OlapFieldValue, OlapReportRequest, OlapReportResponse, ProductDto, ProductGroupDto,
+    ProductOperationResult, ProductsOperationResult, Supplier, Suppliers,
+};
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{RawQuery, State};
+use axum::http::{Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Логин, который принимает фейковый сервер
+pub const MOCK_LOGIN: &str = "admin";
+/// Пароль (SHA-1 хеш), который принимает фейковый сервер
+pub const MOCK_PASSWORD: &str = "d033e22ae348aeb5660fc2140aec35850c4da997";
+
+const API_PREFIX: &str = "/resto/api";
+const SERVER_INFO_PATH: &str = "/resto/get_server_info.jsp";
+
+/// Документ, принятый через `documents/import/*`
+#[derive(Debug, Clone)]
+pub struct ImportedDocument {
+    /// Тип документа из пути, например `incomingInvoice`
+    pub kind: String,
+    pub document_number: Option<String>,
+    /// XML тела запроса как есть
+    pub xml: String,
+}
+
+/// Состояние фейкового сервера
+struct MockState {
+    sessions: HashSet<String>,
+    license_slots: usize,
+    server_version: String,
+    products: Vec<ProductDto>,
+    product_groups: Vec<ProductGroupDto>,
+    categories: Vec<EntityDto>,
+    suppliers: Vec<Supplier>,
+    employees: Vec<Employee>,
+    olap_rows: Vec<HashMap<String, OlapFieldValue>>,
+    documents: Vec<ImportedDocument>,
+}
+
+impl MockState {
+    fn from_fixtures() -> Self {
+        let suppliers: Suppliers = quick_xml::de::from_str(include_str!("fixtures/suppliers.xml"))
+            .expect("suppliers fixture must be valid");
+        let employees: Employees = quick_xml::de::from_str(include_str!("fixtures/employees.xml"))
+            .expect("employees fixture must be valid");
+
+        Self {
+            sessions: HashSet::new(),
+            license_slots: 5,
+            server_version: "9.1.8011.0".to_string(),
+            products: serde_json::from_str(include_str!("fixtures/products.json"))
+                .expect("products fixture must be valid"),
+            product_groups: serde_json::from_str(include_str!("fixtures/product_groups.json"))
+                .expect("product groups fixture must be valid"),
+            categories: serde_json::from_str(include_str!("fixtures/product_categories.json"))
+                .expect("categories fixture must be valid"),
+            suppliers: suppliers.items,
+            employees: employees.items,
+            olap_rows: serde_json::from_str(include_str!("fixtures/olap_sales.json"))
+                .expect("olap fixture must be valid"),
+            documents: Vec::new(),
+        }
+    }
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+/// Запущенный фейковый сервер iiko
+///
+/// Сервер работает в фоновой задаче текущего tokio runtime до вызова `shutdown()`
+/// или до остановки runtime. Удаление `MockIikoServer` сервер не останавливает,
+/// поэтому клиент можно отдать дальше, не храня сам сервер.
+pub struct MockIikoServer {
+    addr: SocketAddr,
+    state: SharedState,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockIikoServer {
+    /// Запустить сервер на свободном порту `127.0.0.1`
+    pub async fn start() -> std::io::Result<Self> {
+        let state: SharedState = Arc::new(Mutex::new(MockState::from_fixtures()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let app = Router::new().fallback(handle).with_state(state.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown_tx),
+        })
+    }
+
+    /// Базовый URL в формате `IikoConfig::base_url`
+    pub fn base_url(&self) -> String {
+        format!("http://{}{}", self.addr, API_PREFIX)
+    }
+
+    /// Конфигурация с учетными данными, которые принимает сервер
+    pub fn config(&self) -> IikoConfig {
+        IikoConfig::new(self.base_url(), MOCK_LOGIN, MOCK_PASSWORD)
+    }
+
+    /// Клиент, настроенный на этот сервер
+    pub fn client(&self) -> crate::Result<IikoClient> {
+        IikoClient::new(self.config())
+    }
+
+    /// Количество ключей, занимающих слот лицензии
+    pub fn active_sessions(&self) -> usize {
+        self.lock().sessions.len()
+    }
+
+    /// Сбросить все выданные ключи, как при перезагрузке сервера iiko
+    pub fn expire_sessions(&self) {
+        self.lock().sessions.clear();
+    }
+
+    /// Задать количество слотов лицензии (по умолчанию 5)
+    pub fn set_license_slots(&self, slots: usize) {
+        self.lock().license_slots = slots;
+    }
+
+    /// Задать версию, которую возвращает `get_server_info.jsp`, например `7.8.1012.0`
+    pub fn set_server_version(&self, version: impl Into<String>) {
+        self.lock().server_version = version.into();
+    }
+
+    /// Текущий список номенклатуры, включая удаленные элементы
+    pub fn products(&self) -> Vec<ProductDto> {
+        self.lock().products.clone()
+    }
+
+    /// Документы, принятые через `documents/import/*`
+    pub fn imported_documents(&self) -> Vec<ImportedDocument> {
+        self.lock().documents.clone()
+    }
+
+    /// Заменить строки, из которых строится ответ `v2/reports/olap`
+    pub fn set_olap_rows(&self, rows: Vec<HashMap<String, OlapFieldValue>>) {
+        self.lock().olap_rows = rows;
+    }
+
+    /// Остановить сервер
+    pub fn shutdown(mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        lock(&self.state)
+    }
+}
+
+fn lock(state: &SharedState) -> MutexGuard<'_, MockState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn text(status: StatusCode, body: impl Into<String>) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        body.into(),
+    )
+        .into_response()
+}
+
+fn xml(status: StatusCode, body: impl Into<String>) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        body.into(),
+    )
+        .into_response()
+}
+
+fn json<T: serde::Serialize>(value: &T) -> Response {
+    match serde_json::to_string(value) {
+        Ok(body) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+            body,
+        )
+            .into_response(),
+        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn to_xml<T: serde::Serialize>(value: &T) -> Response {
+    match serialize_xml(value) {
+        Ok(body) => xml(StatusCode::OK, body),
+        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Сериализовать DTO так, как отвечает iiko: незаполненные поля не передаются
+///
+/// quick-xml пишет `None` как пустой элемент (`<mainRoleId/>`), а такой элемент
+/// не разбирается обратно в `Option<Uuid>`.
+fn serialize_xml<T: serde::Serialize>(value: &T) -> Result<String, quick_xml::SeError> {
+    let xml = quick_xml::se::to_string(value)?;
+    let mut result = String::with_capacity(xml.len());
+    let mut rest = xml.as_str();
+
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = &rest[..end];
+        let is_empty_element = tag.ends_with("/>")
+            && tag[1..tag.len() - 2]
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == ':');
+        if !is_empty_element {
+            result.push_str(tag);
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+fn bad_request(error: impl std::fmt::Display) -> Response {
+    text(StatusCode::BAD_REQUEST, error.to_string())
+}
+
+/// Параметры запроса: query и form data в одном списке, ключи могут повторяться
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn parse(query: Option<&str>, method: &Method, body: &[u8]) -> Self {
+        let mut params: Vec<(String, String)> =
+            serde_urlencoded::from_str(query.unwrap_or_default()).unwrap_or_default();
+
+        let is_form = std::str::from_utf8(body)
+            .map(|body| !body.trim_start().starts_with(['<', '{', '[']))
+            .unwrap_or(false);
+        if *method == Method::POST && is_form {
+            params.extend(
+                serde_urlencoded::from_bytes::<Vec<(String, String)>>(body).unwrap_or_default(),
+            );
+        }
+
+        Self(params)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn all(&self, name: &str) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.get(name) == Some("true")
+    }
+}
+
+async fn handle(
+    State(state): State<SharedState>,
+    method: Method,
+    uri: Uri,
+    RawQuery(query): RawQuery,
+    body: Bytes,
+) -> Response {
+    if uri.path() == SERVER_INFO_PATH {
+        return server_info(&state);
+    }
+
+    let Some(path) = uri.path().strip_prefix(API_PREFIX) else {
+        return text(StatusCode::NOT_FOUND, "Not found");
+    };
+    let path = path.trim_matches('/');
+    let segments: Vec<&str> = path.split('/').collect();
+    let params = Params::parse(query.as_deref(), &method, &body);
+
+    match (&method, segments.as_slice()) {
+        (&Method::POST, ["auth"]) => return auth(&state, &params),
+        (_, ["logout"]) => return logout(&state, &params),
+        _ => {}
+    }
+
+    let authorized = params
+        .get("key")
+        .is_some_and(|key| lock(&state).sessions.contains(key));
+    if !authorized {
+        return text(StatusCode::UNAUTHORIZED, "Token is expired or invalid");
+    }
+
+    let body = String::from_utf8_lossy(&body);
+
+    match (&method, segments.as_slice()) {
+        (&Method::GET | &Method::POST, ["v2", "entities", "products", "list"]) => {
+            list_products(&state, &params)
+        }
+        (&Method::POST, ["v2", "entities", "products", "save"]) => save_product(&state, &body),
+        (&Method::POST, ["v2", "entities", "products", "update"]) => update_product(&state, &body),
+        (
+            &Method::POST,
+            [
+                "v2",
+                "entities",
+                "products",
+                action @ ("delete" | "restore"),
+            ],
+        ) => set_products_deleted(&state, &body, *action == "delete"),
+        (&Method::GET, ["v2", "entities", "products", "group", "list"]) => {
+            let state = lock(&state);
+            let include_deleted = params.flag("includeDeleted");
+            let groups: Vec<&ProductGroupDto> = state
+                .product_groups
+                .iter()
+                .filter(|group| include_deleted || !group.deleted)
+                .collect();
+            json(&groups)
+        }
+        (&Method::GET | &Method::POST, ["v2", "entities", "products", "category", "list"]) => {
+            let state = lock(&state);
+            let include_deleted = params.flag("includeDeleted");
+            let categories: Vec<&EntityDto> = state
+                .categories
+                .iter()
+                .filter(|category| include_deleted || !category.deleted)
+                .collect();
+            json(&categories)
+        }
+        (&Method::POST, ["v2", "entities", "products", "category", action]) => {
+            change_category(&state, action, &body)
+        }
+        (&Method::GET, ["suppliers"]) => to_xml(&Suppliers {
+            items: lock(&state).suppliers.clone(),
+        }),
+        (&Method::GET, ["suppliers", code, "pricelist"]) => {
+            if lock(&state).suppliers.iter().any(|s| s.code == *code) {
+                xml(
+                    StatusCode::OK,
+                    include_str!("fixtures/supplier_pricelist.xml"),
+                )
+            } else {
+                text(
+                    StatusCode::NOT_FOUND,
+                    format!("Supplier with code {} not found", code),
+                )
+            }
+        }
+        (&Method::GET, ["employees"]) => {
+            let state = lock(&state);
+            let include_deleted = params.flag("includeDeleted");
+            to_xml(&Employees {
+                items: state
+                    .employees
+                    .iter()
+                    .filter(|e| include_deleted || e.deleted.as_deref() != Some("true"))
+                    .cloned()
+                    .collect(),
+            })
+        }
+        (&Method::GET, ["employees", "byCode", code]) => {
+            match lock(&state).employees.iter().find(|e| e.code == *code) {
+                Some(employee) => to_xml(employee),
+                None => text(
+                    StatusCode::NOT_FOUND,
+                    format!("Entity of class User not found by code {}", code),
+                ),
+            }
+        }
+        (_, ["employees", "byId", id]) => employee_by_id(&state, &method, id, &body),
+        (&Method::GET, ["corporation", "departments"]) => {
+            xml(StatusCode::OK, include_str!("fixtures/departments.xml"))
+        }
+        (&Method::GET, ["corporation", "stores"]) => {
+            xml(StatusCode::OK, include_str!("fixtures/stores.xml"))
+        }
+        (&Method::GET, ["replication", "serverType"]) => {
+            xml(StatusCode::OK, "<serverType>STANDALONE_RMS</serverType>")
+        }
+        (&Method::POST, ["v2", "reports", "olap"]) => olap_report(&state, &body),
+        (&Method::POST, ["documents", "import", kind]) => import_document(&state, kind, &body),
+        _ => text(StatusCode::NOT_FOUND, format!("Unknown path: {}", path)),
+    }
+}
+
+fn server_info(state: &SharedState) -> Response {
+    let version = lock(state).server_version.clone();
+    xml(
+        StatusCode::OK,
+        format!(
+            "<r><serverName>Mock iiko</serverName><edition>default</edition>\
+             <version>{}</version><serverState>STARTED_SUCCESSFULLY</serverState></r>",
+            version
+        ),
+    )
+}
+
+fn auth(state: &SharedState, params: &Params) -> Response {
+    if params.get("login") != Some(MOCK_LOGIN) || params.get("pass") != Some(MOCK_PASSWORD) {
+        return text(StatusCode::UNAUTHORIZED, "Wrong login or password");
+    }
+
+    let mut state = lock(state);
+    if state.sessions.len() >= state.license_slots {
+        return text(StatusCode::FORBIDDEN, "License enhancement is required");
+    }
+
+    let key = Uuid::new_v4().to_string();
+    state.sessions.insert(key.clone());
+    text(StatusCode::OK, key)
+}
+
+fn logout(state: &SharedState, params: &Params) -> Response {
+    match params.get("key") {
+        Some(key) => {
+            lock(state).sessions.remove(key);
+            text(StatusCode::OK, "")
+        }
+        None => bad_request("key is required"),
+    }
+}
+
+fn list_products(state: &SharedState, params: &Params) -> Response {
+    let state = lock(state);
+    let include_deleted = params.flag("includeDeleted");
+    let ids = params.all("ids");
+    let nums = params.all("nums");
+    let types = params.all("types");
+
+    let products: Vec<&ProductDto> = state
+        .products
+        .iter()
+        .filter(|p| include_deleted || !p.deleted)
+        .filter(|p| {
+            ids.is_empty()
+                || p.id
+                    .is_some_and(|id| ids.iter().any(|wanted| *wanted == id.to_string()))
+        })
+        .filter(|p| nums.is_empty() || p.num.as_deref().is_some_and(|n| nums.contains(&n)))
+        .filter(|p| types.is_empty() || p.r#type.as_deref().is_some_and(|t| types.contains(&t)))
+        .collect();
+
+    json(&products)
+}
+
+fn save_product(state: &SharedState, body: &str) -> Response {
+    let mut product: ProductDto = match serde_json::from_str(body) {
+        Ok(product) => product,
+        Err(e) => return bad_request(e),
+    };
+
+    product.id = Some(ProductId::from(Uuid::new_v4()));
+    product.deleted = false;
+    lock(state).products.push(product.clone());
+
+    json(&ProductOperationResult {
+        result: "SUCCESS".to_string(),
+        errors: None,
+        response: product,
+    })
+}
+
+fn update_product(state: &SharedState, body: &str) -> Response {
+    let product: ProductDto = match serde_json::from_str(body) {
+        Ok(product) => product,
+        Err(e) => return bad_request(e),
+    };
+
+    let mut state = lock(state);
+    let existing = product
+        .id
+        .and_then(|id| state.products.iter_mut().find(|p| p.id == Some(id)));
+
+    match existing {
+        Some(existing) => {
+            *existing = product.clone();
+            json(&ProductOperationResult {
+                result: "SUCCESS".to_string(),
+                errors: None,
+                response: product,
+            })
+        }
+        None => json(&ProductOperationResult {
+            result: "ERROR".to_string(),
+            errors: Some(vec![ErrorDto {
+                code: Some("ENTITY_NOT_FOUND".to_string()),
+                value: product.id.map(|id| id.to_string()),
+                message: Some("Product not found".to_string()),
+            }]),
+            response: product,
+        }),
+    }
+}
+
+fn set_products_deleted(state: &SharedState, body: &str, deleted: bool) -> Response {
+    let request: ItemsRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return bad_request(e),
+    };
+
+    let mut state = lock(state);
+    let ids: Vec<ProductId> = request.items.iter().map(|item| item.id.into()).collect();
+
+    let wrong_state: Vec<String> = state
+        .products
+        .iter()
+        .filter(|p| p.id.is_some_and(|id| ids.contains(&id)) && p.deleted == deleted)
+        .filter_map(|p| p.id.map(|id| id.to_string()))
+        .collect();
+    if !wrong_state.is_empty() {
+        let message = if deleted {
+            "Could not delete already deleted products"
+        } else {
+            "Could not restore not deleted products"
+        };
+        return text(
+            StatusCode::CONFLICT,
+            format!("{}: [{}]", message, wrong_state.join(", ")),
+        );
+    }
+
+    let mut changed = Vec::new();
+    for product in state
+        .products
+        .iter_mut()
+        .filter(|p| p.id.is_some_and(|id| ids.contains(&id)))
+    {
+        product.deleted = deleted;
+        changed.push(product.clone());
+    }
+
+    json(&ProductsOperationResult {
+        result: "SUCCESS".to_string(),
+        errors: None,
+        response: Some(changed),
+    })
+}
+
+fn change_category(state: &SharedState, action: &str, body: &str) -> Response {
+    let mut state = lock(state);
+
+    let category = match action {
+        "save" => match serde_json::from_str::<CategorySaveRequest>(body) {
+            Ok(request) => {
+                let category = EntityDto {
+                    id: ProductCategoryId::from(Uuid::new_v4()),
+                    root_type: Some("ProductCategory".to_string()),
+                    deleted: false,
+                    code: None,
+                    name: Some(request.name),
+                };
+                state.categories.push(category.clone());
+                Some(category)
+            }
+            Err(e) => return bad_request(e),
+        },
+        "update" => match serde_json::from_str::<CategoryUpdateRequest>(body) {
+            Ok(request) => state
+                .categories
+                .iter_mut()
+                .find(|c| c.id == request.id)
+                .map(|c| {
+                    c.name = Some(request.name);
+                    c.clone()
+                }),
+            Err(e) => return bad_request(e),
+        },
+        "delete" | "restore" => match serde_json::from_str::<CategoryDeleteRequest>(body) {
+            Ok(request) => state
+                .categories
+                .iter_mut()
+                .find(|c| c.id == request.id)
+                .map(|c| {
+                    c.deleted = action == "delete";
+                    c.clone()
+                }),
+            Err(e) => return bad_request(e),
+        },
+        _ => return text(StatusCode::NOT_FOUND, format!("Unknown action: {}", action)),
+    };
+
+    match category {
+        Some(category) => json(&CategoryOperationResult {
+            result: "SUCCESS".to_string(),
+            errors: None,
+            response: category,
+        }),
+        None => text(StatusCode::NOT_FOUND, "Product category not found"),
+    }
+}
+
+fn employee_by_id(state: &SharedState, method: &Method, id: &str, body: &str) -> Response {
+    let Ok(id) = Uuid::parse_str(id) else {
+        return bad_request(format!("Invalid UUID: {}", id));
+    };
+    let not_found = || {
+        text(
+            StatusCode::NOT_FOUND,
+            format!("Entity of class User not found by id {}", id),
+        )
+    };
+
+    let mut state = lock(state);
+    let position = state.employees.iter().position(|e| e.id == id);
+
+    match *method {
+        Method::GET => match position {
+            Some(index) => to_xml(&state.employees[index]),
+            None => not_found(),
+        },
+        Method::PUT | Method::POST => {
+            let mut employee: Employee = match quick_xml::de::from_str(body) {
+                Ok(employee) => employee,
+                Err(e) => return bad_request(e),
+            };
+            employee.id = id.into();
+
+            match (position, method) {
+                (Some(index), _) => {
+                    state.employees[index] = employee.clone();
+                    to_xml(&employee)
+                }
+                (None, &Method::PUT) => {
+                    state.employees.push(employee.clone());
+                    match serialize_xml(&employee) {
+                        Ok(body) => xml(StatusCode::CREATED, body),
+                        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+                    }
+                }
+                (None, _) => not_found(),
+            }
+        }
+        Method::DELETE => match position {
+            Some(index) => {
+                state.employees[index].deleted = Some("true".to_string());
+                text(StatusCode::OK, "")
+            }
+            None => not_found(),
+        },
+        _ => text(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+    }
+}
+
+fn olap_report(state: &SharedState, body: &str) -> Response {
+    let request: OlapReportRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return bad_request(e),
+    };
+
+    let fields: Vec<&String> = request
+        .group_by_row_fields
+        .iter()
+        .chain(request.group_by_col_fields.iter().flatten())
+        .chain(request.aggregate_fields.iter())
+        .collect();
+
+    let data = lock(state)
+        .olap_rows
+        .iter()
+        .map(|row| {
+            fields
+                .iter()
+                .map(|field| {
+                    let value = row.get(*field).cloned().unwrap_or(OlapFieldValue::Null);
+                    ((*field).clone(), value)
+                })
+                .collect()
+        })
+        .collect();
+
+    json(&OlapReportResponse {
+        data,
+        summary: Vec::new(),
+    })
+}
+
+fn import_document(state: &SharedState, kind: &str, body: &str) -> Response {
+    #[derive(Deserialize)]
+    struct DocumentHeader {
+        #[serde(rename = "documentNumber", default)]
+        document_number: Option<String>,
+    }
+
+    let header: DocumentHeader = match quick_xml::de::from_str(body) {
+        Ok(header) => header,
+        Err(e) => return bad_request(e),
+    };
+
+    lock(state).documents.push(ImportedDocument {
+        kind: kind.to_string(),
+        document_number: header.document_number.clone(),
+        xml: body.to_string(),
+    });
+
+    to_xml(&DocumentValidationResult {
+        valid: true,
+        warning: false,
+        document_number: header.document_number,
+        other_suggested_number: None,
+        error_message: None,
+        additional_info: None,
+    })
+}
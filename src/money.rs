@@ -0,0 +1,172 @@
+//! Денежные суммы и количества
+//!
+//! Суммы, цены и количества в балансах, строках приходных накладных, прайс-листах
+//! поставщиков, ценах номенклатуры и денежных колонках OLAP имеют тип [`Amount`]:
+//!
+//! - по умолчанию `f64`
+//! - с фичей `rust_decimal` — `rust_decimal::Decimal`. Десятичные строки iiko (`150.75`
+//!   в XML) разбираются без потерь, и сумма строк накладной сходится с итогом iiko
+//!   до копейки
+//!
+//! ```no_run
+//! # #[cfg(feature = "rust_decimal")]
+//! # fn example(invoice: &iiko_server_api_sdk::IncomingInvoiceDto) {
+//! use iiko_server_api_sdk::money::Amount;
+//!
+//! let total: Amount = invoice
+//!     .items
+//!     .iter()
+//!     .flat_map(|items| &items.items)
+//!     .map(|item| item.sum)
+//!     .sum();
+//! # }
+//! ```
+//!
+//! # Важно
+//! - Фича меняет типы полей: код, который заполняет эти поля литералами `f64`, с ней
+//!   не собирается. `Amount::from(100)` работает в обоих вариантах
+//! - В XML `Decimal` записывается так же, как `f64` (`150.75`). В JSON-запросах
+//!   (сохранение номенклатуры) цена передается числом, как и без фичи
+//! - Фича включает `arbitrary_precision` в `serde_json`: JSON-числа (v2, OLAP) читаются
+//!   и записываются по тексту, без промежуточного `f64`. Флаг общий для всей сборки,
+//!   так что `serde_json::Value` в коде приложения тоже хранит числа текстом
+
+use serde::{Deserialize, Deserializer, Serializer, de};
+
+/// Тип сумм, цен и количеств: `f64` или, с фичей `rust_decimal`, `rust_decimal::Decimal`
+#[cfg(not(feature = "rust_decimal"))]
+pub type Amount = f64;
+
+/// Тип сумм, цен и количеств: `f64` или, с фичей `rust_decimal`, `rust_decimal::Decimal`
+#[cfg(feature = "rust_decimal")]
+pub type Amount = rust_decimal::Decimal;
+
+/// Значение как `f64` (для `Decimal` — ближайшее)
+pub(crate) fn to_f64(amount: Amount) -> f64 {
+    #[cfg(feature = "rust_decimal")]
+    {
+        use rust_decimal::prelude::ToPrimitive;
+        amount.to_f64().unwrap_or_default()
+    }
+    #[cfg(not(feature = "rust_decimal"))]
+    {
+        amount
+    }
+}
+
+/// Разобрать сумму из текста элемента XML
+///
+/// quick-xml передает `Decimal` элемент как map, поэтому текст читается строкой
+/// и разбирается как десятичное число (`Decimal` — без округления).
+pub(crate) fn deserialize_text<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Amount, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse(&text).map_err(de::Error::custom)
+}
+
+/// Как [`deserialize_text`], пустой элемент — `None`
+pub(crate) fn deserialize_text_option<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Amount>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) if !text.trim().is_empty() => parse(&text).map(Some).map_err(de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+fn parse(text: &str) -> std::result::Result<Amount, String> {
+    let text = text.trim();
+    #[cfg(feature = "rust_decimal")]
+    let amount = text
+        .parse::<Amount>()
+        .or_else(|_| Amount::from_scientific(text));
+    #[cfg(not(feature = "rust_decimal"))]
+    let amount = text.parse::<Amount>();
+    amount.map_err(|_| format!("invalid decimal number: {}", text))
+}
+
+/// Сериализовать сумму JSON-числом: `Decimal` по умолчанию сериализуется строкой
+///
+/// `Decimal` записывается числом по своему тексту (`arbitrary_precision`), без `f64`.
+pub(crate) fn serialize_number<S: Serializer>(
+    amount: &Option<Amount>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    #[cfg(feature = "rust_decimal")]
+    {
+        rust_decimal::serde::arbitrary_precision_option::serialize(amount, serializer)
+    }
+    #[cfg(not(feature = "rust_decimal"))]
+    {
+        match amount {
+            Some(amount) => serializer.serialize_f64(*amount),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rust_decimal"))]
+mod tests {
+    use super::*;
+    use crate::xml::response::{IncomingInvoiceItemDto, OlapFieldValue, ProductDto};
+    use std::str::FromStr;
+
+    const PRODUCT_ID: &str = "4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f";
+
+    fn amount(text: &str) -> Amount {
+        Amount::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn parses_xml_decimals_without_loss() {
+        let item: IncomingInvoiceItemDto = quick_xml::de::from_str(
+            "<item><num>1</num><amount>3</amount><price>0.1</price><sum>0.3</sum></item>",
+        )
+        .unwrap();
+
+        assert_eq!(item.sum, amount("0.3"));
+        assert_eq!(item.amount.unwrap() * item.price.unwrap(), item.sum);
+    }
+
+    #[test]
+    fn parses_json_prices_beyond_f64_precision() {
+        let product: ProductDto = serde_json::from_str(&format!(
+            r#"{{"id":"{PRODUCT_ID}","defaultSalePrice":12345678901234567.89}}"#
+        ))
+        .unwrap();
+
+        assert_eq!(
+            product.default_sale_price,
+            Some(amount("12345678901234567.89"))
+        );
+    }
+
+    #[test]
+    fn writes_json_prices_as_exact_numbers() {
+        let product: ProductDto = serde_json::from_str(&format!(
+            r#"{{"id":"{PRODUCT_ID}","defaultSalePrice":12345678901234567.89}}"#
+        ))
+        .unwrap();
+
+        let json = serde_json::to_string(&product).unwrap();
+
+        assert!(json.contains(r#""defaultSalePrice":12345678901234567.89"#));
+    }
+
+    #[test]
+    fn reads_olap_money_columns_as_decimals() {
+        let value: OlapFieldValue = serde_json::from_str("12345678901234567.89").unwrap();
+
+        assert_eq!(value.as_decimal(), Some(amount("12345678901234567.89")));
+    }
+
+    #[test]
+    fn keeps_olap_integers_and_floats_apart() {
+        let integer: OlapFieldValue = serde_json::from_str("12").unwrap();
+        let float: OlapFieldValue = serde_json::from_str("1234.56").unwrap();
+
+        assert_eq!(integer.as_integer(), Some(12));
+        assert_eq!(float.as_float(), Some(1234.56));
+    }
+}
@@ -0,0 +1,466 @@
+//! Пул клиентов для сети ресторанов с несколькими серверами iiko
+//!
+//! [`IikoClientPool`] хранит `IikoConfig` для каждого сервера (по идентификатору
+//! арендатора или сервера) и создает `IikoClient` только при первом обращении.
+//! Пул ограничивает количество одновременно занятых слотов лицензии по всем серверам,
+//! освобождает простаивающие сессии через `/logout` и собирает состояние каждого
+//! сервера по результатам запросов.
+//!
+//! ```no_run
+//! # async fn example() -> iiko_server_api_sdk::Result<()> {
+//! use iiko_server_api_sdk::{IikoClientPool, IikoConfig};
+//! use std::time::Duration;
+//!
+//! let pool = IikoClientPool::new(5).with_idle_timeout(Duration::from_secs(300));
+//! pool.register("msk-01", IikoConfig::new("https://msk-01.iiko.it/resto/api", "api", "hash"));
+//! pool.register("spb-01", IikoConfig::new("https://spb-01.iiko.it/resto/api", "api", "hash"));
+//! pool.spawn_idle_reaper(Duration::from_secs(60));
+//!
+//! let client = pool.get("msk-01").await?;
+//! let suppliers = client.suppliers().list(None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::IikoClient;
+use crate::config::IikoConfig;
+use crate::error::{IikoError, Result};
+use crate::transport::{
+    StreamingResponse, Transport, TransportRequest, TransportResponse, async_trait,
+};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+/// Время простоя по умолчанию, после которого сессия освобождается
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Состояние сервера по результатам последних запросов
+#[derive(Debug, Clone, Default)]
+pub struct ServerHealth {
+    /// Время последнего ответа без ошибки 5xx
+    pub last_success: Option<SystemTime>,
+    /// Время последней ошибки соединения или ответа 5xx
+    pub last_failure: Option<SystemTime>,
+    /// Текст последней ошибки
+    pub last_error: Option<String>,
+    /// Ошибки подряд с момента последнего успешного запроса
+    pub consecutive_failures: u32,
+    /// Всего запросов к серверу через пул
+    pub requests: u64,
+    /// Занимает ли пул слот лицензии на этом сервере
+    pub session_active: bool,
+}
+
+impl ServerHealth {
+    /// Последний запрос к серверу завершился без ошибки соединения и без 5xx
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+
+    fn record(&mut self, result: &Result<TransportResponse>) {
+        self.requests += 1;
+
+        let error = match result {
+            Ok(response) if response.status.is_server_error() => {
+                Some(format!("HTTP {}: {}", response.status, response.text()))
+            }
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+
+        match error {
+            Some(error) => {
+                self.last_failure = Some(SystemTime::now());
+                self.last_error = Some(error);
+                self.consecutive_failures += 1;
+            }
+            None => {
+                self.last_success = Some(SystemTime::now());
+                self.consecutive_failures = 0;
+            }
+        }
+    }
+}
+
+/// Транспорт, который записывает результат каждого запроса в `ServerHealth`
+struct HealthTransport<T> {
+    inner: T,
+    health: Arc<Mutex<ServerHealth>>,
+}
+
+#[async_trait]
+impl<T: Transport> Transport for HealthTransport<T> {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let result = self.inner.send(request).await;
+        lock(&self.health).record(&result);
+        result
+    }
+
+    async fn send_streaming(&self, request: TransportRequest) -> Result<StreamingResponse> {
+        let result = match self.inner.send_streaming(request).await {
+            Ok(response) if !response.status.is_server_error() => {
+                let status = TransportResponse::new(response.status, Vec::new());
+                lock(&self.health).record(&Ok(status));
+                return Ok(response);
+            }
+            // Тело ответа 5xx нужно для `last_error`, оно небольшое
+            Ok(response) => response.into_response().await,
+            Err(error) => Err(error),
+        };
+
+        lock(&self.health).record(&result);
+        result.map(StreamingResponse::from)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Сервер, зарегистрированный в пуле
+struct Server {
+    config: IikoConfig,
+    health: Arc<Mutex<ServerHealth>>,
+    client: Option<IikoClient>,
+    /// Слот лицензии, занятый клиентом этого сервера
+    permit: Option<OwnedSemaphorePermit>,
+    /// Количество ссылок больше одной, пока снаружи есть `PooledClient`
+    lease: Arc<()>,
+    last_used: Instant,
+}
+
+impl Server {
+    fn in_use(&self) -> bool {
+        Arc::strong_count(&self.lease) > 1
+    }
+}
+
+struct PoolInner {
+    servers: Mutex<HashMap<String, Server>>,
+    slots: Arc<Semaphore>,
+    max_sessions: usize,
+    /// Сигнал о возврате клиента или освобождении слота
+    released: Notify,
+}
+
+/// Реестр клиентов iiko по идентификатору сервера
+///
+/// # Важно
+/// - Клиент создается при первом `get()`, слот лицензии занимается на время жизни сессии
+/// - Если все `max_sessions` слотов заняты, `get()` освобождает сессию сервера, к которому
+///   дольше всех не обращались и чей клиент сейчас не используется. Если таких нет,
+///   `get()` ждет возврата одного из клиентов
+/// - Слот вытесненного сервера переходит к новому только после `/logout` старой сессии
+/// - Сессия считается используемой, пока жив `PooledClient`. Клоны `IikoClient`,
+///   полученные из `PooledClient`, пулом не отслеживаются
+#[derive(Clone)]
+pub struct IikoClientPool {
+    inner: Arc<PoolInner>,
+    idle_timeout: Duration,
+}
+
+impl IikoClientPool {
+    /// Создать пул, который держит не больше `max_sessions` слотов лицензии одновременно
+    pub fn new(max_sessions: usize) -> Self {
+        let max_sessions = max_sessions.max(1);
+
+        Self {
+            inner: Arc::new(PoolInner {
+                servers: Mutex::new(HashMap::new()),
+                slots: Arc::new(Semaphore::new(max_sessions)),
+                max_sessions,
+                released: Notify::new(),
+            }),
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Время простоя, после которого `logout_idle()` освобождает сессию (по умолчанию 10 минут)
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Зарегистрировать сервер. Если сервер с таким идентификатором уже есть,
+    /// его конфигурация заменяется, а старая сессия освобождается в фоне после
+    /// возврата всех выданных для нее `PooledClient`
+    pub fn register(&self, server_id: impl Into<String>, config: IikoConfig) {
+        let server = Server {
+            config,
+            health: Arc::new(Mutex::new(ServerHealth::default())),
+            client: None,
+            permit: None,
+            lease: Arc::new(()),
+            last_used: Instant::now(),
+        };
+
+        let previous = lock(&self.inner.servers).insert(server_id.into(), server);
+        if let Some(previous) = previous {
+            self.release_in_background(previous);
+        }
+    }
+
+    /// Удалить сервер из пула и освободить его сессию
+    pub async fn remove(&self, server_id: &str) -> Result<()> {
+        let server = lock(&self.inner.servers).remove(server_id);
+        match server {
+            Some(server) => self.release(server.client, server.permit).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Идентификаторы зарегистрированных серверов
+    pub fn server_ids(&self) -> Vec<String> {
+        lock(&self.inner.servers).keys().cloned().collect()
+    }
+
+    /// Получить клиент сервера, при необходимости создав его и заняв слот лицензии
+    ///
+    /// # Что в ответе
+    /// - `PooledClient`, который разыменовывается в `IikoClient`
+    /// - `IikoError::Configuration`, если сервер не зарегистрирован
+    pub async fn get(&self, server_id: &str) -> Result<PooledClient> {
+        loop {
+            let released = self.inner.released.notified();
+
+            let victim = {
+                let mut servers = lock(&self.inner.servers);
+                let server = servers.get_mut(server_id).ok_or_else(|| {
+                    IikoError::Configuration(format!("Unknown iiko server: {}", server_id))
+                })?;
+                server.last_used = Instant::now();
+
+                if server.permit.is_none() {
+                    server.permit = self.inner.slots.clone().try_acquire_owned().ok();
+                }
+
+                if server.permit.is_some() {
+                    return self.lease(server);
+                }
+
+                let victim_id = servers
+                    .iter()
+                    .filter(|(id, server)| {
+                        id.as_str() != server_id && server.permit.is_some() && !server.in_use()
+                    })
+                    .min_by_key(|(_, server)| server.last_used)
+                    .map(|(id, _)| id.clone());
+
+                victim_id.and_then(|victim_id| {
+                    let victim = servers.get_mut(&victim_id)?;
+                    Some((victim.client.clone(), victim.permit.take()?))
+                })
+            };
+
+            let Some((victim_client, permit)) = victim else {
+                released.await;
+                continue;
+            };
+
+            // Слот переходит к новому серверу только после `/logout` старой сессии,
+            // иначе на сервере iiko на время logout занято больше слотов, чем `max_sessions`
+            if let Some(victim_client) = victim_client {
+                let _ = victim_client.release_session().await;
+            }
+
+            let leased = {
+                let mut servers = lock(&self.inner.servers);
+                match servers.get_mut(server_id) {
+                    Some(server) if server.permit.is_none() => {
+                        server.permit = Some(permit);
+                        Some(self.lease(server))
+                    }
+                    // Сервер удален или уже получил слот, пока шел logout
+                    _ => None,
+                }
+            };
+            match leased {
+                Some(leased) => return leased,
+                None => self.inner.released.notify_waiters(),
+            }
+        }
+    }
+
+    fn lease(&self, server: &mut Server) -> Result<PooledClient> {
+        let client = match &server.client {
+            Some(client) => client.clone(),
+            None => {
+                let transport = HealthTransport {
+                    inner: IikoClient::default_transport(&server.config)?,
+                    health: server.health.clone(),
+                };
+                let client = IikoClient::with_transport(server.config.clone(), transport);
+                server.client = Some(client.clone());
+                client
+            }
+        };
+
+        Ok(PooledClient {
+            client,
+            lease: Some(server.lease.clone()),
+            pool: self.inner.clone(),
+        })
+    }
+
+    /// Освободить сессии, к которым не обращались дольше `idle_timeout`
+    ///
+    /// # Что в ответе
+    /// - Количество освобожденных сессий
+    pub async fn logout_idle(&self) -> usize {
+        let idle: Vec<(Option<IikoClient>, Option<OwnedSemaphorePermit>)> = {
+            let mut servers = lock(&self.inner.servers);
+            servers
+                .values_mut()
+                .filter(|server| {
+                    server.permit.is_some()
+                        && !server.in_use()
+                        && server.last_used.elapsed() >= self.idle_timeout
+                })
+                .map(|server| (server.client.clone(), server.permit.take()))
+                .collect()
+        };
+
+        let count = idle.len();
+        for (client, permit) in idle {
+            let _ = self.release(client, permit).await;
+        }
+        count
+    }
+
+    /// Освободить все сессии пула
+    pub async fn logout_all(&self) {
+        let held: Vec<(Option<IikoClient>, Option<OwnedSemaphorePermit>)> = {
+            let mut servers = lock(&self.inner.servers);
+            servers
+                .values_mut()
+                .filter(|server| server.permit.is_some())
+                .map(|server| (server.client.clone(), server.permit.take()))
+                .collect()
+        };
+
+        for (client, permit) in held {
+            let _ = self.release(client, permit).await;
+        }
+    }
+
+    /// Запустить фоновую задачу, которая раз в `interval` вызывает `logout_idle()`
+    ///
+    /// Задача завершается сама, когда удалены все копии пула.
+    pub fn spawn_idle_reaper(&self, interval: Duration) -> JoinHandle<()> {
+        let pool: Weak<PoolInner> = Arc::downgrade(&self.inner);
+        let idle_timeout = self.idle_timeout;
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let Some(inner) = pool.upgrade() else {
+                    break;
+                };
+                IikoClientPool {
+                    inner,
+                    idle_timeout,
+                }
+                .logout_idle()
+                .await;
+            }
+        })
+    }
+
+    /// Состояние сервера
+    pub fn health(&self, server_id: &str) -> Option<ServerHealth> {
+        lock(&self.inner.servers).get(server_id).map(|server| {
+            let mut health = lock(&server.health).clone();
+            health.session_active = server.permit.is_some();
+            health
+        })
+    }
+
+    /// Состояние всех зарегистрированных серверов
+    pub fn health_all(&self) -> HashMap<String, ServerHealth> {
+        lock(&self.inner.servers)
+            .iter()
+            .map(|(id, server)| {
+                let mut health = lock(&server.health).clone();
+                health.session_active = server.permit.is_some();
+                (id.clone(), health)
+            })
+            .collect()
+    }
+
+    /// Количество слотов лицензии, занятых пулом
+    pub fn active_sessions(&self) -> usize {
+        self.inner.max_sessions - self.inner.slots.available_permits()
+    }
+
+    async fn release(
+        &self,
+        client: Option<IikoClient>,
+        permit: Option<OwnedSemaphorePermit>,
+    ) -> Result<()> {
+        let result = match (client, &permit) {
+            (Some(client), Some(_)) => client.release_session().await,
+            _ => Ok(()),
+        };
+
+        drop(permit);
+        self.inner.released.notify_waiters();
+        result
+    }
+
+    fn release_in_background(&self, server: Server) {
+        if server.permit.is_none() {
+            return;
+        }
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let pool = self.clone();
+            handle.spawn(async move {
+                // Старый клиент мог быть выдан до замены: logout под вызывающим кодом
+                // оборвал бы его запросы, поэтому ждем возврата всех PooledClient
+                loop {
+                    let released = pool.inner.released.notified();
+                    if !server.in_use() {
+                        break;
+                    }
+                    released.await;
+                }
+                let _ = pool.release(server.client, server.permit).await;
+            });
+        }
+    }
+}
+
+/// Клиент, выданный пулом
+///
+/// Разыменовывается в `IikoClient`. Пока значение живо, пул не освобождает сессию
+/// этого сервера ни по простою, ни для другого сервера.
+pub struct PooledClient {
+    client: IikoClient,
+    lease: Option<Arc<()>>,
+    pool: Arc<PoolInner>,
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        // Сначала отпускаем lease, чтобы разбуженный get() увидел клиент свободным
+        self.lease.take();
+        self.pool.released.notify_waiters();
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = IikoClient;
+
+    fn deref(&self) -> &IikoClient {
+        &self.client
+    }
+}
@@ -0,0 +1,189 @@
+//! Объекты запросов для endpoints с длинным списком параметров
+//!
+//! Вместо позиционных `Option`-аргументов методы принимают построитель, в котором
+//! каждый параметр задается по имени:
+//!
+//! ```no_run
+//! # async fn example(
+//! #     client: iiko_server_api_sdk::IikoClient,
+//! #     store: iiko_server_api_sdk::StoreId,
+//! # ) -> iiko_server_api_sdk::Result<()> {
+//! use iiko_server_api_sdk::{EmployeeSearch, StoreOperationsQuery};
+//!
+//! let employees = client
+//!     .employees()
+//!     .find(EmployeeSearch::new().last_name("Иванов").supplier(false))
+//!     .await?;
+//!
+//! let operations = client
+//!     .reports()
+//!     .store_operations(
+//!         StoreOperationsQuery::between("01.03.2024", "31.03.2024")
+//!             .stores([store])
+//!             .product_detalization(true),
+//!     )
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Обязательные параметры передаются в конструктор (`new`, `between`, `at`),
+//! необязательные — методами с именами параметров. Прежние методы с позиционными
+//! аргументами оставлены и помечены `#[deprecated]`.
+
+/// Query-параметры запроса в порядке добавления
+#[derive(Debug, Default)]
+pub(crate) struct QueryParams(Vec<(&'static str, String)>);
+
+impl QueryParams {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, name: &'static str, value: impl Into<String>) -> &mut Self {
+        self.0.push((name, value.into()));
+        self
+    }
+
+    /// Добавить параметр, если он задан (`bool` и числа — в текстовом виде)
+    pub(crate) fn push_opt<T: ToString>(
+        &mut self,
+        name: &'static str,
+        value: Option<T>,
+    ) -> &mut Self {
+        if let Some(value) = value {
+            self.0.push((name, value.to_string()));
+        }
+        self
+    }
+
+    /// Повторить параметр для каждого значения списка
+    pub(crate) fn push_all(&mut self, name: &'static str, values: &[String]) -> &mut Self {
+        self.0
+            .extend(values.iter().map(|value| (name, value.clone())));
+        self
+    }
+
+    /// Добавить параметры другого запроса после уже добавленных
+    pub(crate) fn append(&mut self, other: QueryParams) -> &mut Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    /// Пары для `get_with_params` и `post_form_query`
+    pub(crate) fn pairs(&self) -> Vec<(&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect()
+    }
+}
+
+/// Методы построителя для необязательных параметров
+///
+/// Имя метода совпадает с именем поля. Виды параметров: `text` (`Option<String>`),
+/// `flag` (`Option<bool>`), `number` (`Option<i32>`), `revision` (`Option<i64>`),
+/// `list` (`Vec<String>`, значения добавляются к уже заданным) и `date`
+/// (`Option<DateParam>`). `text<T>` и `list<T>` хранят строки, а принимают
+/// `impl Into<T>` — так ID и коды из [`crate::ids`] не смешиваются.
+macro_rules! query_setters {
+    ($query:ty { $($(#[$doc:meta])* $setter:ident: $kind:ident $(<$typed:ty>)?),* $(,)? }) => {
+        impl $query {
+            $(crate::query::query_setters!(@setter $(#[$doc])* $setter $kind $($typed)?);)*
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident text $typed:ty) => {
+        $(#[$doc])*
+        pub fn $setter(mut self, value: impl Into<$typed>) -> Self {
+            self.$setter = Some(value.into().to_string());
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident list $typed:ty) => {
+        $(#[$doc])*
+        pub fn $setter<I>(mut self, values: I) -> Self
+        where
+            I: IntoIterator,
+            I::Item: Into<$typed>,
+        {
+            self.$setter
+                .extend(values.into_iter().map(|value| value.into().to_string()));
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident text) => {
+        $(#[$doc])*
+        pub fn $setter(mut self, value: impl Into<String>) -> Self {
+            self.$setter = Some(value.into());
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident flag) => {
+        $(#[$doc])*
+        pub fn $setter(mut self, value: bool) -> Self {
+            self.$setter = Some(value);
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident number) => {
+        $(#[$doc])*
+        pub fn $setter(mut self, value: i32) -> Self {
+            self.$setter = Some(value);
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident revision) => {
+        $(#[$doc])*
+        pub fn $setter(mut self, value: i64) -> Self {
+            self.$setter = Some(value);
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident list) => {
+        $(#[$doc])*
+        pub fn $setter<I>(mut self, values: I) -> Self
+        where
+            I: IntoIterator,
+            I::Item: Into<String>,
+        {
+            self.$setter.extend(values.into_iter().map(Into::into));
+            self
+        }
+    };
+    (@setter $(#[$doc:meta])* $setter:ident date) => {
+        $(#[$doc])*
+        pub fn $setter(mut self, value: impl Into<crate::date::DateParam>) -> Self {
+            self.$setter = Some(value.into());
+            self
+        }
+    };
+}
+
+pub(crate) use query_setters;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order_and_repeats_list_params() {
+        let mut params = QueryParams::new();
+        params
+            .push("dateFrom", "01.03.2024")
+            .push_opt("hourFrom", Some(9))
+            .push_opt("hourTo", None::<i32>)
+            .push_opt("dishDetails", Some(true))
+            .push_all("stores", &["store-1".to_string(), "store-2".to_string()]);
+
+        assert_eq!(
+            params.pairs(),
+            [
+                ("dateFrom", "01.03.2024"),
+                ("hourFrom", "9"),
+                ("dishDetails", "true"),
+                ("stores", "store-1"),
+                ("stores", "store-2"),
+            ]
+        );
+    }
+}
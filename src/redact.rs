@@ -0,0 +1,130 @@
+//! Удаление секретов из тел запросов и ответов перед записью в журналы
+//!
+//! Используется кассетами, журналом изменений (`audit`) и событиями `tracing`:
+//! - query-параметры и поля форм `key`, `login`, `pass`
+//! - содержимое XML-элементов `<password>` (создание и изменение сотрудников)
+//! - строковые значения полей `password` и `pass` в JSON
+
+use crate::cassette::REDACTED;
+use serde_json::Value;
+
+/// Query-параметры и поля форм, значения которых не записываются
+const SECRET_PARAMS: &[&str] = &["key", "login", "pass"];
+
+/// XML-элементы и JSON-поля, значения которых не записываются
+const SECRET_FIELDS: &[&str] = &["password", "pass"];
+
+pub(crate) fn scrub_params(params: Vec<(String, String)>) -> Vec<(String, String)> {
+    params
+        .into_iter()
+        .map(|(name, value)| {
+            if SECRET_PARAMS.contains(&name.as_str()) {
+                (name, REDACTED.to_string())
+            } else {
+                (name, value)
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn scrub_form(body: &str) -> String {
+    match serde_urlencoded::from_str::<Vec<(String, String)>>(body) {
+        Ok(form) => serde_urlencoded::to_string(scrub_params(form)).unwrap_or_default(),
+        Err(_) => body.to_string(),
+    }
+}
+
+/// Тело XML или JSON без паролей. Формат определяется по первому символу
+pub(crate) fn scrub_text(body: &str) -> String {
+    if body.trim_start().starts_with(['{', '[']) {
+        scrub_json(body)
+    } else {
+        scrub_xml(body)
+    }
+}
+
+fn scrub_xml(body: &str) -> String {
+    let mut body = body.to_string();
+    for field in SECRET_FIELDS {
+        let open = format!("<{}>", field);
+        let close = format!("</{}>", field);
+        let mut scrubbed = String::with_capacity(body.len());
+        let mut rest = body.as_str();
+
+        while let Some(start) = rest.find(&open) {
+            let content = start + open.len();
+            scrubbed.push_str(&rest[..content]);
+            scrubbed.push_str(REDACTED);
+            // Незакрытый элемент: остаток тела считается его содержимым
+            rest = rest[content..]
+                .find(&close)
+                .map_or("", |end| &rest[content + end..]);
+        }
+        scrubbed.push_str(rest);
+        body = scrubbed;
+    }
+    body
+}
+
+fn scrub_json(body: &str) -> String {
+    fn scrub(value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (name, value) in object.iter_mut() {
+                    if SECRET_FIELDS.contains(&name.as_str()) && value.is_string() {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        scrub(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(scrub),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            scrub(&mut value);
+            value.to_string()
+        }
+        // Не JSON: ищем пароли как в XML
+        Err(_) => scrub_xml(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrubs_password_elements_in_xml() {
+        let xml = "<employee><login>cook</login><password>s3cret</password>\
+                   <pass>hash</pass><name>Повар</name></employee>";
+
+        assert_eq!(
+            scrub_text(xml),
+            "<employee><login>cook</login><password>[REDACTED]</password>\
+             <pass>[REDACTED]</pass><name>Повар</name></employee>"
+        );
+        assert_eq!(scrub_text("<password>open"), "<password>[REDACTED]");
+    }
+
+    #[test]
+    fn scrubs_password_fields_in_json() {
+        let json = r#"[{"login":"cook","password":"s3cret","nested":{"pass":"hash"}}]"#;
+
+        let scrubbed = scrub_text(json);
+
+        assert!(!scrubbed.contains("s3cret") && !scrubbed.contains("hash"));
+        assert!(scrubbed.contains(r#""login":"cook""#));
+    }
+
+    #[test]
+    fn scrubs_credentials_in_forms() {
+        assert_eq!(
+            scrub_form("login=admin&pass=secret&name=x"),
+            "login=%5BREDACTED%5D&pass=%5BREDACTED%5D&name=x"
+        );
+    }
+}
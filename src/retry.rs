@@ -0,0 +1,229 @@
+use crate::error::IikoError;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Класс ошибок, при которых запрос можно повторить
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetryOn {
+    /// Сетевая ошибка: не удалось установить соединение или соединение оборвалось
+    Network,
+    /// Истек таймаут HTTP-клиента
+    Timeout,
+    /// 500 Internal Server Error
+    InternalServerError,
+    /// 502 Bad Gateway
+    BadGateway,
+    /// 503 Service Unavailable (например, во время перезапуска репликации)
+    ServiceUnavailable,
+    /// 504 Gateway Timeout
+    GatewayTimeout,
+}
+
+impl RetryOn {
+    /// Класс временной ошибки или `None`, если повтор ошибку не исправит
+    ///
+    /// Единая классификация для `RetryPolicy` и `IikoError::is_retryable()`.
+    pub fn classify(error: &IikoError) -> Option<RetryOn> {
+        match error {
+            IikoError::Http(e) if e.is_timeout() => Some(RetryOn::Timeout),
+            IikoError::Http(e) if e.is_connect() || e.is_request() => Some(RetryOn::Network),
+            IikoError::Transport(_) => Some(RetryOn::Network),
+            IikoError::InternalServerError(_) => Some(RetryOn::InternalServerError),
+            IikoError::BadGateway(_) => Some(RetryOn::BadGateway),
+            IikoError::ServiceUnavailable(_) => Some(RetryOn::ServiceUnavailable),
+            IikoError::GatewayTimeout(_) => Some(RetryOn::GatewayTimeout),
+            _ => None,
+        }
+    }
+}
+
+/// Политика повторных попыток для временных ошибок
+///
+/// Повторы выполняются, пока запрос держит очередь клиента, поэтому между попытками
+/// не может вклиниться другой запрос. Задержка растет экспоненциально:
+/// `base_delay_ms * 2^(n-1)`, но не больше `max_delay_ms`, плюс случайный разброс `jitter`.
+///
+/// Чтение (GET и POST-запросы, не меняющие данные) повторяется по умолчанию.
+/// Запросы на запись (импорт документов, сохранение, удаление) повторяются только
+/// при `retry_writes = true`, так как iiko может успеть применить изменение до обрыва связи.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Максимальное количество попыток, включая первую (1 — без повторов)
+    pub max_attempts: u32,
+    /// Задержка перед первым повтором, мс
+    pub base_delay_ms: u64,
+    /// Верхняя граница задержки между попытками, мс
+    pub max_delay_ms: u64,
+    /// Доля случайного разброса задержки, от 0.0 до 1.0
+    pub jitter: f64,
+    /// Ошибки, при которых запрос повторяется
+    pub retry_on: Vec<RetryOn>,
+    /// Повторять ли запросы на запись
+    pub retry_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+            jitter: 0.2,
+            retry_on: vec![
+                RetryOn::Network,
+                RetryOn::Timeout,
+                RetryOn::InternalServerError,
+                RetryOn::BadGateway,
+                RetryOn::ServiceUnavailable,
+                RetryOn::GatewayTimeout,
+            ],
+            retry_writes: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Политика без повторов: каждый запрос выполняется ровно один раз
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay_ms = delay.as_millis() as u64;
+        self
+    }
+
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay_ms = delay.as_millis() as u64;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: Vec<RetryOn>) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    pub fn with_retry_writes(mut self, retry_writes: bool) -> Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// Можно ли повторить запрос после этой ошибки
+    pub fn is_retryable(&self, error: &IikoError) -> bool {
+        RetryOn::classify(error).is_some_and(|kind| self.retry_on.contains(&kind))
+    }
+
+    /// Нужно ли делать попытку номер `attempt + 1` после неудачной попытки `attempt`
+    pub(crate) fn should_retry(&self, attempt: u32, is_write: bool, error: &IikoError) -> bool {
+        attempt < self.max_attempts && (!is_write || self.retry_writes) && self.is_retryable(error)
+    }
+
+    /// Задержка перед повтором после неудачной попытки номер `attempt` (начиная с 1)
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay_ms
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_delay_ms) as f64;
+
+        let spread = delay * self.jitter.clamp(0.0, 1.0);
+        let jittered = delay - spread + fastrand::f64() * spread * 2.0;
+
+        Duration::from_millis(jittered.max(0.0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_grows_exponentially_and_is_capped() {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(350))
+            .with_jitter(0.0);
+
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(350));
+        assert_eq!(policy.delay_for(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(1000))
+            .with_jitter(0.5);
+
+        for _ in 0..100 {
+            let delay = policy.delay_for(1).as_millis();
+            assert!((500..=1500).contains(&delay), "delay {delay} out of bounds");
+        }
+    }
+
+    #[test]
+    fn writes_are_retried_only_when_enabled() {
+        let error = IikoError::ServiceUnavailable("restarting".to_string());
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(1, false, &error));
+        assert!(!policy.should_retry(1, true, &error));
+        assert!(policy.with_retry_writes(true).should_retry(1, true, &error));
+    }
+
+    #[test]
+    fn default_policy_agrees_with_is_retryable() {
+        let policy = RetryPolicy::default();
+        let errors = [
+            IikoError::Transport("reset".to_string()),
+            IikoError::GatewayTimeout("slow".to_string()),
+            IikoError::SessionExpired("stale".to_string()),
+            IikoError::LicenseExhausted {
+                module_id: None,
+                message: "License enhancement is required".to_string(),
+            },
+            IikoError::Unauthorized(String::new()),
+            IikoError::BadRequest("bad".to_string()),
+        ];
+
+        for error in &errors {
+            assert_eq!(
+                policy.is_retryable(error),
+                error.is_retryable(),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn business_errors_and_exhausted_attempts_are_not_retried() {
+        let policy = RetryPolicy::default();
+
+        assert!(!policy.should_retry(1, false, &IikoError::BusinessLogic("no".to_string())));
+        assert!(!policy.should_retry(
+            3,
+            false,
+            &IikoError::InternalServerError("boom".to_string())
+        ));
+        assert!(!RetryPolicy::none().should_retry(
+            1,
+            false,
+            &IikoError::InternalServerError("boom".to_string())
+        ));
+    }
+}
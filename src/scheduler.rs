@@ -0,0 +1,369 @@
+//! Очередь запросов к серверу iiko с приоритетами
+//!
+//! iiko требует, чтобы запросы выполнялись строго последовательно, поэтому в каждый момент
+//! к серверу идет не больше одного запроса клиента. [`RequestScheduler`] решает, чей запрос
+//! будет следующим: ожидающие запросы делятся на классы [`Priority`] и обслуживаются
+//! взвешенным циклом. Из каждых 13 запросов при полной очереди 8 получают `Interactive`,
+//! 4 — `Sync` и 1 — `Bulk`, поэтому короткий запрос кассы не ждет всю очередь OLAP-отчетов,
+//! а фоновые выгрузки все равно продвигаются. Внутри класса порядок — FIFO.
+//!
+//! Выполняющийся запрос не прерывается: запрос с высоким приоритетом ждет, пока завершится
+//! текущий (включая его повторы по `RetryPolicy`).
+//!
+//! ```no_run
+//! # async fn example(client: iiko_server_api_sdk::IikoClient) -> iiko_server_api_sdk::Result<()> {
+//! use iiko_server_api_sdk::Priority;
+//!
+//! let pos = client.with_priority(Priority::Interactive);
+//! let employee = pos.employees().get_by_code("42").await?;
+//!
+//! let stats = client.queue_stats();
+//! if stats.queued() > 10 {
+//!     println!("сервер перегружен: bulk ждет {:?}", stats.bulk.oldest_wait);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Класс приоритета запроса
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Короткие запросы, которых ждет пользователь (поиск сотрудника, проверка товара на кассе)
+    Interactive,
+    /// Обычная синхронизация справочников и документов
+    #[default]
+    Sync,
+    /// Тяжелые выгрузки: OLAP-отчеты, полная номенклатура, история документов
+    Bulk,
+}
+
+impl Priority {
+    const ALL: [Priority; 3] = [Priority::Interactive, Priority::Sync, Priority::Bulk];
+
+    fn index(self) -> usize {
+        match self {
+            Priority::Interactive => 0,
+            Priority::Sync => 1,
+            Priority::Bulk => 2,
+        }
+    }
+
+    /// Сколько запросов класса обслуживается за один цикл, если очередь не пуста
+    fn weight(self) -> u32 {
+        match self {
+            Priority::Interactive => 8,
+            Priority::Sync => 4,
+            Priority::Bulk => 1,
+        }
+    }
+}
+
+/// Состояние очереди одного класса приоритета
+#[derive(Debug, Clone, Default)]
+pub struct PriorityStats {
+    /// Запросов в очереди сейчас
+    pub queued: usize,
+    /// Время ожидания самого старого запроса в очереди (ноль, если очередь пуста)
+    pub oldest_wait: Duration,
+    /// Всего запросов, получивших очередь
+    pub served: u64,
+    /// Суммарное время ожидания обслуженных запросов
+    pub total_wait: Duration,
+    /// Наибольшее время ожидания обслуженного запроса
+    pub max_wait: Duration,
+}
+
+impl PriorityStats {
+    /// Среднее время ожидания обслуженных запросов
+    pub fn average_wait(&self) -> Duration {
+        if self.served == 0 {
+            return Duration::ZERO;
+        }
+        self.total_wait.div_f64(self.served as f64)
+    }
+}
+
+/// Снимок очереди запросов клиента
+///
+/// Возвращается из `IikoClient::queue_stats()`. Статистика общая для всех клонов клиента.
+#[derive(Debug, Clone, Default)]
+pub struct QueueStats {
+    /// Выполняется ли сейчас запрос к серверу
+    pub busy: bool,
+    pub interactive: PriorityStats,
+    pub sync: PriorityStats,
+    pub bulk: PriorityStats,
+}
+
+impl QueueStats {
+    /// Статистика класса приоритета
+    pub fn get(&self, priority: Priority) -> &PriorityStats {
+        match priority {
+            Priority::Interactive => &self.interactive,
+            Priority::Sync => &self.sync,
+            Priority::Bulk => &self.bulk,
+        }
+    }
+
+    /// Запросов в очереди по всем классам
+    pub fn queued(&self) -> usize {
+        self.interactive.queued + self.sync.queued + self.bulk.queued
+    }
+}
+
+struct Waiter {
+    id: u64,
+    enqueued: Instant,
+    wake: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+struct State {
+    busy: bool,
+    next_id: u64,
+    queues: [VecDeque<Waiter>; 3],
+    /// Сколько запросов каждого класса обслужено в текущем цикле
+    round: [u32; 3],
+    stats: [PriorityStats; 3],
+}
+
+impl State {
+    fn record_wait(&mut self, priority: Priority, wait: Duration) {
+        let stats = &mut self.stats[priority.index()];
+        stats.served += 1;
+        stats.total_wait += wait;
+        stats.max_wait = stats.max_wait.max(wait);
+    }
+
+    /// Выбрать класс следующего запроса по взвешенному циклу
+    fn next_priority(&mut self) -> Option<Priority> {
+        let waiting = || {
+            Priority::ALL
+                .into_iter()
+                .filter(|p| !self.queues[p.index()].is_empty())
+        };
+
+        let priority = waiting()
+            .find(|p| self.round[p.index()] < p.weight())
+            .or_else(|| waiting().next())?;
+
+        if self.round[priority.index()] >= priority.weight() {
+            // Все классы с ожидающими запросами исчерпали свою долю — новый цикл
+            self.round = [0; 3];
+        }
+        self.round[priority.index()] += 1;
+
+        Some(priority)
+    }
+
+    /// Передать очередь следующему запросу или освободить ее
+    fn release(&mut self) {
+        let Some(priority) = self.next_priority() else {
+            self.busy = false;
+            return;
+        };
+
+        let waiter = self.queues[priority.index()]
+            .pop_front()
+            .expect("next_priority returns a non-empty class");
+        self.record_wait(priority, waiter.enqueued.elapsed());
+        // Отмененный запрос убирает себя из очереди раньше, чем закрывается получатель,
+        // поэтому отправка не может потеряться
+        let _ = waiter.wake.send(());
+    }
+}
+
+/// Очередь запросов клиента: пропускает к серверу по одному запросу с учетом приоритета
+#[derive(Clone, Default)]
+pub(crate) struct RequestScheduler {
+    state: Arc<Mutex<State>>,
+}
+
+impl RequestScheduler {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Дождаться своей очереди. Сервер свободен для запроса, пока жив `SchedulerPermit`
+    ///
+    /// Отмена ожидания безопасна: запрос убирается из очереди, а если очередь
+    /// уже была передана ему, она передается следующему.
+    pub(crate) async fn acquire(&self, priority: Priority) -> SchedulerPermit {
+        let (wake, woken) = oneshot::channel();
+
+        let id = {
+            let mut state = self.state();
+            if !state.busy && state.queues.iter().all(VecDeque::is_empty) {
+                state.busy = true;
+                state.record_wait(priority, Duration::ZERO);
+                return SchedulerPermit {
+                    scheduler: self.clone(),
+                };
+            }
+
+            state.next_id += 1;
+            let id = state.next_id;
+            state.queues[priority.index()].push_back(Waiter {
+                id,
+                enqueued: Instant::now(),
+                wake,
+            });
+            id
+        };
+
+        let mut pending = PendingAcquire {
+            scheduler: self,
+            priority,
+            id,
+            armed: true,
+        };
+        // Отправитель живет в очереди до передачи ему очереди, а очередь живет в self
+        let _ = woken.await;
+        pending.armed = false;
+
+        SchedulerPermit {
+            scheduler: self.clone(),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> QueueStats {
+        let state = self.state();
+        let now = Instant::now();
+
+        let snapshot = |priority: Priority| {
+            let queue = &state.queues[priority.index()];
+            PriorityStats {
+                queued: queue.len(),
+                oldest_wait: queue
+                    .front()
+                    .map(|waiter| now.saturating_duration_since(waiter.enqueued))
+                    .unwrap_or_default(),
+                ..state.stats[priority.index()].clone()
+            }
+        };
+
+        QueueStats {
+            busy: state.busy,
+            interactive: snapshot(Priority::Interactive),
+            sync: snapshot(Priority::Sync),
+            bulk: snapshot(Priority::Bulk),
+        }
+    }
+}
+
+/// Право на выполнение запроса; при удалении очередь переходит к следующему запросу
+pub(crate) struct SchedulerPermit {
+    scheduler: RequestScheduler,
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        self.scheduler.state().release();
+    }
+}
+
+/// Убирает запрос из очереди, если future `acquire` отменили во время ожидания
+struct PendingAcquire<'a> {
+    scheduler: &'a RequestScheduler,
+    priority: Priority,
+    id: u64,
+    armed: bool,
+}
+
+impl Drop for PendingAcquire<'_> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+
+        let mut state = self.scheduler.state();
+        let queue = &mut state.queues[self.priority.index()];
+        match queue.iter().position(|waiter| waiter.id == self.id) {
+            Some(position) => {
+                queue.remove(position);
+            }
+            // Очередь уже передана этому запросу, но он отменен — передать дальше
+            None => state.release(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_classes_by_weight_and_fifo_within_class() {
+        let scheduler = RequestScheduler::default();
+        let running = scheduler.acquire(Priority::Sync).await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (priority, count) in [(Priority::Bulk, 3), (Priority::Interactive, 10)] {
+            for n in 0..count {
+                let scheduler = scheduler.clone();
+                let order = order.clone();
+                tasks.push(tokio::spawn(async move {
+                    let _permit = scheduler.acquire(priority).await;
+                    order.lock().unwrap().push((priority, n));
+                }));
+                tokio::task::yield_now().await;
+            }
+        }
+        while scheduler.stats().queued() < 13 {
+            tokio::task::yield_now().await;
+        }
+
+        drop(running);
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let order = order.lock().unwrap().clone();
+        let classes: Vec<_> = order.iter().map(|(p, _)| *p).collect();
+        let mut expected = vec![Priority::Interactive; 8];
+        expected.extend([Priority::Bulk, Priority::Interactive, Priority::Interactive]);
+        assert_eq!(classes[..11], expected);
+
+        let bulk: Vec<_> = order
+            .iter()
+            .filter(|(p, _)| *p == Priority::Bulk)
+            .map(|(_, n)| *n)
+            .collect();
+        assert_eq!(bulk, [0, 1, 2]);
+
+        let stats = scheduler.stats();
+        assert!(!stats.busy);
+        assert_eq!(stats.queued(), 0);
+        assert_eq!(stats.interactive.served, 10);
+        assert_eq!(stats.bulk.served, 3);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_leaves_queue() {
+        let scheduler = RequestScheduler::default();
+        let running = scheduler.acquire(Priority::Bulk).await;
+
+        let waiting = scheduler.acquire(Priority::Interactive);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(5), waiting)
+                .await
+                .is_err()
+        );
+        assert_eq!(scheduler.stats().queued(), 0);
+
+        drop(running);
+        let _permit =
+            tokio::time::timeout(Duration::from_secs(1), scheduler.acquire(Priority::Sync))
+                .await
+                .expect("queue should be free after cancellation");
+        assert!(scheduler.stats().busy);
+    }
+}
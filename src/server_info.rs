@@ -0,0 +1,187 @@
+//! Версия и редакция сервера iiko
+//!
+//! `IikoClient::server_info()` запрашивает `get_server_info.jsp` (ключ сессии не нужен,
+//! слот лицензии не занимается) и кэширует ответ на все время жизни клиента и его клонов.
+//!
+//! Методы, появившиеся в iiko 5.0 и позже, сверяют версию сервера с требуемой и
+//! возвращают `IikoError::UnsupportedVersion`, не отправляя запрос. Версия запрашивается
+//! при первом вызове такого метода. Параметры, которые старый сервер проигнорировал бы
+//! (`revisionFrom` до 6.4, `includeDeleted` до 5.0), не передаются. Если версию узнать
+//! не удалось, запросы отправляются как есть; после временной ошибки (таймаут, 5xx) версия
+//! запрашивается снова при следующем вызове.
+//! Проверка отключается через `IikoConfig::with_version_check(false)`.
+//!
+//! ```no_run
+//! # async fn example(client: iiko_server_api_sdk::IikoClient) -> iiko_server_api_sdk::Result<()> {
+//! use iiko_server_api_sdk::ServerVersion;
+//!
+//! let info = client.server_info().await?;
+//! println!("iiko {} ({:?})", info.version, info.edition);
+//! if info.version >= ServerVersion::new(7, 9, 3) {
+//!     // внутренние перемещения доступны
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{IikoError, Result};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Версия сервера iiko в терминах документации: `7.9.3`
+///
+/// Сервер сообщает версию со сборкой (`7.9.3007.0`); номер сборки в сравнении не участвует.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Разбирает `7.9.3` и версию сборки `7.9.3007.0`
+///
+/// В версии сборки третье число — исправление и номер сборки (`3007` — исправление 3,
+/// сборка 007; `0012` — исправление 0, сборка 012), поэтому у чисел длиннее трех цифр
+/// три последние цифры отбрасываются.
+impl FromStr for ServerVersion {
+    type Err = IikoError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || IikoError::Api(format!("Unrecognized iiko server version: {}", s));
+
+        let mut parts = s.trim().split('.');
+        let mut next = |required: bool| -> Result<&str> {
+            match parts.next() {
+                Some(part) if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) => {
+                    Ok(part)
+                }
+                None if !required => Ok("0"),
+                _ => Err(invalid()),
+            }
+        };
+
+        let major = next(true)?;
+        let minor = next(true)?;
+        let patch = next(false)?;
+        let patch = match patch.len() {
+            0..=3 => patch,
+            len => &patch[..len - 3],
+        };
+
+        let number = |part: &str| part.parse::<u32>().map_err(|_| invalid());
+        Ok(Self {
+            major: number(major)?,
+            minor: number(minor)?,
+            patch: number(patch)?,
+        })
+    }
+}
+
+/// Ответ `get_server_info.jsp`
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    /// Версия для сравнения с требованиями методов
+    pub version: ServerVersion,
+    /// Версия как ее вернул сервер, например `7.9.3007.0`
+    pub raw_version: String,
+    /// Редакция: `default` для ресторана, `chain` для сети (iikoChain)
+    pub edition: Option<String>,
+    pub server_name: Option<String>,
+    /// Состояние сервера, например `STARTED_SUCCESSFULLY`
+    pub server_state: Option<String>,
+}
+
+impl ServerInfo {
+    /// Сервер iikoChain (головной офис сети)
+    pub fn is_chain(&self) -> bool {
+        self.edition.as_deref() == Some("chain")
+    }
+
+    pub(crate) fn from_xml(xml: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Raw {
+            version: String,
+            edition: Option<String>,
+            server_name: Option<String>,
+            server_state: Option<String>,
+        }
+
+        let raw: Raw = quick_xml::de::from_str(xml)?;
+        Ok(Self {
+            // Непонятная версия — такой же неразбираемый ответ, как битый XML
+            version: raw
+                .version
+                .parse()
+                .map_err(|error: IikoError| quick_xml::DeError::Custom(error.to_string()))?,
+            raw_version: raw.version,
+            edition: raw.edition,
+            server_name: raw.server_name,
+            server_state: raw.server_state,
+        })
+    }
+}
+
+/// URL `get_server_info.jsp` для `base_url` вида `https://host/resto/api`
+pub(crate) fn server_info_url(base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    let base = base.strip_suffix("/api").unwrap_or(base);
+    format!("{}/get_server_info.jsp", base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_build_version_and_compares() {
+        let info = ServerInfo::from_xml(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<r>
+    <serverName>Ресторан на Невском</serverName>
+    <edition>default</edition>
+    <version>7.9.3007.0</version>
+    <computerName>IIKO-SRV</computerName>
+    <serverState>STARTED_SUCCESSFULLY</serverState>
+    <protocol>https</protocol>
+    <serverSubUrl>/resto</serverSubUrl>
+    <isPresent>false</isPresent>
+</r>"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.version, ServerVersion::new(7, 9, 3));
+        assert_eq!(info.raw_version, "7.9.3007.0");
+        assert!(!info.is_chain());
+        assert!(info.version < ServerVersion::new(7, 10, 0));
+        assert!(info.version >= ServerVersion::new(7, 6, 1));
+        assert_eq!("9.1".parse::<ServerVersion>().unwrap().to_string(), "9.1.0");
+        assert_eq!(
+            "6.1.0012.0".parse::<ServerVersion>().unwrap(),
+            ServerVersion::new(6, 1, 0)
+        );
+        assert!("latest".parse::<ServerVersion>().is_err());
+
+        assert_eq!(
+            server_info_url("https://demo.iiko.it/resto/api/"),
+            "https://demo.iiko.it/resto/get_server_info.jsp"
+        );
+    }
+}
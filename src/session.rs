@@ -0,0 +1,242 @@
+//! Освобождение слотов лицензии iiko
+//!
+//! Каждый ключ сессии занимает слот лицензии, пока не вызван `/logout` или пока ключ
+//! не истечет на сервере (около часа). Если `logout()` забыт или задача упала с паникой,
+//! следующие авторизации получают "License enhancement is required".
+//!
+//! - [`SessionGuard`] из `IikoClient::session()` освобождает слот при выходе из области видимости
+//! - [`release_all`] освобождает все слоты, которые еще держит процесс
+//! - [`install_exit_hook`] вызывает `release_all` при Ctrl+C / SIGTERM и сообщает сигнал приложению
+
+use crate::client::IikoClient;
+use crate::error::{IikoError, Result};
+use crate::transport::{Method, Transport, TransportRequest};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Ключ сессии, полученный процессом и еще не освобожденный
+struct HeldSession {
+    base_url: String,
+    key: String,
+    transport: Arc<dyn Transport>,
+    timeout: Option<Duration>,
+}
+
+static HELD_SESSIONS: Mutex<Vec<HeldSession>> = Mutex::new(Vec::new());
+
+fn held_sessions() -> MutexGuard<'static, Vec<HeldSession>> {
+    HELD_SESSIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Запомнить выданный ключ сессии
+pub(crate) fn register(
+    base_url: &str,
+    key: &str,
+    transport: &Arc<dyn Transport>,
+    timeout: Option<Duration>,
+) {
+    held_sessions().push(HeldSession {
+        base_url: base_url.to_string(),
+        key: key.to_string(),
+        transport: transport.clone(),
+        timeout,
+    });
+}
+
+/// Забыть ключ сессии: он освобожден через `/logout` или уже недействителен на сервере
+pub(crate) fn unregister(key: &str) {
+    held_sessions().retain(|session| session.key != key);
+}
+
+/// Количество ключей сессии, которые процесс получил и еще не освободил
+pub fn held_count() -> usize {
+    held_sessions().len()
+}
+
+/// Освободить все слоты лицензии, которые еще держит процесс
+///
+/// Вызывает `/logout` для каждого ключа, полученного любым `IikoClient` и не освобожденного
+/// через `logout()`. Ошибки `/logout` игнорируются: ключ мог уже истечь на сервере.
+/// Клиенты, чьи ключи были освобождены, авторизуются заново при следующем запросе
+/// (если не отключен `auto_reauth`).
+///
+/// # Что в ответе
+/// - Количество ключей, для которых `/logout` завершился успешно
+pub async fn release_all() -> usize {
+    let sessions = std::mem::take(&mut *held_sessions());
+    let mut released = 0;
+
+    for session in sessions {
+        if logout_key(
+            &session.base_url,
+            &session.key,
+            session.transport.as_ref(),
+            session.timeout,
+        )
+        .await
+        .is_ok()
+        {
+            released += 1;
+        }
+    }
+
+    released
+}
+
+/// Сигнал завершения процесса, полученный [`install_exit_hook`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownSignal {
+    /// Ctrl+C (SIGINT)
+    Interrupt,
+    /// SIGTERM (только unix)
+    Terminate,
+}
+
+impl ShutdownSignal {
+    /// Общепринятый код выхода после сигнала: 130 для SIGINT, 143 для SIGTERM
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ShutdownSignal::Interrupt => 130,
+            ShutdownSignal::Terminate => 143,
+        }
+    }
+}
+
+/// Освободить все слоты лицензии при Ctrl+C (и SIGTERM на unix)
+///
+/// Запускает фоновую задачу в текущем tokio runtime. При получении сигнала задача вызывает
+/// [`release_all`] и завершается, возвращая полученный сигнал. Процесс она не завершает:
+/// когда и с каким кодом выходить, решает приложение.
+///
+/// ```no_run
+/// # async fn example() {
+/// let hook = iiko_server_api_sdk::session::install_exit_hook();
+/// // ... работа приложения ...
+/// if let Ok(signal) = hook.await {
+///     // слоты уже освобождены, можно завершить свои задачи
+///     std::process::exit(signal.exit_code());
+/// }
+/// # }
+/// ```
+///
+/// # Важно
+/// - Должна вызываться внутри tokio runtime
+/// - Пока задача ждет сигнал, Ctrl+C не завершает процесс сам: после того как задача
+///   завершилась, приложение должно выйти или продолжить работу самостоятельно
+/// - При обычном завершении `main` нужно вызвать `release_all().await` самостоятельно
+pub fn install_exit_hook() -> JoinHandle<ShutdownSignal> {
+    tokio::spawn(async {
+        let signal = wait_for_shutdown_signal().await;
+        release_all().await;
+        signal
+    })
+}
+
+async fn wait_for_shutdown_signal() -> ShutdownSignal {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return ShutdownSignal::Interrupt,
+                _ = terminate.recv() => return ShutdownSignal::Terminate,
+            }
+        }
+    }
+
+    let _ = tokio::signal::ctrl_c().await;
+    ShutdownSignal::Interrupt
+}
+
+/// Отправить `/logout` для ключа
+pub(crate) async fn logout_key(
+    base_url: &str,
+    key: &str,
+    transport: &dyn Transport,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let mut request = TransportRequest::new(Method::POST, format!("{}/logout", base_url));
+    request.timeout = timeout;
+    request.headers.push((
+        "Content-Type".to_string(),
+        "application/x-www-form-urlencoded".to_string(),
+    ));
+    let form = serde_urlencoded::to_string([("key", key)])
+        .map_err(|e| IikoError::Configuration(format!("Failed to encode form data: {}", e)))?;
+    request.body = Some(form.into_bytes());
+
+    let response = transport.send(request).await?;
+    unregister(key);
+
+    if response.status.is_success() {
+        Ok(())
+    } else {
+        Err(IikoError::Api(format!(
+            "Logout failed with status: {} - {}",
+            response.status,
+            response.text()
+        )))
+    }
+}
+
+/// Сессия, которая освобождает слот лицензии при выходе из области видимости
+///
+/// Возвращается из `IikoClient::session()`. Разыменовывается в `IikoClient`, поэтому
+/// все endpoints доступны напрямую: `session.products().find(...)`.
+///
+/// При удалении guard'а `/logout` выполняется в фоновой задаче текущего tokio runtime,
+/// в том числе при выходе по `?` и при панике. Чтобы дождаться освобождения слота
+/// и получить ошибку `/logout`, вызовите [`SessionGuard::release`].
+///
+/// # Важно
+/// - Ключ сессии общий для всех клонов клиента: после освобождения другие клоны
+///   авторизуются заново при следующем запросе
+/// - Если ключ не был получен (ни одного запроса), `/logout` не вызывается
+pub struct SessionGuard {
+    client: IikoClient,
+    released: bool,
+}
+
+impl SessionGuard {
+    pub(crate) fn new(client: IikoClient) -> Self {
+        Self {
+            client,
+            released: false,
+        }
+    }
+
+    /// Освободить слот лицензии сейчас и дождаться ответа `/logout`
+    pub async fn release(mut self) -> Result<()> {
+        self.released = true;
+        self.client.release_session().await
+    }
+}
+
+impl Deref for SessionGuard {
+    type Target = IikoClient;
+
+    fn deref(&self) -> &IikoClient {
+        &self.client
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+
+        // Без runtime выполнить /logout нельзя; ключ останется в release_all()
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            handle.spawn(async move {
+                let _ = client.release_session().await;
+            });
+        }
+    }
+}
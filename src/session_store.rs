@@ -0,0 +1,236 @@
+//! Хранилище ключей сессии между перезапусками процесса
+//!
+//! Ключ сессии iiko остается действительным на сервере и занимает слот лицензии примерно
+//! час после последнего запроса. Если процесс перезапускается без `/logout`, новый `/auth`
+//! занимает еще один слот. Клиент с [`SessionStore`] сначала берет сохраненный ключ и
+//! авторизуется заново, только если iiko его отклонил.
+//!
+//! - [`FileSessionStore`] — JSON-файл, общий для перезапусков и процессов на одной машине
+//! - [`MemorySessionStore`] — общий для клиентов одного процесса (например, пересоздаваемых)
+//!
+//! Ключи хранятся по области `{login}@{base_url}`, поэтому одно хранилище можно отдать
+//! клиентам разных серверов и пользователей.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::{FileSessionStore, IikoClient, IikoConfig};
+//!
+//! let client = IikoClient::new(IikoConfig::from_env()?)?
+//!     .with_session_store(FileSessionStore::new("/var/lib/worker/iiko-session.json"));
+//! client.suppliers().list(None).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Важно
+//! - Ключ, освобожденный через `logout()` или `SessionGuard`, удаляется из хранилища.
+//!   Чтобы ключ пережил перезапуск, не освобождайте его при остановке процесса
+//! - `session::release_all()` хранилище не трогает: освобожденный им ключ iiko отклонит
+//!   при следующем запуске, и клиент авторизуется заново
+//! - Ошибки хранилища не прерывают запрос: клиент авторизуется как без хранилища
+//! - Ключ сессии дает доступ к API от имени пользователя: файл создается с правами `0600`
+
+use crate::error::{IikoError, Result};
+use crate::transport::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Хранилище ключей сессии, к которому клиент обращается перед `/auth`
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Сохраненный ключ для области `scope`
+    async fn load(&self, scope: &str) -> Result<Option<String>>;
+
+    /// Сохранить ключ, выданный `/auth`
+    async fn save(&self, scope: &str, key: &str) -> Result<()>;
+
+    /// Удалить ключ, если для `scope` сохранен именно `key`
+    ///
+    /// Вызывается, когда iiko отклонил ключ или ключ освобожден. Сравнение с `key` не дает
+    /// удалить свежий ключ, который успел сохранить другой процесс.
+    async fn remove(&self, scope: &str, key: &str) -> Result<()>;
+}
+
+/// Ключи сессии в памяти процесса
+///
+/// Клоны разделяют одно хранилище.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySessionStore {
+    keys: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn keys(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        self.keys
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn load(&self, scope: &str) -> Result<Option<String>> {
+        Ok(self.keys().get(scope).cloned())
+    }
+
+    async fn save(&self, scope: &str, key: &str) -> Result<()> {
+        self.keys().insert(scope.to_string(), key.to_string());
+        Ok(())
+    }
+
+    async fn remove(&self, scope: &str, key: &str) -> Result<()> {
+        let mut keys = self.keys();
+        if keys.get(scope).is_some_and(|saved| saved == key) {
+            keys.remove(scope);
+        }
+        Ok(())
+    }
+}
+
+/// Ключи сессии в JSON-файле (`{"область": "ключ"}`)
+///
+/// Файл перезаписывается целиком через временный файл рядом с ним, поэтому процесс,
+/// упавший во время записи, не оставляет поврежденный файл. Несколько процессов могут
+/// использовать один файл: при одновременной записи сохранится один из ключей, а второй
+/// процесс переавторизуется, когда iiko отклонит чужой ключ.
+#[derive(Debug)]
+pub struct FileSessionStore {
+    path: PathBuf,
+    /// Сериализует чтение-изменение-запись внутри процесса
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FileSessionStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn read(&self) -> Result<HashMap<String, String>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(json) if json.is_empty() => Ok(HashMap::new()),
+            Ok(json) => Ok(serde_json::from_slice(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write(&self, keys: &HashMap<String, String>) -> Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(format!(".{}.tmp", std::process::id()));
+        let tmp = PathBuf::from(tmp);
+
+        let json = serde_json::to_vec_pretty(keys)?;
+        write_private(&tmp, &json).await?;
+        tokio::fs::rename(&tmp, &self.path).await.map_err(|e| {
+            IikoError::Io(std::io::Error::new(
+                e.kind(),
+                format!("Failed to replace {}: {}", self.path.display(), e),
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn load(&self, scope: &str) -> Result<Option<String>> {
+        let _lock = self.lock.lock().await;
+        Ok(self.read().await?.remove(scope))
+    }
+
+    async fn save(&self, scope: &str, key: &str) -> Result<()> {
+        let _lock = self.lock.lock().await;
+        let mut keys = self.read().await?;
+        keys.insert(scope.to_string(), key.to_string());
+        self.write(&keys).await
+    }
+
+    async fn remove(&self, scope: &str, key: &str) -> Result<()> {
+        let _lock = self.lock.lock().await;
+        let mut keys = self.read().await?;
+        if keys.get(scope).is_some_and(|saved| saved == key) {
+            keys.remove(scope);
+            self.write(&keys).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Записать файл, доступный только владельцу
+async fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    Ok(())
+}
+
+/// Область ключа в хранилище: один ключ на пользователя и сервер
+pub(crate) fn scope(base_url: &str, login: &str) -> String {
+    format!("{}@{}", login, base_url.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_store_survives_reopen_and_keeps_newer_key() {
+        let path = std::env::temp_dir().join(format!(
+            "iiko-session-store-{}-{}.json",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        let scope = scope("http://iiko.test/resto/api/", "admin");
+        assert_eq!(scope, "admin@http://iiko.test/resto/api");
+
+        let store = FileSessionStore::new(&path);
+        assert_eq!(store.load(&scope).await.unwrap(), None);
+        store.save(&scope, "key-1").await.unwrap();
+        store.save("other@http://x", "key-x").await.unwrap();
+
+        let reopened = FileSessionStore::new(&path);
+        assert_eq!(
+            reopened.load(&scope).await.unwrap().as_deref(),
+            Some("key-1")
+        );
+
+        // Другой процесс уже сохранил новый ключ — отклоненный старый его не удаляет
+        reopened.save(&scope, "key-2").await.unwrap();
+        store.remove(&scope, "key-1").await.unwrap();
+        assert_eq!(store.load(&scope).await.unwrap().as_deref(), Some("key-2"));
+
+        store.remove(&scope, "key-2").await.unwrap();
+        assert_eq!(store.load(&scope).await.unwrap(), None);
+        assert_eq!(
+            store.load("other@http://x").await.unwrap().as_deref(),
+            Some("key-x")
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
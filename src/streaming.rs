@@ -0,0 +1,449 @@
+//! Чтение больших ответов iiko по частям
+//!
+//! OLAP-отчет за месяц по сети или изображение в Base64 может занимать сотни мегабайт.
+//! Вместо того чтобы собирать такой ответ в `String`, клиент отдает тело как поток
+//! ([`ResponseStream`]), а разбор выполняется по мере получения данных:
+//!
+//! - [`OlapRowStream`] — строки `data` OLAP-отчета по одной
+//!   (`ReportsEndpoint::stream_olap_report`)
+//! - `ImagesEndpoint::load_into` — декодирует Base64 изображения сразу в `AsyncWrite`
+//!
+//! Пока поток не дочитан или не удален, он занимает очередь запросов клиента:
+//! iiko требует последовательного выполнения запросов.
+
+use crate::error::{IikoError, Result};
+use crate::scheduler::SchedulerPermit;
+use crate::transport::BodyStream;
+use crate::xml::response::OlapFieldValue;
+use base64::Engine;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Ключ массива строк в ответе `/v2/reports/olap`
+const OLAP_ROWS_KEY: &[u8] = b"data";
+/// Ключ Base64-данных в ответе `/v2/images/load`
+const IMAGE_DATA_KEY: &[u8] = b"data";
+
+/// Base64 из `byte[]` в Java: стандартный алфавит, паддинг не обязателен
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Тело ответа iiko, которое читается по частям
+///
+/// Занимает очередь запросов клиента, пока не будет дочитано или удалено.
+pub struct ResponseStream {
+    body: BodyStream,
+    _permit: Option<SchedulerPermit>,
+}
+
+impl ResponseStream {
+    /// `permit` — очередь, если она не передана вместе с `body` (например, из цепочки middleware)
+    pub(crate) fn new(body: BodyStream, permit: Option<SchedulerPermit>) -> Self {
+        Self {
+            body,
+            _permit: permit,
+        }
+    }
+}
+
+impl Stream for ResponseStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.body.poll_next_unpin(cx)
+    }
+}
+
+/// Строка OLAP-отчета: поле отчета → значение
+pub type OlapRow = HashMap<String, OlapFieldValue>;
+
+/// Строки `data` OLAP-отчета, разобранные по мере получения ответа
+///
+/// Возвращается из `ReportsEndpoint::stream_olap_report`. В памяти держится только
+/// текущая строка. Итоги (`summary`) не разбираются; после последней строки соединение
+/// закрывается и очередь запросов освобождается, не дожидаясь конца ответа.
+/// `"data": null` и ответ без `data` дают пустой поток.
+pub struct OlapRowStream {
+    body: Option<ResponseStream>,
+    scanner: OlapRowScanner,
+    rows: VecDeque<Vec<u8>>,
+}
+
+impl OlapRowStream {
+    pub(crate) fn new(body: ResponseStream) -> Self {
+        Self {
+            body: Some(body),
+            scanner: OlapRowScanner::default(),
+            rows: VecDeque::new(),
+        }
+    }
+}
+
+impl Stream for OlapRowStream {
+    type Item = Result<OlapRow>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(row) = this.rows.pop_front() {
+                return Poll::Ready(Some(serde_json::from_slice(&row).map_err(Into::into)));
+            }
+
+            let Some(body) = this.body.as_mut() else {
+                return Poll::Ready(None);
+            };
+
+            if this.scanner.finished {
+                this.body = None;
+                continue;
+            }
+
+            match ready!(body.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => this.scanner.feed(&chunk, &mut this.rows),
+                Some(Err(error)) => {
+                    this.body = None;
+                    return Poll::Ready(Some(Err(error)));
+                }
+                None => {
+                    this.body = None;
+                    return Poll::Ready(Some(Err(IikoError::Api(
+                        "OLAP response ended before the `data` array was complete".to_string(),
+                    ))));
+                }
+            }
+        }
+    }
+}
+
+/// Декодировать Base64 изображения из ответа `/v2/images/load` в `writer`
+///
+/// Ответ может быть JSON-объектом `{"id": ..., "data": "..."}` или просто строкой Base64.
+/// Возвращает количество записанных байт.
+pub(crate) async fn write_image<W>(mut body: ResponseStream, writer: &mut W) -> Result<u64>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut scanner = ImageDataScanner::default();
+    let mut encoded = Vec::new();
+    let mut written = 0;
+
+    while let Some(chunk) = body.next().await {
+        scanner.feed(&chunk?, &mut encoded);
+
+        // Base64 декодируется группами по 4 символа, остаток ждет следующего куска
+        let complete = encoded.len() / 4 * 4;
+        written += decode_into(&encoded[..complete], writer).await?;
+        encoded.drain(..complete);
+
+        if scanner.finished {
+            break;
+        }
+    }
+
+    written += decode_into(&encoded, writer).await?;
+    writer.flush().await?;
+    Ok(written)
+}
+
+async fn decode_into<W>(encoded: &[u8], writer: &mut W) -> Result<u64>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    if encoded.is_empty() {
+        return Ok(0);
+    }
+
+    let decoded = BASE64
+        .decode(encoded)
+        .map_err(|e| IikoError::Api(format!("Invalid Base64 image data: {}", e)))?;
+    writer.write_all(&decoded).await?;
+    Ok(decoded.len() as u64)
+}
+
+/// Что означает очередной байт JSON-документа
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Token {
+    StringStart,
+    /// Байт внутри строки; `escaped` — байт после `\`
+    StringByte {
+        escaped: bool,
+    },
+    StringEnd,
+    Open,
+    Close,
+    Colon,
+    Comma,
+    /// Пробелы, числа, `true`/`false`/`null`
+    Other,
+}
+
+/// Минимальный лексер JSON: глубина вложенности и границы строк
+#[derive(Default)]
+struct JsonLexer {
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonLexer {
+    fn next(&mut self, byte: u8) -> Token {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+                return Token::StringByte { escaped: true };
+            }
+            return match byte {
+                b'\\' => {
+                    self.escaped = true;
+                    Token::StringByte { escaped: false }
+                }
+                b'"' => {
+                    self.in_string = false;
+                    Token::StringEnd
+                }
+                _ => Token::StringByte { escaped: false },
+            };
+        }
+
+        match byte {
+            b'"' => {
+                self.in_string = true;
+                Token::StringStart
+            }
+            b'{' | b'[' => {
+                self.depth += 1;
+                Token::Open
+            }
+            b'}' | b']' => {
+                self.depth = self.depth.saturating_sub(1);
+                Token::Close
+            }
+            b':' => Token::Colon,
+            b',' => Token::Comma,
+            _ => Token::Other,
+        }
+    }
+}
+
+/// Поиск значения по ключу верхнего уровня JSON-объекта
+#[derive(Default)]
+struct TopLevelKey {
+    key: Vec<u8>,
+    reading_key: bool,
+    /// После `:` — совпал ли ключ с искомым
+    value_of: Option<bool>,
+}
+
+impl TopLevelKey {
+    /// Обработать токен на глубине 1. Возвращает `true`, если токен начинает значение
+    /// искомого ключа
+    fn on_token(&mut self, token: Token, byte: u8, wanted: &[u8]) -> bool {
+        match token {
+            Token::StringStart if self.value_of.is_none() => {
+                self.reading_key = true;
+                self.key.clear();
+            }
+            Token::StringByte { .. } if self.reading_key => self.key.push(byte),
+            Token::StringEnd if self.reading_key => self.reading_key = false,
+            Token::Colon => self.value_of = Some(self.key == wanted),
+            Token::Comma => self.value_of = None,
+            Token::StringStart | Token::Open => return self.value_of == Some(true),
+            // `null`, число или `true`/`false` вместо строки или массива
+            Token::Other if !byte.is_ascii_whitespace() => return self.value_of == Some(true),
+            _ => {}
+        }
+        false
+    }
+}
+
+/// Выделяет элементы-объекты массива `data` из OLAP-ответа
+#[derive(Default)]
+struct OlapRowScanner {
+    lexer: JsonLexer,
+    key: TopLevelKey,
+    in_rows: bool,
+    row: Vec<u8>,
+    finished: bool,
+}
+
+impl OlapRowScanner {
+    fn feed(&mut self, chunk: &[u8], rows: &mut VecDeque<Vec<u8>>) {
+        for &byte in chunk {
+            if self.finished {
+                return;
+            }
+
+            let depth = self.lexer.depth;
+            let token = self.lexer.next(byte);
+
+            if self.in_rows {
+                // Массив строк на глубине 2, содержимое строк — на глубине 3 и больше
+                if depth >= 3 || (depth == 2 && token == Token::Open) {
+                    self.row.push(byte);
+                    if depth == 3 && token == Token::Close {
+                        rows.push_back(std::mem::take(&mut self.row));
+                    }
+                } else if depth == 2 && token == Token::Close {
+                    self.finished = true;
+                }
+            } else if depth == 1 && token == Token::Close {
+                // Объект закончился без ключа `data`: строк нет
+                self.finished = true;
+            } else if depth == 1 && self.key.on_token(token, byte, OLAP_ROWS_KEY) {
+                // `"data": null` — строк нет
+                self.in_rows = byte == b'[';
+                self.finished = !self.in_rows;
+            }
+        }
+    }
+}
+
+/// Как выглядит ответ `/v2/images/load`
+#[derive(Default, PartialEq, Eq)]
+enum ImageBody {
+    #[default]
+    Unknown,
+    /// `{"id": ..., "data": "..."}`
+    Json,
+    /// Строка Base64 без обертки (возможно, в кавычках)
+    Raw,
+}
+
+/// Выделяет символы Base64 изображения из ответа
+#[derive(Default)]
+struct ImageDataScanner {
+    body: ImageBody,
+    lexer: JsonLexer,
+    key: TopLevelKey,
+    in_data: bool,
+    finished: bool,
+}
+
+impl ImageDataScanner {
+    fn feed(&mut self, chunk: &[u8], encoded: &mut Vec<u8>) {
+        for &byte in chunk {
+            if self.finished {
+                return;
+            }
+
+            if self.body == ImageBody::Unknown {
+                if byte.is_ascii_whitespace() {
+                    continue;
+                }
+                self.body = if byte == b'{' {
+                    ImageBody::Json
+                } else {
+                    ImageBody::Raw
+                };
+            }
+
+            let depth = self.lexer.depth;
+            let token = self.lexer.next(byte);
+
+            match self.body {
+                ImageBody::Raw => match token {
+                    Token::StringByte { escaped: true } if byte == b'/' => encoded.push(byte),
+                    Token::StringByte { escaped: true } => {}
+                    _ if is_base64(byte) => encoded.push(byte),
+                    _ => {}
+                },
+                ImageBody::Json if self.in_data => match token {
+                    Token::StringEnd => self.finished = true,
+                    // В JSON `/` может быть экранирован как `\/`; `\n` и прочие — переносы строк
+                    Token::StringByte { escaped: true } if byte == b'/' => encoded.push(byte),
+                    Token::StringByte { escaped: false } if is_base64(byte) => encoded.push(byte),
+                    _ => {}
+                },
+                ImageBody::Json => {
+                    if depth == 1 && self.key.on_token(token, byte, IMAGE_DATA_KEY) {
+                        self.in_data = token == Token::StringStart;
+                        self.finished = !self.in_data;
+                    }
+                }
+                ImageBody::Unknown => unreachable!("body kind is detected above"),
+            }
+        }
+    }
+}
+
+fn is_base64(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_rows(body: &str, chunk_size: usize) -> Vec<String> {
+        let mut scanner = OlapRowScanner::default();
+        let mut rows = VecDeque::new();
+        for chunk in body.as_bytes().chunks(chunk_size) {
+            scanner.feed(chunk, &mut rows);
+        }
+        assert!(scanner.finished);
+        rows.into_iter()
+            .map(|row| String::from_utf8(row).unwrap())
+            .collect()
+    }
+
+    fn scan_image(body: &str, chunk_size: usize) -> String {
+        let mut scanner = ImageDataScanner::default();
+        let mut encoded = Vec::new();
+        for chunk in body.as_bytes().chunks(chunk_size) {
+            scanner.feed(chunk, &mut encoded);
+        }
+        String::from_utf8(encoded).unwrap()
+    }
+
+    #[test]
+    fn splits_olap_rows_across_chunks() {
+        let body = r#"{"summary": [[{"data": 1}]], "data": [
+            {"Department": "Кафе {центр}", "DishSumInt": 10.5},
+            {"Department": "Бар \"[1]\"", "Nested": {"a": [1, 2]}}
+        ], "extra": {"data": []}}"#;
+
+        for chunk_size in [1, 3, 7, body.len()] {
+            let rows = scan_rows(body, chunk_size);
+            assert_eq!(rows.len(), 2);
+            assert_eq!(
+                rows[0],
+                r#"{"Department": "Кафе {центр}", "DishSumInt": 10.5}"#
+            );
+            assert!(rows[1].ends_with(r#""Nested": {"a": [1, 2]}}"#));
+        }
+
+        assert!(scan_rows(r#"{"data": []}"#, 1).is_empty());
+    }
+
+    #[test]
+    fn treats_null_or_missing_olap_data_as_no_rows() {
+        for body in [
+            r#"{"data": null, "summary": []}"#,
+            r#"{"summary": [], "data":null}"#,
+            r#"{"summary": [{"data": [{"a": 1}]}]}"#,
+        ] {
+            for chunk_size in [1, 4, body.len()] {
+                assert!(scan_rows(body, chunk_size).is_empty(), "{}", body);
+            }
+        }
+    }
+
+    #[test]
+    fn extracts_image_base64_from_json_and_raw_bodies() {
+        let json = r#"{"id": "5f0c", "data": "aGVs\/bG8\n="}"#;
+        for chunk_size in [1, 5, json.len()] {
+            assert_eq!(scan_image(json, chunk_size), "aGVs/bG8=");
+        }
+
+        assert_eq!(scan_image(" \"aGVsbG8=\"\n", 2), "aGVsbG8=");
+        assert_eq!(scan_image(r#"{"id": "5f0c", "data": null}"#, 4), "");
+    }
+}
@@ -0,0 +1,145 @@
+//! Вспомогательные функции для фичи `tracing`
+//!
+//! Ключ сессии передается только в query-параметрах, а логин и пароль — в теле `/auth`,
+//! поэтому query в span'ы и события не попадают. Тела ответов и тела запросов, не
+//! отправленных в режиме `WriteMode::DryRun`, пишутся только на уровне TRACE, без паролей
+//! (`<password>`, `pass`) и обрезанными до `MAX_BODY_LEN` байт.
+
+use crate::error::Result;
+use crate::scheduler::Priority;
+use crate::transport::{Method, StatusCode, TransportResponse};
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::{Instrument, Span, debug, info, trace, warn};
+
+/// Максимальная длина тела в событиях TRACE, байт
+const MAX_BODY_LEN: usize = 2048;
+
+/// Span одного вызова `IikoClient` (включая повторы и переавторизацию)
+pub(crate) fn request_span(method: &Method, endpoint: &str, priority: Priority) -> Span {
+    tracing::debug_span!(
+        "iiko.request",
+        method = %method,
+        endpoint,
+        priority = ?priority,
+        queue_wait_ms = Empty,
+        status = Empty,
+        latency_ms = Empty,
+        response_bytes = Empty,
+        retries = 0u32,
+    )
+}
+
+/// Записать статус ответа в текущий span
+pub(crate) fn record_status(status: StatusCode) {
+    Span::current().record("status", status.as_u16());
+}
+
+/// Записать размер прочитанного ответа в текущий span, тело — событием TRACE
+pub(crate) fn record_response(response: &TransportResponse) {
+    Span::current().record("response_bytes", response.body.len());
+
+    if tracing::enabled!(tracing::Level::TRACE) {
+        let body = crate::redact::scrub_text(&String::from_utf8_lossy(&response.body));
+        trace!(body = truncate(&body), "iiko response body");
+    }
+}
+
+/// Выполнить `/auth` или `/logout` в span'е `iiko.session`
+///
+/// Записывается только статус: в ответе `/auth` — ключ сессии.
+pub(crate) async fn session_call(
+    endpoint: &str,
+    call: impl Future<Output = Result<TransportResponse>>,
+) -> Result<TransportResponse> {
+    let span = tracing::debug_span!("iiko.session", endpoint, status = Empty);
+
+    async {
+        let result = call.await;
+        if let Ok(response) = &result {
+            Span::current().record("status", response.status.as_u16());
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Записать, сколько запрос ждал своей очереди
+pub(crate) fn record_queue_wait(queued: Instant) {
+    Span::current().record("queue_wait_ms", queued.elapsed().as_millis() as u64);
+}
+
+pub(crate) fn record_retry(attempt: u32, error: &crate::error::IikoError) {
+    Span::current().record("retries", attempt);
+    warn!(attempt, error = %error, "iiko request failed, retrying");
+}
+
+pub(crate) fn record_store_error(operation: &str, error: &crate::error::IikoError) {
+    warn!(operation, error = %error, "iiko session store failed");
+}
+
+pub(crate) fn record_audit_error(error: &crate::error::IikoError) {
+    warn!(error = %error, "iiko audit sink failed");
+}
+
+pub(crate) fn record_reauth() {
+    debug!("iiko session key rejected, re-authenticating");
+}
+
+pub(crate) fn record_outcome<T>(started: Instant, result: &Result<T>) {
+    Span::current().record("latency_ms", started.elapsed().as_millis() as u64);
+
+    if let Err(error) = result {
+        debug!(error = %error, "iiko request failed");
+    }
+}
+
+/// Обрезать строку до `MAX_BODY_LEN` байт по границе символа
+fn truncate(body: &str) -> &str {
+    if body.len() <= MAX_BODY_LEN {
+        return body;
+    }
+
+    let mut end = MAX_BODY_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
+
+/// Изменяющий запрос, не отправленный в режиме `WriteMode::DryRun`
+///
+/// На уровне INFO — только метод и endpoint. Тело запроса — событием TRACE, как тела
+/// ответов: без паролей и обрезанное до `MAX_BODY_LEN` байт.
+pub(crate) fn record_dry_run(request: &crate::middleware::ApiRequest) -> Result<()> {
+    info!(
+        method = %request.method,
+        endpoint = %request.endpoint,
+        "iiko dry run: request not sent"
+    );
+
+    if tracing::enabled!(tracing::Level::TRACE) {
+        let body = request.body.redacted_text()?;
+        trace!(
+            body = truncate(body.as_deref().unwrap_or("")),
+            "iiko dry run request body"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_on_char_boundary() {
+        let body = "я".repeat(MAX_BODY_LEN);
+        let truncated = truncate(&body);
+
+        assert!(truncated.len() <= MAX_BODY_LEN);
+        assert!(truncated.chars().all(|c| c == 'я'));
+        assert_eq!(truncate("<ok/>"), "<ok/>");
+    }
+}
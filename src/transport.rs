@@ -0,0 +1,291 @@
+//! HTTP-транспорт, через который `IikoClient` отправляет запросы
+//!
+//! По умолчанию используется [`ReqwestTransport`]. Собственную реализацию [`Transport`]
+//! можно передать в `IikoClient::with_transport`, например, чтобы в тестах отдавать
+//! заранее подготовленные ответы без обращения к серверу iiko.
+
+use crate::error::{IikoError, Result};
+use bytes::Bytes;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use std::fmt;
+use std::time::Duration;
+
+pub use async_trait::async_trait;
+pub use reqwest::{Method, StatusCode};
+
+/// Тело ответа, которое читается по частям
+pub type BodyStream = BoxStream<'static, Result<Bytes>>;
+
+/// Запрос, который клиент передает транспорту
+///
+/// Ключ сессии уже добавлен в `query`, тело уже сериализовано.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TransportRequest {
+    pub method: Method,
+    /// Полный URL без query-параметров, например `https://host/resto/api/suppliers`
+    pub url: String,
+    /// Query-параметры в порядке добавления (ключи могут повторяться)
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    /// Таймаут этого запроса. `None` — таймаут транспорта по умолчанию
+    pub timeout: Option<Duration>,
+}
+
+impl TransportRequest {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    /// Путь запроса относительно `base_url`, например `v2/entities/products/list`
+    pub fn endpoint<'a>(&'a self, base_url: &str) -> &'a str {
+        self.url
+            .strip_prefix(base_url)
+            .unwrap_or(&self.url)
+            .trim_start_matches('/')
+    }
+
+    /// Первое значение query-параметра
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Первое значение заголовка (без учета регистра имени)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Тело запроса как UTF-8 строка
+    pub fn body_text(&self) -> Option<&str> {
+        self.body
+            .as_deref()
+            .and_then(|body| std::str::from_utf8(body).ok())
+    }
+}
+
+/// Ответ транспорта: статус и тело как есть, без интерпретации ошибок
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Первое значение заголовка (без учета регистра имени)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Тело ответа как строка (невалидные UTF-8 последовательности заменяются)
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+impl From<TransportResponse> for StreamingResponse {
+    fn from(response: TransportResponse) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers,
+            body: stream::once(async move { Ok(Bytes::from(response.body)) }).boxed(),
+        }
+    }
+}
+
+/// Ответ транспорта, тело которого еще не прочитано
+///
+/// Используется для больших ответов (OLAP-отчеты, изображения), чтобы не держать
+/// все тело в памяти.
+#[non_exhaustive]
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: BodyStream,
+}
+
+impl StreamingResponse {
+    pub fn new(status: StatusCode, body: BodyStream) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Первое значение заголовка (без учета регистра имени)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Прочитать тело целиком
+    pub async fn into_response(self) -> Result<TransportResponse> {
+        let body = self
+            .body
+            .try_fold(Vec::new(), |mut body, chunk| async move {
+                body.extend_from_slice(&chunk);
+                Ok(body)
+            })
+            .await?;
+
+        Ok(TransportResponse {
+            status: self.status,
+            headers: self.headers,
+            body,
+        })
+    }
+}
+
+impl fmt::Debug for StreamingResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Способ доставки HTTP-запроса до сервера iiko
+///
+/// Реализация не должна интерпретировать статус ответа: разбор ошибок, повторная
+/// авторизация и повторы выполняются в `IikoClient`. Ошибки уровня соединения
+/// следует возвращать как `IikoError::Http` или `IikoError::Transport`.
+/// Если в запросе задан `timeout`, реализация должна его соблюдать.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse>;
+
+    /// Отправить запрос и вернуть ответ, не дочитывая тело
+    ///
+    /// По умолчанию вызывает `send` и отдает тело одним куском. Транспорты, которые
+    /// умеют читать тело по частям, переопределяют этот метод.
+    async fn send_streaming(&self, request: TransportRequest) -> Result<StreamingResponse> {
+        self.send(request).await.map(StreamingResponse::from)
+    }
+}
+
+/// Транспорт по умолчанию на основе `reqwest::Client`
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Создать транспорт с таймаутом на запрос. `None` — без таймаута на уровне HTTP-клиента
+    pub fn new(timeout: Option<Duration>) -> Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let client = builder.build().map_err(|e| {
+            IikoError::Configuration(format!("Failed to create HTTP client: {}", e))
+        })?;
+
+        Ok(Self { client })
+    }
+
+    /// Использовать уже настроенный `reqwest::Client` (прокси, TLS, пул соединений)
+    pub fn from_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl ReqwestTransport {
+    async fn execute(&self, request: TransportRequest) -> Result<reqwest::Response> {
+        let mut builder = self
+            .client
+            .request(request.method, &request.url)
+            .query(&request.query);
+
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        // URL в ошибке reqwest содержит ключ сессии в query, поэтому убираем его
+        Ok(builder.send().await.map_err(reqwest::Error::without_url)?)
+    }
+}
+
+fn response_headers(response: &reqwest::Response) -> Vec<(String, String)> {
+    response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let response = self.execute(request).await?;
+        let status = response.status();
+        let headers = response_headers(&response);
+        let body = response
+            .bytes()
+            .await
+            .map_err(reqwest::Error::without_url)?
+            .to_vec();
+
+        Ok(TransportResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    async fn send_streaming(&self, request: TransportRequest) -> Result<StreamingResponse> {
+        let response = self.execute(request).await?;
+
+        Ok(StreamingResponse {
+            status: response.status(),
+            headers: response_headers(&response),
+            body: response
+                .bytes_stream()
+                .map_err(|e| IikoError::Http(e.without_url()))
+                .boxed(),
+        })
+    }
+}
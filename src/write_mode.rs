@@ -0,0 +1,216 @@
+//! Защита от случайных изменений данных на сервере
+//!
+//! `IikoConfig::with_write_mode` задает, что клиент делает с изменяющими запросами
+//! (импорт документов, сохранение и удаление номенклатуры, сотрудников, шкал и т.д.):
+//!
+//! - [`WriteMode::Normal`] — отправляет на сервер (по умолчанию)
+//! - [`WriteMode::ReadOnly`] — возвращает `IikoError::ReadOnly`, не отправляя запрос
+//! - [`WriteMode::DryRun`] — не отправляет запрос, записывает его в журнал
+//!   (`IikoClient::dry_run_requests()`, с фичей `tracing` — событием INFO, тело — на TRACE)
+//!   и возвращает синтетический успешный ответ
+//!
+//! Читающие запросы, в том числе POST (OLAP-отчеты, списки с фильтрами), выполняются
+//! во всех режимах. Режим проверяется после цепочки middleware, поэтому middleware
+//! видит и отклоненные, и не отправленные запросы.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use iiko_server_api_sdk::{IikoClient, IikoConfig, ProductId, WriteMode};
+//! use uuid::Uuid;
+//!
+//! let client = IikoClient::new(IikoConfig::from_env()?.with_write_mode(WriteMode::DryRun))?;
+//! client.products().delete(vec![ProductId::from(Uuid::nil())]).await?;
+//!
+//! for request in client.dry_run_requests() {
+//!     println!("{} {}: {:?}", request.method, request.endpoint, request.body.text()?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Что в синтетическом ответе
+//! - XML-документы: `valid = true`, номер документа из запроса
+//! - JSON-операции (`result`, `errors`, `response`): `result = "SUCCESS"`, в `response` —
+//!   тело запроса; объекту без `id` подставляется `Uuid::nil()`
+//! - Удаление и восстановление номенклатуры: `response = None`
+//! - Шкалы размеров: шкала из запроса или шкала без размеров с `id` из запроса
+//! - Сотрудники: сотрудник из запроса
+//!
+//! Данных сервера (присвоенных кодов, ревизий, заполненных по умолчанию полей)
+//! в синтетическом ответе нет.
+
+use crate::middleware::{ApiBody, ApiRequest};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Что клиент делает с запросами, изменяющими данные на сервере
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WriteMode {
+    /// Отправлять на сервер
+    #[default]
+    Normal,
+    /// Отклонять с `IikoError::ReadOnly`
+    ReadOnly,
+    /// Не отправлять, записать в журнал и вернуть синтетический ответ
+    DryRun,
+}
+
+/// Сколько последних запросов хранит журнал `IikoClient::dry_run_requests()`
+pub(crate) const DRY_RUN_LOG_LEN: usize = 100;
+
+/// Тело синтетического ответа, которое разберет метод endpoint'а
+pub(crate) fn synthetic_body(request: &ApiRequest) -> String {
+    let endpoint = request.endpoint.trim_start_matches('/');
+    match &request.body {
+        ApiBody::Xml(xml) => synthetic_xml(endpoint, xml),
+        ApiBody::Json(body) => {
+            let body = serde_json::from_str(body).unwrap_or(Value::Null);
+            synthetic_json(endpoint, body).to_string()
+        }
+        // DELETE `v2/entities/products/{id}/productScale` возвращает id шкалы
+        ApiBody::Empty if endpoint.ends_with("/productScale") => Uuid::nil().to_string(),
+        ApiBody::Empty | ApiBody::Form(_) => String::new(),
+    }
+}
+
+fn synthetic_xml(endpoint: &str, xml: &str) -> String {
+    let number = xml_element(xml, "documentNumber").unwrap_or_default();
+
+    if endpoint == "documents/import/incomingInventory" {
+        format!(
+            "<incomingInventoryValidationResult><valid>true</valid><warning>false</warning>\
+             <documentNumber>{number}</documentNumber></incomingInventoryValidationResult>"
+        )
+    } else if endpoint.starts_with("documents/") {
+        format!(
+            "<documentValidationResult><valid>true</valid><warning>false</warning>\
+             <documentNumber>{number}</documentNumber></documentValidationResult>"
+        )
+    } else if endpoint == "events/add" {
+        "<eventsList/>".to_string()
+    } else {
+        // employees/byId/{id}: сервер возвращает сохраненного сотрудника
+        xml.to_string()
+    }
+}
+
+fn synthetic_json(endpoint: &str, body: Value) -> Value {
+    match endpoint {
+        "v2/entities/products/delete" | "v2/entities/products/restore" => operation(Value::Null),
+        "v2/entities/productScales/save" | "v2/entities/productScales/update" => with_id(body),
+        "v2/entities/productScales/delete" | "v2/entities/productScales/restore" => {
+            empty_scale(&body["items"][0]["id"])
+        }
+        _ if endpoint.ends_with("/productScale") => empty_scale(&body["id"]),
+        _ => operation(with_id(body)),
+    }
+}
+
+/// Результат JSON-операции v2: `{"result": "SUCCESS", "errors": null, "response": ...}`
+fn operation(response: Value) -> Value {
+    json!({ "result": "SUCCESS", "errors": null, "response": response })
+}
+
+fn with_id(mut body: Value) -> Value {
+    if let Value::Object(object) = &mut body {
+        object
+            .entry("id")
+            .or_insert_with(|| Value::String(Uuid::nil().to_string()));
+    }
+    body
+}
+
+fn empty_scale(id: &Value) -> Value {
+    let id = match id {
+        Value::String(id) => id.clone(),
+        _ => Uuid::nil().to_string(),
+    };
+    json!({ "id": id, "name": "", "productSizes": [] })
+}
+
+/// Текст первого элемента `<name>...</name>`
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = xml.find(&open)? + open.len();
+    let len = xml[start..].find(&close)?;
+    Some(&xml[start..start + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::Access;
+    use crate::transport::Method;
+    use crate::xml::response::{
+        EventsList, IncomingInventoryValidationResultDto, InternalTransferOperationResult,
+        ProductScaleDto,
+    };
+
+    fn body(method: Method, endpoint: &str, body: ApiBody) -> String {
+        synthetic_body(&ApiRequest::new(Access::Write, method, endpoint, &[], body))
+    }
+
+    const PRODUCT: &str = "4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f";
+
+    #[test]
+    fn echoes_document_number_in_import_validation() {
+        let inventory: IncomingInventoryValidationResultDto = quick_xml::de::from_str(&body(
+            Method::POST,
+            "documents/import/incomingInventory",
+            ApiBody::Xml("<document><documentNumber>INV-7</documentNumber></document>".into()),
+        ))
+        .unwrap();
+        assert!(inventory.valid);
+        assert_eq!(inventory.document_number, "INV-7");
+    }
+
+    #[test]
+    fn returns_empty_events_list_for_events_add() {
+        let events: EventsList = quick_xml::de::from_str(&body(
+            Method::POST,
+            "events/add",
+            ApiBody::Xml("<eventsList><event><type>x</type></event></eventsList>".into()),
+        ))
+        .unwrap();
+        assert!(events.events.is_empty());
+    }
+
+    #[test]
+    fn reports_success_for_internal_transfer() {
+        let transfer: InternalTransferOperationResult = serde_json::from_str(&body(
+            Method::POST,
+            "v2/documents/internalTransfer",
+            ApiBody::Json(
+                r#"{"dateIncoming":"2024-01-01T10:00:00","status":"NEW","storeFromId":"4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f","storeToId":"4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f","items":[]}"#
+                    .into(),
+            ),
+        ))
+        .unwrap();
+        assert_eq!(transfer.result, "SUCCESS");
+    }
+
+    #[test]
+    fn echoes_saved_product_scale() {
+        let scale: ProductScaleDto = serde_json::from_str(&body(
+            Method::POST,
+            &format!("v2/entities/products/{}/productScale", PRODUCT),
+            ApiBody::Json(format!(r#"{{"id":"{}","productSizes":[]}}"#, PRODUCT)),
+        ))
+        .unwrap();
+        assert_eq!(scale.id.to_string(), PRODUCT);
+    }
+
+    #[test]
+    fn returns_nil_id_for_product_scale_delete() {
+        assert_eq!(
+            body(
+                Method::DELETE,
+                &format!("v2/entities/products/{}/productScale", PRODUCT),
+                ApiBody::Empty
+            ),
+            Uuid::nil().to_string()
+        );
+    }
+}
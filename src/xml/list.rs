@@ -0,0 +1,375 @@
+//! Списки в XML-ответах
+//!
+//! Старые отчеты (`reports/sales`, `reports/storeOperations`) и прайс-лист поставщика
+//! возвращают один и тот же список в разном виде: внутри обертки
+//! (`<dayDishValues><dayDishValue>...</dayDishValue></dayDishValues>`), элементами без
+//! обертки, одним элементом или пустым телом. [`deserialize_list`] принимает все
+//! варианты:
+//!
+//! ```
+//! use iiko_server_api_sdk::xml::deserialize_list;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct Item {
+//!     value: f64,
+//! }
+//!
+//! let wrapped = "<items><item><value>1</value></item><item><value>2</value></item></items>";
+//! assert_eq!(deserialize_list::<Item>(wrapped, "items", "item").unwrap().len(), 2);
+//! let single = "<item><value>1</value></item>";
+//! assert_eq!(deserialize_list::<Item>(single, "items", "item").unwrap().len(), 1);
+//! assert!(deserialize_list::<Item>("", "items", "item").unwrap().is_empty());
+//!
+//! let error = deserialize_list::<Item>(&wrapped.replace(">2<", ">два<"), "items", "item")
+//!     .unwrap_err();
+//! assert!(error.to_string().contains("<item> #1, field `value`"));
+//! ```
+//!
+//! Поле DTO, которое iiko присылает одним элементом или несколькими, имеет тип
+//! [`OneOrMany`].
+//!
+//! # Важно
+//! - Элементы списка ищутся на любой глубине под любым корнем, другие элементы
+//!   пропускаются: имя обертки нужно только, чтобы отличить пустой список от чужого ответа
+//! - Тело без элементов списка с корнем, который не совпадает с оберткой (`<error>`,
+//!   HTML-страница прокси), — ошибка [`IikoError::XmlListRoot`], а не пустой список
+//! - Каждый элемент разбирается отдельно: ошибка [`IikoError::XmlListItem`] называет
+//!   элемент, его номер и поле, а не последнюю из попыток разбора
+
+use crate::error::{IikoError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use serde::de::{DeserializeOwned, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Разобрать список элементов `element` из XML-ответа
+///
+/// Список — все элементы `element` на любой глубине, под оберткой `wrapper`, другим
+/// корнем или без корня. Пустое тело или пустая обертка — пустой список. Тело без
+/// элементов с другим корнем — [`IikoError::XmlListRoot`].
+pub fn deserialize_list<T: DeserializeOwned>(
+    xml: &str,
+    wrapper: &str,
+    element: &str,
+) -> Result<Vec<T>> {
+    item_fragments(xml, wrapper, element)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, fragment)| deserialize_item(fragment, element, index))
+        .collect()
+}
+
+/// Текст каждого элемента `element` на любой глубине
+fn item_fragments<'a>(xml: &'a str, wrapper: &str, element: &str) -> Result<Vec<&'a str>> {
+    let mut reader = Reader::from_str(xml);
+    let mut fragments = Vec::new();
+    let mut depth = 0usize;
+    let mut unexpected_root = None;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event().map_err(quick_xml::DeError::from)? {
+            Event::Start(tag) if tag.local_name().as_ref() == element.as_bytes() => {
+                reader
+                    .read_to_end(tag.name())
+                    .map_err(quick_xml::DeError::from)?;
+                fragments.push(&xml[start..reader.buffer_position() as usize]);
+            }
+            Event::Empty(tag) if tag.local_name().as_ref() == element.as_bytes() => {
+                fragments.push(&xml[start..reader.buffer_position() as usize]);
+            }
+            Event::Start(tag) => {
+                if depth == 0 {
+                    note_root(&mut unexpected_root, tag.local_name().as_ref(), wrapper);
+                }
+                depth += 1;
+            }
+            Event::Empty(tag) if depth == 0 => {
+                note_root(&mut unexpected_root, tag.local_name().as_ref(), wrapper);
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    match unexpected_root {
+        Some(root) if fragments.is_empty() => Err(IikoError::XmlListRoot {
+            wrapper: wrapper.to_string(),
+            element: element.to_string(),
+            root,
+        }),
+        _ => Ok(fragments),
+    }
+}
+
+/// Запомнить первый корень, который не совпадает с оберткой
+fn note_root(unexpected_root: &mut Option<String>, root: &[u8], wrapper: &str) {
+    if unexpected_root.is_none() && root != wrapper.as_bytes() {
+        *unexpected_root = Some(String::from_utf8_lossy(root).into_owned());
+    }
+}
+
+fn deserialize_item<T: DeserializeOwned>(fragment: &str, element: &str, index: usize) -> Result<T> {
+    let mut deserializer = quick_xml::de::Deserializer::from_str(fragment);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|error| IikoError::XmlListItem {
+        element: element.to_string(),
+        index,
+        field: error.path().to_string(),
+        message: error.into_inner().to_string(),
+    })
+}
+
+/// Поле DTO со списком, который iiko присылает одним элементом, несколькими подряд
+/// или не присылает вовсе
+///
+/// ```
+/// use iiko_server_api_sdk::xml::OneOrMany;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Report {
+///     #[serde(rename = "row", default)]
+///     rows: OneOrMany<u32>,
+/// }
+///
+/// let one: Report = quick_xml::de::from_str("<report><row>1</row></report>").unwrap();
+/// let many: Report = quick_xml::de::from_str("<report><row>1</row><row>2</row></report>").unwrap();
+/// let none: Report = quick_xml::de::from_str("<report/>").unwrap();
+/// assert_eq!(*one.rows, [1]);
+/// assert_eq!(*many.rows, [1, 2]);
+/// assert!(none.rows.is_empty());
+/// ```
+///
+/// # Важно
+/// - Отсутствующее поле — пустой список только с `#[serde(default)]`
+/// - В JSON поле ждет массив
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct OneOrMany<T>(pub Vec<T>);
+
+impl<T> OneOrMany<T> {
+    /// Элементы списка
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Default for OneOrMany<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> Deref for OneOrMany<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> From<Vec<T>> for OneOrMany<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T> IntoIterator for OneOrMany<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a OneOrMany<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct ItemsVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for ItemsVisitor<T> {
+            type Value = OneOrMany<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("one or more list elements")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(OneOrMany(items))
+            }
+        }
+
+        // quick-xml собирает повторяющиеся элементы поля в последовательность, даже
+        // если элемент один; deserialize_any отдал бы только первый
+        deserializer.deserialize_seq(ItemsVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Value {
+        #[serde(rename = "productId")]
+        product_id: String,
+        value: f64,
+    }
+
+    fn value(product_id: &str, value: f64) -> Value {
+        Value {
+            product_id: product_id.to_string(),
+            value,
+        }
+    }
+
+    fn item(product_id: &str, value: &str) -> String {
+        format!(
+            "<dayDishValue><productId>{product_id}</productId><value>{value}</value></dayDishValue>"
+        )
+    }
+
+    fn list(xml: &str) -> Result<Vec<Value>> {
+        deserialize_list(xml, "dayDishValues", "dayDishValue")
+    }
+
+    #[test]
+    fn reads_items_inside_wrapper_and_skips_other_elements() {
+        let xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<dayDishValues>\n{}\n<total>3</total>\n{}\n</dayDishValues>",
+            item("a", "1.5"),
+            item("b", "2")
+        );
+
+        assert_eq!(list(&xml).unwrap(), [value("a", 1.5), value("b", 2.0)]);
+    }
+
+    #[test]
+    fn reads_items_nested_deeper_in_wrapper() {
+        let xml = format!(
+            "<dayDishValues><page>{}</page>{}</dayDishValues>",
+            item("a", "1.5"),
+            item("b", "2")
+        );
+
+        assert_eq!(list(&xml).unwrap(), [value("a", 1.5), value("b", 2.0)]);
+    }
+
+    #[test]
+    fn reads_bare_and_single_items() {
+        let bare = format!("{}{}", item("a", "1.5"), item("b", "2"));
+
+        assert_eq!(list(&bare).unwrap(), [value("a", 1.5), value("b", 2.0)]);
+        assert_eq!(list(&item("a", "1.5")).unwrap(), [value("a", 1.5)]);
+    }
+
+    #[test]
+    fn reads_empty_body_and_wrapper_as_no_items() {
+        for empty in [
+            "",
+            "  \n",
+            "<dayDishValues/>",
+            "<dayDishValues></dayDishValues>",
+        ] {
+            assert!(list(empty).unwrap().is_empty(), "{empty:?}");
+        }
+    }
+
+    #[test]
+    fn reads_items_under_other_root() {
+        let xml = format!(
+            "<dayDishValueDtoes><page>{}</page>{}</dayDishValueDtoes>",
+            item("a", "1.5"),
+            item("b", "2")
+        );
+
+        assert_eq!(list(&xml).unwrap(), [value("a", 1.5), value("b", 2.0)]);
+    }
+
+    #[test]
+    fn rejects_unexpected_root_without_items() {
+        for (xml, root) in [
+            ("<error>license</error>", "error"),
+            ("<html><body><h1>502 Bad Gateway</h1></body></html>", "html"),
+            ("<dayDishValues/><error/>", "error"),
+        ] {
+            assert!(matches!(
+                list(xml),
+                Err(IikoError::XmlListRoot { wrapper, element, root: actual })
+                    if wrapper == "dayDishValues" && element == "dayDishValue" && actual == root
+            ));
+        }
+    }
+
+    #[test]
+    fn names_item_and_field_that_failed() {
+        let xml = format!(
+            "<dayDishValues>{}{}</dayDishValues>",
+            item("a", "1"),
+            item("b", "n/a")
+        );
+
+        assert!(matches!(
+            list(&xml),
+            Err(IikoError::XmlListItem { element, index: 1, field, .. })
+                if element == "dayDishValue" && field == "value"
+        ));
+    }
+
+    #[test]
+    fn reports_missing_field_of_item() {
+        let error = list("<dayDishValue><value>1</value></dayDishValue>").unwrap_err();
+
+        assert!(error.to_string().contains("missing field `productId`"));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Report {
+        #[serde(rename = "dayDishValue", default)]
+        values: OneOrMany<Value>,
+    }
+
+    #[test]
+    fn one_or_many_reads_single_and_repeated_elements() {
+        let one: Report =
+            quick_xml::de::from_str(&format!("<report>{}</report>", item("a", "1.5"))).unwrap();
+        let many: Report = quick_xml::de::from_str(&format!(
+            "<report>{}{}</report>",
+            item("a", "1.5"),
+            item("b", "2")
+        ))
+        .unwrap();
+
+        assert_eq!(*one.values, [value("a", 1.5)]);
+        assert_eq!(many.values.into_vec(), [value("a", 1.5), value("b", 2.0)]);
+    }
+
+    #[test]
+    fn one_or_many_defaults_to_no_items() {
+        let report: Report = quick_xml::de::from_str("<report/>").unwrap();
+
+        assert!(report.values.is_empty());
+    }
+}
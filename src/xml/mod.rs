@@ -1,6 +1,9 @@
+pub mod list;
 pub mod request;
 pub mod response;
 
+pub use list::{OneOrMany, deserialize_list};
+
 // Re-export request types
 pub use request::{DocumentsRequest, InventoryRequest, Request, SuppliersRequest};
 // Re-export response types
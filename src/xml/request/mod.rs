@@ -1,3 +1,4 @@
+use crate::ids::StoreId;
 use quick_xml::se::to_string;
 use serde::Serialize;
 
@@ -24,7 +25,7 @@ impl<T: Serialize> Request<T> {
 #[serde(rename = "request")]
 pub struct InventoryRequest {
     #[serde(rename = "storeId", skip_serializing_if = "Option::is_none")]
-    pub store_id: Option<String>,
+    pub store_id: Option<StoreId>,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,7 +37,7 @@ pub struct SuppliersRequest {
 #[derive(Debug, Serialize)]
 pub struct DocumentsRequest {
     #[serde(rename = "storeId")]
-    pub store_id: Option<String>,
+    pub store_id: Option<StoreId>,
     #[serde(rename = "dateFrom")]
     pub date_from: Option<String>,
     #[serde(rename = "dateTo")]
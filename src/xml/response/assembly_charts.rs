@@ -1,3 +1,5 @@
+use crate::date::IikoDate;
+use crate::ids::{DepartmentId, ProductId};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -29,7 +31,7 @@ pub enum ProductSizeAssemblyStrategy {
 pub struct StoreSpecification {
     /// Список ID подразделений
     #[serde(rename = "departments", default)]
-    pub departments: Vec<Uuid>,
+    pub departments: Vec<DepartmentId>,
     /// false — фильтр включающий (строка действует для всех перечисленных подразделений)
     /// true — фильтр исключающий (строка действует для всех подразделений, КРОМЕ перечисленных)
     #[serde(rename = "inverse", default)]
@@ -47,7 +49,7 @@ pub struct AssemblyChartItemDto {
     pub sort_weight: f64,
     /// UUID списываемого ингредиента (товара, заготовки)
     #[serde(rename = "productId")]
-    pub product_id: Uuid,
+    pub product_id: ProductId,
     /// UUID размера блюда. Null для строк "общей" (COMMON) техкарты
     #[serde(rename = "productSizeSpecification", default)]
     pub product_size_specification: Option<Uuid>,
@@ -103,16 +105,25 @@ pub struct AssemblyChartDto {
     pub id: Uuid,
     /// UUID приготавливаемого элемента номенклатуры (блюда, модификатора, заготовки)
     #[serde(rename = "assembledProductId")]
-    pub assembled_product_id: Uuid,
+    pub assembled_product_id: ProductId,
     /// Учетный день начала действия технологической карты
     /// Все списания assembledProductId, начиная с 00:00 этого дня, проводятся по данной техкарте
-    #[serde(rename = "dateFrom")]
-    pub date_from: String,
+    #[serde(
+        rename = "dateFrom",
+        deserialize_with = "crate::date::deserialize",
+        serialize_with = "crate::date::serialize_iso"
+    )]
+    pub date_from: IikoDate,
     /// Учетный день прекращения действия технологической карты
     /// Начиная с 00:00 этого дня списания проводятся по СЛЕДУЮЩЕЙ техкарте
     /// null означает, что техкарта действует бессрочно
-    #[serde(rename = "dateTo", default)]
-    pub date_to: Option<String>,
+    #[serde(
+        rename = "dateTo",
+        default,
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_option"
+    )]
+    pub date_to: Option<IikoDate>,
     /// Норма закладки приготавливаемого блюда
     #[serde(rename = "assembledAmount", default)]
     pub assembled_amount: Option<f64>,
@@ -156,7 +167,7 @@ pub struct PreparedChartItemDto {
     pub sort_weight: f64,
     /// UUID списываемого ингредиента (товара, заготовки)
     #[serde(rename = "productId")]
-    pub product_id: Uuid,
+    pub product_id: ProductId,
     /// UUID размера блюда. Null для строк "общей" (COMMON) техкарты
     #[serde(rename = "productSizeSpecification", default)]
     pub product_size_specification: Option<Uuid>,
@@ -182,13 +193,22 @@ pub struct PreparedChartDto {
     pub id: Uuid,
     /// UUID приготавливаемого элемента номенклатуры (блюда, модификатора, заготовки)
     #[serde(rename = "assembledProductId")]
-    pub assembled_product_id: Uuid,
+    pub assembled_product_id: ProductId,
     /// Учетный день начала действия технологической карты
-    #[serde(rename = "dateFrom")]
-    pub date_from: String,
+    #[serde(
+        rename = "dateFrom",
+        deserialize_with = "crate::date::deserialize",
+        serialize_with = "crate::date::serialize_iso"
+    )]
+    pub date_from: IikoDate,
     /// Учетный день прекращения действия технологической карты (null означает бессрочно)
-    #[serde(rename = "dateTo", default)]
-    pub date_to: Option<String>,
+    #[serde(
+        rename = "dateTo",
+        default,
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_option"
+    )]
+    pub date_to: Option<IikoDate>,
     /// Список UUID подразделений, где элемент номенклатуры списывается целиком
     #[serde(rename = "effectiveDirectWriteoffStoreSpecification", default)]
     pub effective_direct_writeoff_store_specification: Option<StoreSpecification>,
@@ -1,4 +1,5 @@
 use super::common::IdName;
+use crate::ids::DepartmentId;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -143,7 +144,7 @@ pub struct GroupDto {
     #[serde(rename = "name")]
     pub name: String,
     #[serde(rename = "departmentId", default)]
-    pub department_id: Option<Uuid>,
+    pub department_id: Option<DepartmentId>,
     #[serde(rename = "groupServiceMode")]
     pub group_service_mode: String,
     #[serde(rename = "pointOfSaleDtoes")]
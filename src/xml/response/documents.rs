@@ -1,3 +1,6 @@
+use crate::date::{IikoDate, IikoDateTime};
+use crate::ids::{ProductArticle, ProductId, StoreCode, StoreId, SupplierId};
+use crate::money::Amount;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -7,8 +10,12 @@ pub struct Document {
     pub id: Uuid,
     #[serde(rename = "number")]
     pub number: String,
-    #[serde(rename = "date")]
-    pub date: String,
+    #[serde(
+        rename = "date",
+        deserialize_with = "crate::date::deserialize",
+        serialize_with = "crate::date::serialize_iso_date_time"
+    )]
+    pub date: IikoDateTime,
     #[serde(rename = "type")]
     pub doc_type: String,
 }
@@ -60,8 +67,12 @@ pub struct InternalTransferDto {
     #[serde(rename = "id", default, skip_serializing_if = "Option::is_none")]
     pub id: Option<Uuid>,
     /// Дата документа.
-    #[serde(rename = "dateIncoming")]
-    pub date_incoming: String,
+    #[serde(
+        rename = "dateIncoming",
+        deserialize_with = "crate::date::deserialize",
+        serialize_with = "crate::date::serialize_iso_date_time"
+    )]
+    pub date_incoming: IikoDateTime,
     /// Номер документа. Если не задан при создании, генерируется iiko.
     #[serde(
         rename = "documentNumber",
@@ -84,10 +95,10 @@ pub struct InternalTransferDto {
     pub comment: Option<String>,
     /// Склад-источник.
     #[serde(rename = "storeFromId")]
-    pub store_from_id: Uuid,
+    pub store_from_id: StoreId,
     /// Склад-получатель.
     #[serde(rename = "storeToId")]
-    pub store_to_id: Uuid,
+    pub store_to_id: StoreId,
     /// Позиции документа.
     #[serde(rename = "items", default)]
     pub items: Vec<InternalTransferItemDto>,
@@ -101,7 +112,7 @@ pub struct InternalTransferItemDto {
     pub num: Option<i32>,
     /// Товар.
     #[serde(rename = "productId")]
-    pub product_id: Uuid,
+    pub product_id: ProductId,
     /// Количество в базовых единицах товара.
     #[serde(rename = "amount")]
     pub amount: f64,
@@ -198,13 +209,15 @@ pub struct IncomingInvoiceDto {
     /// Учетный номер документа
     #[serde(rename = "documentNumber", default)]
     pub document_number: Option<String>,
-    /// Дата документа (формат: dd.MM.yyyy)
+    /// Дата документа (формат: yyyy-MM-ddTHH:mm:ss)
     #[serde(
         rename = "dateIncoming",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_date_time_option"
     )]
-    pub date_incoming: Option<String>,
+    pub date_incoming: Option<IikoDateTime>,
     /// Номер счет-фактуры
     #[serde(rename = "invoice", default, skip_serializing_if = "Option::is_none")]
     pub invoice: Option<String>,
@@ -215,21 +228,29 @@ pub struct IncomingInvoiceDto {
         default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub default_store: Option<Uuid>,
+    pub default_store: Option<StoreId>,
     /// Поставщик (UUID)
     #[serde(rename = "supplier", default, skip_serializing_if = "Option::is_none")]
-    pub supplier: Option<Uuid>,
+    pub supplier: Option<SupplierId>,
     /// Срок оплаты (формат: dd.MM.yyyy)
-    #[serde(rename = "dueDate", default, skip_serializing_if = "Option::is_none")]
-    pub due_date: Option<String>,
+    #[serde(
+        rename = "dueDate",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_day_option"
+    )]
+    pub due_date: Option<IikoDate>,
     /// Входящая дата внешнего документа в формате yyyy-MM-dd (с версии 7.6.1)
     /// Если при импорте не указана, то берется из dateIncoming
     #[serde(
         rename = "incomingDate",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_option"
     )]
-    pub incoming_date: Option<String>,
+    pub incoming_date: Option<IikoDate>,
     /// Использовать настройки проведения документов (с версии 5.2)
     /// false (по умолчанию): использовать переданные дату-время dateIncoming как есть
     /// true: использовать настройки проведения документов, заданные в подразделении
@@ -272,8 +293,12 @@ pub struct IncomingInvoiceItemDto {
     #[serde(rename = "isAdditionalExpense", default)]
     pub is_additional_expense: bool,
     /// Количество товара в его основных единицах измерения
-    #[serde(rename = "amount", default)]
-    pub amount: Option<f64>,
+    #[serde(
+        rename = "amount",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub amount: Option<Amount>,
     /// Товар у поставщика (UUID)
     #[serde(rename = "supplierProduct", default)]
     pub supplier_product: Option<Uuid>,
@@ -282,10 +307,10 @@ pub struct IncomingInvoiceItemDto {
     pub supplier_product_article: Option<String>,
     /// Товар (UUID). Хотя бы одно из полей должно быть заполнено: product или productArticle
     #[serde(rename = "product", default)]
-    pub product: Option<Uuid>,
+    pub product: Option<ProductId>,
     /// Товар (артикул). Можно задать вместо guid товара начиная с 5.0, guid имеет приоритет
     #[serde(rename = "productArticle", default)]
-    pub product_article: Option<String>,
+    pub product_article: Option<ProductArticle>,
     /// Производитель/импортер (UUID)
     /// Должен содержаться в списке производителей/импортеров в карточке товара
     #[serde(rename = "producer", default)]
@@ -300,45 +325,73 @@ pub struct IncomingInvoiceItemDto {
     #[serde(rename = "amountUnit", default)]
     pub amount_unit: Option<Uuid>,
     /// Вес единицы измерения (не реализовано)
-    #[serde(rename = "actualUnitWeight", default)]
-    pub actual_unit_weight: Option<f64>,
+    #[serde(
+        rename = "actualUnitWeight",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub actual_unit_weight: Option<Amount>,
     /// Сумма строки без учета скидки (обязательное поле)
     /// Как правило sum == amount * price / container + discountSum + vatSum
-    #[serde(rename = "sum")]
-    pub sum: f64,
+    #[serde(rename = "sum", deserialize_with = "crate::money::deserialize_text")]
+    pub sum: Amount,
     /// Сумма скидки (не реализовано)
-    #[serde(rename = "discountSum", default)]
-    pub discount_sum: Option<f64>,
+    #[serde(
+        rename = "discountSum",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub discount_sum: Option<Amount>,
     /// Величина процента НДС (с версии 5.0)
     /// Если не задана сумма, она вычисляется по проценту
     /// Если не задан процент, он берется из карточки товара
     /// Нельзя задать только сумму, не задавая процент
-    #[serde(rename = "vatPercent", default)]
-    pub vat_percent: Option<f64>,
+    #[serde(
+        rename = "vatPercent",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub vat_percent: Option<Amount>,
     /// Сумма НДС для строки документа (с версии 5.0)
-    #[serde(rename = "vatSum", default)]
-    pub vat_sum: Option<f64>,
+    #[serde(
+        rename = "vatSum",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub vat_sum: Option<Amount>,
     /// Цена единицы измерения
     #[serde(rename = "priceUnit", default)]
     pub price_unit: Option<String>,
     /// Цена за единицу
-    #[serde(rename = "price", default)]
-    pub price: Option<f64>,
+    #[serde(
+        rename = "price",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub price: Option<Amount>,
     /// Цена без НДС за фасовку с учетом скидки (с версии 6.2)
-    #[serde(rename = "priceWithoutVat", default)]
-    pub price_without_vat: Option<f64>,
+    #[serde(
+        rename = "priceWithoutVat",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub price_without_vat: Option<Amount>,
     /// Код (не реализовано)
     #[serde(rename = "code", default)]
     pub code: Option<String>,
     /// Склад (UUID)
     #[serde(rename = "store", default)]
-    pub store: Option<Uuid>,
+    pub store: Option<StoreId>,
     /// Номер государственной таможенной декларации
     #[serde(rename = "customsDeclarationNumber", default)]
     pub customs_declaration_number: Option<String>,
     /// Фактическое (подтвержденное) количество основных единиц товара
-    #[serde(rename = "actualAmount", default)]
-    pub actual_amount: Option<f64>,
+    #[serde(
+        rename = "actualAmount",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub actual_amount: Option<Amount>,
 }
 
 /// Результат валидации документа (DocumentValidationResult)
@@ -372,6 +425,19 @@ pub struct DocumentValidationResult {
     pub additional_info: Option<String>,
 }
 
+impl DocumentValidationResult {
+    /// Вернуть `IikoError::DocumentValidation`, если документ не прошел проверку
+    ///
+    /// Результат с `warning = true` ошибкой не считается.
+    pub fn into_result(self) -> crate::error::Result<Self> {
+        if self.valid || self.warning {
+            Ok(self)
+        } else {
+            Err(self.into())
+        }
+    }
+}
+
 /// Расходная накладная (OutgoingInvoiceDto)
 ///
 /// # Согласно документации iiko API v4.4:
@@ -403,9 +469,11 @@ pub struct OutgoingInvoiceDto {
     #[serde(
         rename = "dateIncoming",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_date_time_option"
     )]
-    pub date_incoming: Option<String>,
+    pub date_incoming: Option<IikoDateTime>,
     /// Использовать настройки проведения документов
     /// false (по умолчанию): использовать переданные дату-время dateIncoming как есть
     /// true: использовать настройки проведения документов, заданные в подразделении
@@ -436,9 +504,10 @@ pub struct OutgoingInvoiceDto {
     #[serde(
         rename = "defaultStoreId",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
     )]
-    pub default_store_id: Option<String>,
+    pub default_store_id: Option<StoreId>,
     /// Склад (код)
     /// При создании накладных с проведением обязателен
     /// Заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
@@ -447,7 +516,7 @@ pub struct OutgoingInvoiceDto {
         default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub default_store_code: Option<String>,
+    pub default_store_code: Option<StoreCode>,
     /// Контрагент (UUID)
     #[serde(
         rename = "counteragentId",
@@ -505,25 +574,35 @@ pub struct OutgoingInvoiceItems {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutgoingInvoiceItemDto {
     /// Элемент номенклатуры (UUID)
-    #[serde(rename = "productId", default, skip_serializing_if = "Option::is_none")]
-    pub product_id: Option<String>,
+    #[serde(
+        rename = "productId",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub product_id: Option<ProductId>,
     /// Элемент номенклатуры (код/артикул)
     #[serde(
         rename = "productArticle",
         default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub product_article: Option<String>,
+    pub product_article: Option<ProductArticle>,
     /// Склад (UUID)
     /// При создании накладных с проведением обязателен
     /// Заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
-    #[serde(rename = "storeId", default, skip_serializing_if = "Option::is_none")]
-    pub store_id: Option<String>,
+    #[serde(
+        rename = "storeId",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub store_id: Option<StoreId>,
     /// Склад (код)
     /// При создании накладных с проведением обязателен
     /// Заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
     #[serde(rename = "storeCode", default, skip_serializing_if = "Option::is_none")]
-    pub store_code: Option<String>,
+    pub store_code: Option<StoreCode>,
     /// Фасовка (UUID)
     #[serde(
         rename = "containerId",
@@ -618,9 +697,11 @@ pub struct ReturnedInvoiceDto {
     #[serde(
         rename = "dateIncoming",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_date_time_option"
     )]
-    pub date_incoming: Option<String>,
+    pub date_incoming: Option<IikoDateTime>,
     /// Использовать настройки проведения документов (с версии 5.2)
     /// false (по умолчанию): использовать переданные дату-время dateIncoming как есть
     /// true: использовать настройки проведения документов, заданные в подразделении
@@ -634,8 +715,12 @@ pub struct ReturnedInvoiceDto {
     pub incoming_invoice_number: String,
     /// Учетная дата приходной накладной, для которой заводится возврат (обязательное поле)
     /// Формат: yyyy-MM-ddTHH:mm:ss или yyyy-MM-dd
-    #[serde(rename = "incomingInvoiceDate")]
-    pub incoming_invoice_date: String,
+    #[serde(
+        rename = "incomingInvoiceDate",
+        deserialize_with = "crate::date::deserialize",
+        serialize_with = "crate::date::serialize_iso_date_time"
+    )]
+    pub incoming_invoice_date: IikoDateTime,
     /// Способ учета себестоимости
     /// true: списывать по цене приобретения товара
     /// false: списывать по текущей (средневзвешенной скользящей) себестоимости
@@ -655,9 +740,10 @@ pub struct ReturnedInvoiceDto {
     #[serde(
         rename = "defaultStoreId",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
     )]
-    pub default_store_id: Option<String>,
+    pub default_store_id: Option<StoreId>,
     /// Склад (код)
     /// При создании накладных с проведением обязателен
     /// Заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
@@ -666,7 +752,7 @@ pub struct ReturnedInvoiceDto {
         default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub default_store_code: Option<String>,
+    pub default_store_code: Option<StoreCode>,
     /// Контрагент (UUID)
     #[serde(
         rename = "counteragentId",
@@ -718,8 +804,13 @@ pub struct ReturnedInvoiceItems {
 pub struct ReturnedInvoiceItemDto {
     /// Элемент номенклатуры (UUID)
     /// Хотя бы одно из полей должно быть заполнено: productId или productArticle
-    #[serde(rename = "productId", default, skip_serializing_if = "Option::is_none")]
-    pub product_id: Option<String>,
+    #[serde(
+        rename = "productId",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub product_id: Option<ProductId>,
     /// Элемент номенклатуры (код/артикул)
     /// Хотя бы одно из полей должно быть заполнено: productId или productArticle
     #[serde(
@@ -727,7 +818,7 @@ pub struct ReturnedInvoiceItemDto {
         default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub product_article: Option<String>,
+    pub product_article: Option<ProductArticle>,
     /// Товар поставщика (UUID) (с версии 5.0.2)
     #[serde(
         rename = "supplierProduct",
@@ -752,13 +843,18 @@ pub struct ReturnedInvoiceItemDto {
     /// Склад (UUID)
     /// При создании накладных с проведением обязателен
     /// Заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
-    #[serde(rename = "storeId", default, skip_serializing_if = "Option::is_none")]
-    pub store_id: Option<String>,
+    #[serde(
+        rename = "storeId",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub store_id: Option<StoreId>,
     /// Склад (код)
     /// При создании накладных с проведением обязателен
     /// Заполняется либо в документе, либо в каждой строке отдельно, но не одновременно
     #[serde(rename = "storeCode", default, skip_serializing_if = "Option::is_none")]
-    pub store_code: Option<String>,
+    pub store_code: Option<StoreCode>,
     /// Фасовка (UUID)
     #[serde(
         rename = "containerId",
@@ -849,9 +945,11 @@ pub struct IncomingInventoryDto {
     #[serde(
         rename = "dateIncoming",
         default,
-        skip_serializing_if = "Option::is_none"
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_date_time_option"
     )]
-    pub date_incoming: Option<String>,
+    pub date_incoming: Option<IikoDateTime>,
     /// Использовать настройки проведения документов
     /// false (по умолчанию): использовать переданные дату-время dateIncoming как есть
     /// true: использовать настройки проведения документов, заданные в подразделении
@@ -878,12 +976,17 @@ pub struct IncomingInventoryDto {
     pub account_shortage_code: Option<String>,
     /// Склад (UUID)
     /// Обязателен для заполнения
-    #[serde(rename = "storeId", default, skip_serializing_if = "Option::is_none")]
-    pub store_id: Option<String>,
+    #[serde(
+        rename = "storeId",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub store_id: Option<StoreId>,
     /// Склад (код)
     /// Обязателен для заполнения
     #[serde(rename = "storeCode", default, skip_serializing_if = "Option::is_none")]
-    pub store_code: Option<String>,
+    pub store_code: Option<StoreCode>,
     /// Концепция (UUID)
     #[serde(
         rename = "conceptionId",
@@ -932,15 +1035,20 @@ pub struct IncomingInventoryItemDto {
     )]
     pub recalculation_number: Option<i32>,
     /// Элемент номенклатуры (UUID)
-    #[serde(rename = "productId", default, skip_serializing_if = "Option::is_none")]
-    pub product_id: Option<String>,
+    #[serde(
+        rename = "productId",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub product_id: Option<ProductId>,
     /// Элемент номенклатуры (код/артикул)
     #[serde(
         rename = "productArticle",
         default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub product_article: Option<String>,
+    pub product_article: Option<ProductArticle>,
     /// Фасовка (UUID)
     #[serde(
         rename = "containerId",
@@ -1143,16 +1251,16 @@ mod internal_transfer_tests {
     fn internal_transfer_serializes_create_payload_without_read_only_fields() {
         let transfer = InternalTransferDto {
             id: None,
-            date_incoming: "2021-11-15T06:00".to_string(),
+            date_incoming: "2021-11-15T06:00:00".parse().unwrap(),
             document_number: None,
             status: DocumentStatus::New,
             conception_id: None,
             comment: Some("zzz".to_string()),
-            store_from_id: uuid("05a407d4-d7c6-4bc2-a578-6ad5de99d468"),
-            store_to_id: uuid("370620fe-c789-46db-9d92-33bec29b82a3"),
+            store_from_id: uuid("05a407d4-d7c6-4bc2-a578-6ad5de99d468").into(),
+            store_to_id: uuid("370620fe-c789-46db-9d92-33bec29b82a3").into(),
             items: vec![InternalTransferItemDto {
                 num: None,
-                product_id: uuid("ccdada6c-1643-4c52-9e09-752a4de117a0"),
+                product_id: uuid("ccdada6c-1643-4c52-9e09-752a4de117a0").into(),
                 amount: 5.0,
                 measure_unit_id: None,
                 container_id: Some(uuid("e2e67737-18bf-437b-8230-8ec17da75096")),
@@ -1162,7 +1270,7 @@ mod internal_transfer_tests {
 
         let value = serde_json::to_value(&transfer).unwrap();
 
-        assert_eq!(value["dateIncoming"], "2021-11-15T06:00");
+        assert_eq!(value["dateIncoming"], "2021-11-15T06:00:00");
         assert_eq!(value["status"], "NEW");
         assert_eq!(value["comment"], "zzz");
         assert_eq!(value["items"][0]["amount"], 5.0);
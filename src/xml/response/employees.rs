@@ -1,3 +1,4 @@
+use crate::ids::{DepartmentCode, EmployeeCode, EmployeeId};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
@@ -31,10 +32,10 @@ where
 pub struct Employee {
     /// UUID сотрудника
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: EmployeeId,
     /// Табельный номер сотрудника. Пуст у системных учетных записей
     #[serde(rename = "code")]
-    pub code: String,
+    pub code: EmployeeCode,
     /// Имя сотрудника
     #[serde(rename = "name")]
     pub name: String,
@@ -115,13 +116,13 @@ pub struct Employee {
     pub deactivation_date: Option<String>,
     /// Предпочтительное подразделение (одно из departmentCodes), в котором сотруднику назначаются смены в первую очередь (с версии 5.0)
     #[serde(rename = "preferredDepartmentCode", default)]
-    pub preferred_department_code: Option<String>,
+    pub preferred_department_code: Option<DepartmentCode>,
     /// Назначенные подразделения. Если null - сотруднику назначены все подразделения (существующие и будущие)
     #[serde(rename = "departmentCodes", default)]
-    pub department_codes: Option<Vec<String>>,
+    pub department_codes: Option<Vec<DepartmentCode>>,
     /// Подразделения, в которых сотрудник является ответственным. Если null - сотрудник является ответственным во всех существующих и будущих подразделениях
     #[serde(rename = "responsibilityDepartmentCodes", default)]
-    pub responsibility_department_codes: Option<Vec<String>>,
+    pub responsibility_department_codes: Option<Vec<DepartmentCode>>,
     /// Удален
     #[serde(rename = "deleted", default)]
     pub deleted: Option<String>,
@@ -159,8 +160,8 @@ pub struct Employee {
 impl Default for Employee {
     fn default() -> Self {
         Self {
-            id: Uuid::nil(),
-            code: String::new(),
+            id: EmployeeId::from(Uuid::nil()),
+            code: EmployeeCode::default(),
             name: String::new(),
             login: None,
             password: None,
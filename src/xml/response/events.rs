@@ -1,3 +1,5 @@
+use crate::date::IikoDateTime;
+use crate::ids::DepartmentId;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -18,14 +20,19 @@ pub struct Event {
     #[serde(rename = "id", default)]
     pub id: Option<Uuid>,
     /// Дата и время события
-    #[serde(rename = "date", default)]
-    pub date: Option<String>,
+    #[serde(
+        rename = "date",
+        default,
+        deserialize_with = "crate::date::deserialize_option",
+        serialize_with = "crate::date::serialize_iso_date_time_millis_option"
+    )]
+    pub date: Option<IikoDateTime>,
     /// Тип события
     #[serde(rename = "type", default)]
     pub r#type: Option<String>,
     /// ID департамента (guid) - для iikoChain
     #[serde(rename = "departmentId", default)]
-    pub department_id: Option<Uuid>,
+    pub department_id: Option<DepartmentId>,
     /// Список атрибутов события
     #[serde(rename = "attribute", default)]
     pub attributes: Vec<EventAttribute>,
@@ -1,3 +1,5 @@
+use crate::ids::{ProductArticle, ProductCategoryId, ProductGroupId, ProductId};
+use crate::money::Amount;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -163,7 +165,7 @@ pub struct ProductDto {
     /// - Генерируется при создании (save)
     /// - Может быть null при ошибке импорта/обновления
     #[serde(rename = "id", default)]
-    pub id: Option<Uuid>,
+    pub id: Option<ProductId>,
     #[serde(rename = "deleted", default)]
     pub deleted: bool,
     #[serde(rename = "name", default)]
@@ -171,17 +173,17 @@ pub struct ProductDto {
     #[serde(rename = "description", default)]
     pub description: Option<String>,
     #[serde(rename = "num", default)]
-    pub num: Option<String>,
+    pub num: Option<ProductArticle>,
     #[serde(rename = "code", default)]
     pub code: Option<String>,
     #[serde(rename = "parent", default)]
-    pub parent: Option<Uuid>,
+    pub parent: Option<ProductGroupId>,
     /// Модификаторы (версия 6.2+)
     #[serde(rename = "modifiers", default)]
     pub modifiers: Vec<ChoiceBindingDto>,
     /// UUID пользовательской категории
     #[serde(rename = "category", default)]
-    pub category: Option<Uuid>,
+    pub category: Option<ProductCategoryId>,
     /// UUID бухгалтерской категории (по умолчанию "товар", версия 6.2.3+)
     #[serde(rename = "accountingCategory", default)]
     pub accounting_category: Option<Uuid>,
@@ -206,8 +208,12 @@ pub struct ProductDto {
     pub excluded_sections: Option<Vec<Uuid>>,
     /// Цена, по которой по умолчанию продаётся продукт (если для него нет приказов о меню).
     /// По умолчанию 0
-    #[serde(rename = "defaultSalePrice", default)]
-    pub default_sale_price: Option<f64>,
+    #[serde(
+        rename = "defaultSalePrice",
+        default,
+        serialize_with = "crate::money::serialize_number"
+    )]
+    pub default_sale_price: Option<Amount>,
     /// UUID места приготовления блюда (поле имеет смысл только для блюд).
     /// Обязателен, если `defaultIncludeInMenu == true`
     #[serde(rename = "placeType", default)]
@@ -250,8 +256,12 @@ pub struct ProductDto {
     pub allergen_groups: Option<Vec<String>>,
     /// Оценочная себестоимость. Поле используется для расчёта себестоимости в случае,
     /// если по данному товару не было приходов (по умолчанию 0, версия 7.1.5+)
-    #[serde(rename = "estimatedPurchasePrice", default)]
-    pub estimated_purchase_price: Option<f64>,
+    #[serde(
+        rename = "estimatedPurchasePrice",
+        default,
+        serialize_with = "crate::money::serialize_number"
+    )]
+    pub estimated_purchase_price: Option<Amount>,
     /// Свободная цена (версия 7.4.4+)
     #[serde(rename = "canSetOpenPrice", default)]
     pub can_set_open_price: Option<bool>,
@@ -274,7 +284,7 @@ pub struct Color {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductGroupDto {
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: ProductGroupId,
     #[serde(rename = "deleted", default)]
     pub deleted: bool,
     #[serde(rename = "name", default)]
@@ -286,7 +296,7 @@ pub struct ProductGroupDto {
     #[serde(rename = "code", default)]
     pub code: Option<String>,
     #[serde(rename = "parent", default)]
-    pub parent: Option<Uuid>,
+    pub parent: Option<ProductGroupId>,
     #[serde(rename = "color", default)]
     pub color: Option<Color>,
     #[serde(rename = "fontColor", default)]
@@ -396,7 +406,7 @@ pub struct ItemsRequest {
 pub struct EntityDto {
     /// UUID категории
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: ProductCategoryId,
     /// Тип корневой сущности (например, "ProductCategory")
     #[serde(rename = "rootType", default)]
     pub root_type: Option<String>,
@@ -424,7 +434,7 @@ pub struct CategorySaveRequest {
 pub struct CategoryUpdateRequest {
     /// UUID редактируемой категории (обязательно)
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: ProductCategoryId,
     /// Новое имя категории (обязательно)
     #[serde(rename = "name")]
     pub name: String,
@@ -435,7 +445,7 @@ pub struct CategoryUpdateRequest {
 pub struct CategoryDeleteRequest {
     /// UUID удаляемой категории (обязательно)
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: ProductCategoryId,
 }
 
 /// Запрос на восстановление категории
@@ -443,7 +453,7 @@ pub struct CategoryDeleteRequest {
 pub struct CategoryRestoreRequest {
     /// UUID восстанавливаемой категории (обязательно)
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: ProductCategoryId,
 }
 
 /// Результат операции с категорией (импорт/обновление/удаление/восстановление)
@@ -1,5 +1,5 @@
+use crate::ids::DepartmentId;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 /// Тип сервера
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -14,7 +14,7 @@ pub enum ServerType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReplicationStatus {
     #[serde(rename = "departmentId", default)]
-    pub department_id: Option<Uuid>,
+    pub department_id: Option<DepartmentId>,
     #[serde(rename = "departmentName", default)]
     pub department_name: Option<String>,
     #[serde(rename = "lastReplicationDate", default)]
@@ -1,3 +1,5 @@
+use crate::ids::{AccountId, DepartmentId, ProductArticle, ProductId, StoreId};
+use crate::money::Amount;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -22,14 +24,14 @@ pub enum OlapFilter {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceCounteragent {
     /// ID счета
-    pub account: String,
+    pub account: AccountId,
     /// ID контрагента (может быть null)
     #[serde(default)]
     pub counteragent: Option<String>,
     /// ID подразделения
-    pub department: String,
+    pub department: DepartmentId,
     /// Сумма баланса
-    pub sum: f64,
+    pub sum: Amount,
 }
 
 /// Остаток товара на складе
@@ -39,13 +41,13 @@ pub struct BalanceCounteragent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceStore {
     /// ID склада
-    pub store: String,
+    pub store: StoreId,
     /// ID элемента номенклатуры
-    pub product: String,
+    pub product: ProductId,
     /// Количество товара
-    pub amount: f64,
+    pub amount: Amount,
     /// Сумма остатка
-    pub sum: f64,
+    pub sum: Amount,
 }
 
 /// Состояние акцизной марки
@@ -313,8 +315,8 @@ pub enum OlapFieldValue {
     String(String),
     /// Целое число (для INTEGER, DURATION_IN_SECONDS)
     Integer(i64),
-    /// Число с плавающей точкой (для PERCENT, AMOUNT, MONEY)
-    Float(f64),
+    /// Дробное число (для PERCENT, AMOUNT, MONEY), см. `crate::money::Amount`
+    Float(Amount),
     /// Null значение
     Null,
 }
@@ -339,12 +341,22 @@ impl OlapFieldValue {
     /// Получить значение как число с плавающей точкой, если это число
     pub fn as_float(&self) -> Option<f64> {
         match self {
-            OlapFieldValue::Float(f) => Some(*f),
+            OlapFieldValue::Float(f) => Some(crate::money::to_f64(*f)),
             OlapFieldValue::Integer(i) => Some(*i as f64),
             _ => None,
         }
     }
 
+    /// Получить число как `Decimal` без потерь (денежные колонки OLAP)
+    #[cfg(feature = "rust_decimal")]
+    pub fn as_decimal(&self) -> Option<rust_decimal::Decimal> {
+        match self {
+            OlapFieldValue::Float(f) => Some(*f),
+            OlapFieldValue::Integer(i) => Some(rust_decimal::Decimal::from(*i)),
+            _ => None,
+        }
+    }
+
     /// Проверить, является ли значение null
     pub fn is_null(&self) -> bool {
         matches!(self, OlapFieldValue::Null)
@@ -1021,8 +1033,12 @@ pub struct DayDishValue {
     #[serde(rename = "date", default)]
     pub date: Option<String>,
     /// ID продукта
-    #[serde(rename = "productId", default)]
-    pub product_id: Option<String>,
+    #[serde(
+        rename = "productId",
+        default,
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub product_id: Option<ProductId>,
     /// Название продукта
     #[serde(rename = "productName", default)]
     pub product_name: Option<String>,
@@ -1100,10 +1116,14 @@ pub struct IngredientEntryDto {
     pub name: Option<String>,
     /// Артикул продукта
     #[serde(rename = "num", default)]
-    pub num: Option<String>,
+    pub num: Option<ProductArticle>,
     /// ID продукта (guid)
-    #[serde(rename = "product", default)]
-    pub product: Option<String>,
+    #[serde(
+        rename = "product",
+        default,
+        deserialize_with = "crate::ids::deserialize_optional"
+    )]
+    pub product: Option<ProductId>,
     /// Стоимость товара в блюде
     #[serde(rename = "productInDishCost", default)]
     pub product_in_dish_cost: Option<f64>,
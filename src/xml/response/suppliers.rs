@@ -1,3 +1,5 @@
+use crate::ids::{ProductArticle, ProductId, SupplierCode, SupplierId};
+use crate::money::Amount;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -10,10 +12,10 @@ use uuid::Uuid;
 pub struct Supplier {
     /// UUID поставщика
     #[serde(rename = "id")]
-    pub id: Uuid,
+    pub id: SupplierId,
     /// Табельный номер/Код поставщика
     #[serde(rename = "code")]
-    pub code: String,
+    pub code: SupplierCode,
     /// Имя поставщика
     #[serde(rename = "name")]
     pub name: String,
@@ -129,13 +131,13 @@ pub struct Suppliers {
 pub struct SupplierPriceListItemDto {
     /// Товар у нас (UUID)
     #[serde(rename = "nativeProduct", default)]
-    pub native_product: Option<Uuid>,
+    pub native_product: Option<ProductId>,
     /// Код товара у нас
     #[serde(rename = "nativeProductCode", default)]
     pub native_product_code: Option<String>,
     /// Артикул товара у нас
     #[serde(rename = "nativeProductNum", default)]
-    pub native_product_num: Option<String>,
+    pub native_product_num: Option<ProductArticle>,
     /// Название товара у нас
     #[serde(rename = "nativeProductName", default)]
     pub native_product_name: Option<String>,
@@ -152,11 +154,19 @@ pub struct SupplierPriceListItemDto {
     #[serde(rename = "supplierProductName", default)]
     pub supplier_product_name: Option<String>,
     /// Стоимость товара
-    #[serde(rename = "costPrice", default)]
-    pub cost_price: Option<f64>,
+    #[serde(
+        rename = "costPrice",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub cost_price: Option<Amount>,
     /// Допустимое отклонение от цены (%)
-    #[serde(rename = "allowablePriceDeviation", default)]
-    pub allowable_price_deviation: Option<f64>,
+    #[serde(
+        rename = "allowablePriceDeviation",
+        default,
+        deserialize_with = "crate::money::deserialize_text_option"
+    )]
+    pub allowable_price_deviation: Option<Amount>,
     /// Фасовка
     #[serde(rename = "container", default)]
     pub container: Option<SupplierContainerDto>,
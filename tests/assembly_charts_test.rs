@@ -1,5 +1,6 @@
 mod common;
 use common::{cleanup_after_test, get_test_client};
+use iiko_server_api_sdk::{AssemblyChartsQuery, ProductQuery};
 
 #[tokio::test]
 async fn test_get_all() {
@@ -10,7 +11,11 @@ async fn test_get_all() {
     let today = "2024-01-01".to_string(); // Используем фиксированную дату для теста
     let result = client
         .assembly_charts()
-        .get_all(today.clone(), None, Some(true), Some(false))
+        .all(
+            AssemblyChartsQuery::new(today.clone())
+                .include_deleted_products(true)
+                .include_prepared_charts(false),
+        )
         .await;
 
     match result {
@@ -49,7 +54,7 @@ async fn test_get_tree() {
     // Сначала получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
@@ -107,7 +112,7 @@ async fn test_get_assembled() {
     // Сначала получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
@@ -171,7 +176,7 @@ async fn test_get_prepared() {
     // Сначала получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
@@ -233,7 +238,7 @@ async fn test_get_history() {
     // Сначала получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
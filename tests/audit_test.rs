@@ -0,0 +1,134 @@
+mod common;
+
+use common::fake::{BASE_URL, FakeTransport};
+use iiko_server_api_sdk::transport::{StatusCode, async_trait};
+use iiko_server_api_sdk::xml::response::Employee;
+use iiko_server_api_sdk::{
+    AuditEntry, AuditSink, IikoClient, IikoConfig, JsonLinesAuditSink, WriteMode,
+};
+use std::sync::{Arc, Mutex};
+
+/// Журнал в памяти теста
+#[derive(Clone, Default)]
+struct AuditEntries(Arc<Mutex<Vec<AuditEntry>>>);
+
+#[async_trait]
+impl AuditSink for AuditEntries {
+    async fn record(&self, entry: &AuditEntry) -> iiko_server_api_sdk::Result<()> {
+        self.0.lock().unwrap().push(entry.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn audit_sink_records_mutating_calls_with_actor() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "[]")
+        .respond(
+            StatusCode::OK,
+            "<documentValidationResult><valid>true</valid><documentNumber>ПН-42</documentNumber></documentValidationResult>",
+        );
+    let entries = AuditEntries::default();
+    let config = IikoConfig::new(BASE_URL, "admin", "hash").with_version_check(false);
+    let client = IikoClient::with_transport(config, transport.clone())
+        .with_audit_sink(entries.clone())
+        .with_actor("accountant");
+
+    // Читающий POST в журнал не попадает
+    client
+        .products()
+        .list_categories(None, None, None)
+        .await
+        .unwrap();
+    let invoice =
+        quick_xml::de::from_str("<document><documentNumber>ПН-42</documentNumber></document>")
+            .unwrap();
+    client
+        .documents()
+        .import_incoming_invoice(invoice)
+        .await
+        .unwrap();
+
+    let read_only = IikoClient::with_transport(
+        IikoConfig::new(BASE_URL, "admin", "hash").with_write_mode(WriteMode::ReadOnly),
+        transport.clone(),
+    )
+    .with_audit_sink(entries.clone());
+    let employee = uuid::Uuid::new_v4();
+    read_only.employees().delete(employee).await.unwrap_err();
+
+    let entries = entries.0.lock().unwrap().clone();
+    assert_eq!(entries.len(), 2);
+
+    let import = &entries[0];
+    assert_eq!(import.actor.as_deref(), Some("accountant"));
+    assert_eq!(import.method, "POST");
+    assert_eq!(import.endpoint, "documents/import/incomingInvoice");
+    assert!(
+        import
+            .payload
+            .as_deref()
+            .unwrap()
+            .contains("<documentNumber>ПН-42</documentNumber>")
+    );
+    assert!(
+        import
+            .response
+            .as_deref()
+            .unwrap()
+            .contains("<valid>true</valid>")
+    );
+    assert!(import.error.is_none());
+    assert!(!import.dry_run);
+    assert!(import.params.iter().all(|(name, _)| name != "key"));
+
+    let rejected = &entries[1];
+    assert_eq!(rejected.actor, None);
+    assert_eq!(rejected.method, "DELETE");
+    assert!(rejected.response.is_none());
+    assert!(rejected.error.as_deref().unwrap().contains("Read-only"));
+}
+
+#[tokio::test]
+async fn audit_file_never_contains_employee_password() {
+    let path = std::env::temp_dir().join(format!(
+        "iiko-audit-employee-{}-{}.jsonl",
+        std::process::id(),
+        uuid::Uuid::new_v4()
+    ));
+    let transport = FakeTransport::default();
+    transport.respond(StatusCode::OK, "session-1").respond(
+        StatusCode::OK,
+        "<employee><name>Повар</name><password>cook-password</password></employee>",
+    );
+    let config = IikoConfig::new(BASE_URL, "admin", "hash").with_version_check(false);
+    let client = IikoClient::with_transport(config, transport.clone())
+        .with_audit_sink(JsonLinesAuditSink::new(&path));
+    let employee = Employee {
+        name: "Повар".to_string(),
+        password: Some("cook-password".to_string()),
+        ..Employee::default()
+    };
+
+    client
+        .employees()
+        .update_partial(uuid::Uuid::nil(), &employee)
+        .await
+        .unwrap();
+
+    let audit = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        transport
+            .request(1)
+            .body_text()
+            .unwrap()
+            .contains("cook-password"),
+        "the server still receives the password"
+    );
+    assert!(audit.contains("<name>Повар</name>"));
+    assert!(audit.contains("<password>[REDACTED]</password>"));
+    assert!(!audit.contains("cook-password"));
+}
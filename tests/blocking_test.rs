@@ -0,0 +1,58 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use common::fake::{FakeTransport, client, server_info};
+use iiko_server_api_sdk::Priority;
+use iiko_server_api_sdk::transport::StatusCode;
+
+#[test]
+fn blocking_client_calls_endpoints_without_async() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(
+            StatusCode::OK,
+            r#"[{"id":"d3b07384-d9a0-4c9b-8d5f-0c2f5e6a7b8c","name":"Основная"}]"#,
+        )
+        .respond(StatusCode::OK, &server_info("9.1.8011.0"))
+        .respond(
+            StatusCode::OK,
+            r#"{"id":"d3b07384-d9a0-4c9b-8d5f-0c2f5e6a7b8c","data":"aGVsbG8="}"#,
+        )
+        .respond(StatusCode::OK, "");
+
+    let client = iiko_server_api_sdk::blocking::IikoClient::from_async(client(&transport)).unwrap();
+    let categories = client
+        .products()
+        .list_categories(Some(false), None, None)
+        .unwrap();
+    assert_eq!(categories.len(), 1);
+
+    // Клон из другого потока разделяет сессию: повторного /auth нет
+    let clone = client.with_priority(Priority::Bulk);
+    let image = std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let written = clone
+            .images()
+            .load_into(uuid::Uuid::nil(), &mut bytes)
+            .unwrap();
+        assert_eq!(written, 5);
+        bytes
+    })
+    .join()
+    .unwrap();
+    assert_eq!(image, b"hello");
+
+    client.logout().unwrap();
+    assert_eq!(
+        transport.endpoints(),
+        [
+            "auth",
+            "v2/entities/products/category/list",
+            "http://iiko.test/resto/get_server_info.jsp",
+            "v2/images/load",
+            "logout"
+        ]
+    );
+}
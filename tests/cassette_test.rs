@@ -0,0 +1,42 @@
+mod common;
+
+use common::fake::{BASE_URL, FakeTransport};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{IikoClient, IikoConfig, RecordingTransport};
+
+#[tokio::test]
+async fn replays_recorded_cassette_without_secrets() {
+    let transport = FakeTransport::default();
+    transport.respond(StatusCode::OK, "session-1").respond(
+        StatusCode::OK,
+        r#"[{"id":"d3b07384-d9a0-4c9b-8d5f-0c2f5e6a7b8c","name":"Основная"}]"#,
+    );
+
+    let path = std::env::temp_dir().join(format!("iiko-cassette-{}.json", uuid::Uuid::new_v4()));
+    let config = IikoConfig::new(BASE_URL, "admin", "hash");
+    let recorder = IikoClient::with_transport(
+        config.clone(),
+        RecordingTransport::new(transport.clone(), &path),
+    );
+    let recorded = recorder
+        .products()
+        .list_categories(Some(false), None, None)
+        .await
+        .unwrap();
+
+    let cassette = std::fs::read_to_string(&path).unwrap();
+    assert!(!cassette.contains("session-1"));
+    assert!(!cassette.contains("hash"));
+    assert!(!cassette.contains("iiko.test"));
+
+    let replayed = IikoClient::replaying(config, &path)
+        .unwrap()
+        .products()
+        .list_categories(Some(false), None, None)
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(replayed.len(), recorded.len());
+    assert_eq!(replayed[0].id, recorded[0].id);
+}
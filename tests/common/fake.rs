@@ -0,0 +1,85 @@
+//! Транспорт с заготовленными ответами для тестов без сервера iiko
+
+use iiko_server_api_sdk::transport::{StatusCode, async_trait};
+use iiko_server_api_sdk::{
+    IikoClient, IikoConfig, IikoError, RetryPolicy, Transport, TransportRequest, TransportResponse,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const BASE_URL: &str = "http://iiko.test/resto/api";
+
+/// Транспорт, который отдает заранее подготовленные ответы и запоминает запросы
+#[derive(Clone, Default)]
+pub struct FakeTransport {
+    responses: Arc<Mutex<VecDeque<TransportResponse>>>,
+    requests: Arc<Mutex<Vec<TransportRequest>>>,
+    delay: Arc<Mutex<Option<Duration>>>,
+}
+
+impl FakeTransport {
+    pub fn respond(&self, status: StatusCode, body: &str) -> &Self {
+        self.respond_with(TransportResponse::new(status, body))
+    }
+
+    /// Ответ с заголовками или бинарным телом
+    pub fn respond_with(&self, response: TransportResponse) -> &Self {
+        self.responses.lock().unwrap().push_back(response);
+        self
+    }
+
+    pub fn delay(&self, delay: Option<Duration>) {
+        *self.delay.lock().unwrap() = delay;
+    }
+
+    pub fn endpoints(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.endpoint(BASE_URL).to_string())
+            .collect()
+    }
+
+    pub fn request(&self, index: usize) -> TransportRequest {
+        self.requests.lock().unwrap()[index].clone()
+    }
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn send(
+        &self,
+        request: TransportRequest,
+    ) -> iiko_server_api_sdk::Result<TransportResponse> {
+        self.requests.lock().unwrap().push(request);
+
+        let delay = *self.delay.lock().unwrap();
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| IikoError::Transport("no canned response left".to_string()))
+    }
+}
+
+pub fn server_info(version: &str) -> String {
+    format!(
+        "<r><edition>default</edition><version>{}</version><serverState>STARTED_SUCCESSFULLY</serverState></r>",
+        version
+    )
+}
+
+pub fn client(transport: &FakeTransport) -> IikoClient {
+    let retry = RetryPolicy::default()
+        .with_base_delay(Duration::from_millis(1))
+        .with_jitter(0.0);
+    let config = IikoConfig::new(BASE_URL, "admin", "hash").with_retry_policy(retry);
+
+    IikoClient::with_transport(config, transport.clone())
+}
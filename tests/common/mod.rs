@@ -1,3 +1,7 @@
+#![allow(dead_code)]
+
+pub mod fake;
+
 use iiko_server_api_sdk::{IikoClient, IikoConfig};
 
 /// Создать новый клиент для теста
@@ -11,6 +15,7 @@ use iiko_server_api_sdk::{IikoClient, IikoConfig};
 /// # Примечание:
 /// Если тесты падают с ошибкой "License enhancement is required", это означает, что все слоты лицензии заняты.
 /// В этом случае нужно либо подождать истечения старых сессий (обычно 1 час), либо освободить слоты вручную.
+/// В новых тестах удобнее использовать `client.session()`: guard освобождает слот даже при панике теста.
 pub async fn get_test_client() -> IikoClient {
     dotenvy::dotenv().ok();
     let config = IikoConfig::from_env()
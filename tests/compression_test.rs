@@ -0,0 +1,74 @@
+mod common;
+
+use common::fake::{BASE_URL, FakeTransport};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{Compression, IikoClient, IikoConfig, TransportResponse};
+
+#[tokio::test]
+async fn decompresses_responses_and_falls_back_from_request_gzip() {
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    let categories = format!(
+        "[{}]",
+        vec![r#"{"id":"d3b07384-d9a0-4c9b-8d5f-0c2f5e6a7b8c","name":"Основная"}"#; 50].join(",")
+    );
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(categories.as_bytes()).unwrap();
+    let mut gzipped = TransportResponse::new(StatusCode::OK, encoder.finish().unwrap());
+    gzipped
+        .headers
+        .push(("Content-Encoding".to_string(), "gzip".to_string()));
+
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond_with(gzipped)
+        .respond(StatusCode::UNSUPPORTED_MEDIA_TYPE, "")
+        .respond(StatusCode::OK, "<documentValidationResult/>")
+        .respond(StatusCode::OK, "<documentValidationResult/>");
+
+    let client = IikoClient::with_transport(
+        IikoConfig::new(BASE_URL, "admin", "hash")
+            .with_compression(Compression::default().with_request_gzip(8)),
+        transport.clone(),
+    );
+
+    let list = client
+        .products()
+        .list_categories(Some(false), None, None)
+        .await
+        .expect("gzipped categories should parse");
+    assert_eq!(list.len(), 50);
+    assert_eq!(
+        transport.request(1).header("accept-encoding"),
+        Some("gzip, deflate")
+    );
+
+    let stats = client.compression_stats();
+    assert_eq!(stats.compressed_responses, 1);
+    assert_eq!(stats.response_bytes, categories.len() as u64);
+    assert!(stats.saved_bytes() > 0);
+
+    // Сервер не принимает сжатые тела: запрос повторяется без сжатия, дальше не сжимается
+    let document = "<document><documentNumber>INV-1</documentNumber></document>";
+    client
+        .post_xml("documents/import/incomingInvoice", document)
+        .await
+        .unwrap();
+    client
+        .post_xml("documents/import/incomingInvoice", document)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        transport.request(2).header("content-encoding"),
+        Some("gzip")
+    );
+    assert_ne!(transport.request(2).body_text(), Some(document));
+    for index in [3, 4] {
+        assert_eq!(transport.request(index).header("content-encoding"), None);
+        assert_eq!(transport.request(index).body_text(), Some(document));
+    }
+    assert_eq!(client.compression_stats().compressed_requests, 0);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "chrono")]
+
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::transport::StatusCode;
+
+#[tokio::test]
+async fn formats_chrono_dates_per_endpoint() {
+    use chrono::NaiveDate;
+    use iiko_server_api_sdk::AssemblyChartsQuery;
+
+    let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, r#"{"knownRevision":42}"#)
+        .respond(
+            StatusCode::OK,
+            "<eventsList><event><date>2024-03-01T09:15:02.417+03:00</date></event></eventsList>",
+        )
+        .respond(StatusCode::OK, "<supplierPriceList/>");
+    let client = client(&transport);
+
+    client
+        .assembly_charts()
+        .all(AssemblyChartsQuery::new(day).date_to(day.succ_opt().unwrap()))
+        .await
+        .unwrap();
+    let events = client
+        .events()
+        .get_events(Some(day.into()), None, None)
+        .await
+        .unwrap();
+    client
+        .suppliers()
+        .get_pricelist("42", Some(day.into()))
+        .await
+        .unwrap();
+
+    let charts = transport.request(1);
+    assert_eq!(charts.query_param("dateFrom"), Some("2024-03-01"));
+    assert_eq!(charts.query_param("dateTo"), Some("2024-03-02"));
+    assert_eq!(
+        transport.request(2).query_param("from_time"),
+        Some("2024-03-01T00:00:00.000")
+    );
+    assert_eq!(transport.request(3).query_param("date"), Some("01.03.2024"));
+
+    assert_eq!(
+        events.events[0].date,
+        Some(day.and_hms_milli_opt(9, 15, 2, 417).unwrap())
+    );
+}
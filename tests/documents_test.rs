@@ -1,11 +1,17 @@
+// Тесты вызывают и прежние методы с позиционными параметрами
+#![allow(deprecated)]
+// Даты в DTO — строки или (с фичей `chrono`) Copy-типы, clone нужен для обоих вариантов
+#![allow(clippy::clone_on_copy)]
+
 mod common;
 use chrono::Datelike;
 use common::{cleanup_after_test, get_test_client};
+use iiko_server_api_sdk::money::Amount;
 use iiko_server_api_sdk::{
-    DocumentStatus, IncomingInventoryDto, IncomingInventoryItemDto, IncomingInventoryItems,
-    IncomingInvoiceDto, IncomingInvoiceItemDto, IncomingInvoiceItems, InventoryItemStatus,
-    OutgoingInvoiceDto, OutgoingInvoiceItemDto, OutgoingInvoiceItems, ReturnedInvoiceDto,
-    ReturnedInvoiceItemDto, ReturnedInvoiceItems,
+    DocumentStatus, IikoDate, IikoDateTime, IncomingInventoryDto, IncomingInventoryItemDto,
+    IncomingInventoryItems, IncomingInvoiceDto, IncomingInvoiceItemDto, IncomingInvoiceItems,
+    InventoryItemStatus, OutgoingInvoiceDto, OutgoingInvoiceItemDto, OutgoingInvoiceItems,
+    ReturnedInvoiceDto, ReturnedInvoiceItemDto, ReturnedInvoiceItems, StoreCode, StoreId,
 };
 
 #[tokio::test]
@@ -43,22 +49,23 @@ async fn test_import_incoming_invoice() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id;
+    let store_id = StoreId::from(store.id);
 
-    // Формируем текущую дату в формате dd.MM.yyyy
     let now = chrono::Local::now();
-    let date_incoming = now.format("%d.%m.%Y").to_string();
-    let due_date = (now + chrono::Duration::days(10))
-        .format("%d.%m.%Y")
-        .to_string();
-    let incoming_date = now.format("%Y-%m-%d").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
+    let due_date: IikoDate = (now + chrono::Duration::days(10))
+        .format("%Y-%m-%d")
+        .to_string()
+        .parse()
+        .unwrap();
+    let incoming_date: IikoDate = now.format("%Y-%m-%d").to_string().parse().unwrap();
 
     // Создаем тестовую приходную накладную
     let invoice = IncomingInvoiceDto {
         items: Some(IncomingInvoiceItems {
             items: vec![IncomingInvoiceItemDto {
                 is_additional_expense: false,
-                amount: Some(1.0),
+                amount: Some(Amount::from(1)),
                 supplier_product: None,
                 supplier_product_article: None,
                 product: Some(product_id),
@@ -68,17 +75,17 @@ async fn test_import_incoming_invoice() {
                 container_id: None,
                 amount_unit: None,
                 actual_unit_weight: None,
-                sum: 100.0,
-                discount_sum: Some(0.0),
-                vat_percent: Some(0.0),
-                vat_sum: Some(0.0),
+                sum: Amount::from(100),
+                discount_sum: Some(Amount::from(0)),
+                vat_percent: Some(Amount::from(0)),
+                vat_sum: Some(Amount::from(0)),
                 price_unit: None,
-                price: Some(100.0),
+                price: Some(Amount::from(100)),
                 price_without_vat: None,
                 code: None,
                 store: Some(store_id),
                 customs_declaration_number: None,
-                actual_amount: Some(1.0),
+                actual_amount: Some(Amount::from(1)),
             }],
         }),
         id: None,
@@ -164,10 +171,10 @@ async fn test_import_incoming_invoice_minimal() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id;
+    let store_id = StoreId::from(store.id);
 
     let now = chrono::Local::now();
-    let date_incoming = now.format("%d.%m.%Y").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
 
     // Минимальная накладная с обязательными полями
     // date_incoming - обязательное поле, иначе сервер вернет ошибку парсинга даты
@@ -175,7 +182,7 @@ async fn test_import_incoming_invoice_minimal() {
         items: Some(IncomingInvoiceItems {
             items: vec![IncomingInvoiceItemDto {
                 is_additional_expense: false,
-                amount: Some(1.0),
+                amount: Some(Amount::from(1)),
                 supplier_product: None,
                 supplier_product_article: None,
                 product: Some(product_id),
@@ -185,12 +192,12 @@ async fn test_import_incoming_invoice_minimal() {
                 container_id: None,
                 amount_unit: None,
                 actual_unit_weight: None,
-                sum: 100.0, // Обязательное поле
+                sum: Amount::from(100), // Обязательное поле
                 discount_sum: None,
                 vat_percent: None,
                 vat_sum: None,
                 price_unit: None,
-                price: Some(100.0),
+                price: Some(Amount::from(100)),
                 price_without_vat: None,
                 code: None,
                 store: Some(store_id),
@@ -260,12 +267,12 @@ async fn test_import_outgoing_invoice() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id.to_string();
-    let store_code = store.code.as_ref().map(|c| c.clone());
+    let store_id = StoreId::from(store.id);
+    let store_code = store.code.as_ref().map(StoreCode::from);
 
     // Формируем текущую дату в формате yyyy-MM-ddTHH:mm:ss
     let now = chrono::Local::now();
-    let date_incoming = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
 
     // Создаем тестовую расходную накладную
     let invoice = OutgoingInvoiceDto {
@@ -276,7 +283,7 @@ async fn test_import_outgoing_invoice() {
         status: Some(DocumentStatus::New),
         account_to_code: Some("5.01".to_string()),
         revenue_account_code: Some("4.01".to_string()),
-        default_store_id: Some(store_id.clone()),
+        default_store_id: Some(store_id),
         default_store_code: store_code.clone(),
         counteragent_id: None,
         counteragent_code: None,
@@ -286,9 +293,9 @@ async fn test_import_outgoing_invoice() {
         linked_outgoing_invoice_id: None,
         items: Some(OutgoingInvoiceItems {
             items: vec![OutgoingInvoiceItemDto {
-                product_id: Some(product_id.to_string()),
+                product_id: Some(product_id),
                 product_article: product_article.clone(),
-                store_id: Some(store_id.clone()),
+                store_id: Some(store_id),
                 store_code: store_code.clone(),
                 container_id: None,
                 container_code: None,
@@ -354,11 +361,11 @@ async fn test_import_outgoing_invoice_minimal() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id.to_string();
-    let store_code = store.code.as_ref().map(|c| c.clone());
+    let store_id = StoreId::from(store.id);
+    let store_code = store.code.as_ref().map(StoreCode::from);
 
     let now = chrono::Local::now();
-    let date_incoming = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
 
     // Минимальная расходная накладная с обязательными полями
     let invoice = OutgoingInvoiceDto {
@@ -369,7 +376,7 @@ async fn test_import_outgoing_invoice_minimal() {
         status: None,
         account_to_code: None,      // По умолчанию "5.01"
         revenue_account_code: None, // По умолчанию "4.01"
-        default_store_id: Some(store_id.clone()),
+        default_store_id: Some(store_id),
         default_store_code: store_code.clone(),
         counteragent_id: None,
         counteragent_code: None,
@@ -379,9 +386,9 @@ async fn test_import_outgoing_invoice_minimal() {
         linked_outgoing_invoice_id: None,
         items: Some(OutgoingInvoiceItems {
             items: vec![OutgoingInvoiceItemDto {
-                product_id: Some(product_id.to_string()),
+                product_id: Some(product_id),
                 product_article: product_article.clone(),
-                store_id: Some(store_id.clone()),
+                store_id: Some(store_id),
                 store_code: store_code.clone(),
                 container_id: None,
                 container_code: None,
@@ -446,10 +453,10 @@ async fn test_unprocess_incoming_invoice() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id;
+    let store_id = StoreId::from(store.id);
 
     let now = chrono::Local::now();
-    let date_incoming = now.format("%d.%m.%Y").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
     let document_number = format!("UNPROC-IN-{}", now.timestamp());
 
     // Создаем приходную накладную для распроведения
@@ -457,7 +464,7 @@ async fn test_unprocess_incoming_invoice() {
         items: Some(IncomingInvoiceItems {
             items: vec![IncomingInvoiceItemDto {
                 is_additional_expense: false,
-                amount: Some(1.0),
+                amount: Some(Amount::from(1)),
                 supplier_product: None,
                 supplier_product_article: None,
                 product: Some(product_id),
@@ -467,17 +474,17 @@ async fn test_unprocess_incoming_invoice() {
                 container_id: None,
                 amount_unit: None,
                 actual_unit_weight: None,
-                sum: 100.0,
-                discount_sum: Some(0.0),
-                vat_percent: Some(0.0),
-                vat_sum: Some(0.0),
+                sum: Amount::from(100),
+                discount_sum: Some(Amount::from(0)),
+                vat_percent: Some(Amount::from(0)),
+                vat_sum: Some(Amount::from(0)),
                 price_unit: None,
-                price: Some(100.0),
+                price: Some(Amount::from(100)),
                 price_without_vat: None,
                 code: None,
                 store: Some(store_id),
                 customs_declaration_number: None,
-                actual_amount: Some(1.0),
+                actual_amount: Some(Amount::from(1)),
             }],
         }),
         id: None,
@@ -601,11 +608,11 @@ async fn test_unprocess_outgoing_invoice() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id.to_string();
-    let store_code = store.code.as_ref().map(|c| c.clone());
+    let store_id = StoreId::from(store.id);
+    let store_code = store.code.as_ref().map(StoreCode::from);
 
     let now = chrono::Local::now();
-    let date_incoming = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
     let document_number = format!("UNPROC-OUT-{}", now.timestamp());
 
     // Создаем расходную накладную для распроведения
@@ -617,7 +624,7 @@ async fn test_unprocess_outgoing_invoice() {
         status: Some(DocumentStatus::New),
         account_to_code: Some("5.01".to_string()),
         revenue_account_code: Some("4.01".to_string()),
-        default_store_id: Some(store_id.clone()),
+        default_store_id: Some(store_id),
         default_store_code: store_code.clone(),
         counteragent_id: None,
         counteragent_code: None,
@@ -627,9 +634,9 @@ async fn test_unprocess_outgoing_invoice() {
         linked_outgoing_invoice_id: None,
         items: Some(OutgoingInvoiceItems {
             items: vec![OutgoingInvoiceItemDto {
-                product_id: Some(product_id.to_string()),
+                product_id: Some(product_id),
                 product_article: product_article.clone(),
-                store_id: Some(store_id.clone()),
+                store_id: Some(store_id),
                 store_code: store_code.clone(),
                 container_id: None,
                 container_code: None,
@@ -686,7 +693,7 @@ async fn test_unprocess_outgoing_invoice() {
         use_default_document_time: false,
         account_to_code: None,
         revenue_account_code: None,
-        default_store_id: Some(store_id.clone()),
+        default_store_id: Some(store_id),
         default_store_code: store_code.clone(),
         counteragent_id: None,
         counteragent_code: None,
@@ -774,7 +781,7 @@ async fn test_export_outgoing_invoice_with_supplier() {
     }
 
     let supplier = suppliers.first().unwrap();
-    let supplier_id = supplier.id.to_string();
+    let supplier_id = supplier.id;
 
     // Получаем текущую дату и дату неделю назад
     let now = chrono::Local::now();
@@ -786,11 +793,7 @@ async fn test_export_outgoing_invoice_with_supplier() {
     // Экспортируем расходные накладные с фильтром по поставщику
     let invoices = client
         .documents()
-        .export_outgoing_invoice(
-            date_from.clone(),
-            date_to.clone(),
-            Some(supplier_id.clone()),
-        )
+        .export_outgoing_invoice(date_from.clone(), date_to.clone(), Some(supplier_id))
         .await
         .expect("Failed to export outgoing invoices with supplier filter");
 
@@ -852,8 +855,8 @@ async fn test_export_outgoing_invoice_by_number() {
         .export_outgoing_invoice_by_number(
             test_number.clone(),
             false,
-            Some(date_from.clone()),
-            Some(date_to.clone()),
+            Some(date_from.clone().into()),
+            Some(date_to.clone().into()),
         )
         .await
         .expect("Failed to export outgoing invoice by number");
@@ -972,19 +975,19 @@ async fn test_import_returned_invoice() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id;
+    let store_id = StoreId::from(store.id);
 
     let now = chrono::Local::now();
-    let date_incoming = now.format("%d.%m.%Y").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
     let incoming_invoice_number = format!("INV-RET-{}", now.timestamp());
-    let incoming_invoice_date = now.format("%Y-%m-%d").to_string();
+    let incoming_invoice_date: IikoDate = now.format("%Y-%m-%d").to_string().parse().unwrap();
 
     // Создаем приходную накладную
     let incoming_invoice = IncomingInvoiceDto {
         items: Some(IncomingInvoiceItems {
             items: vec![IncomingInvoiceItemDto {
                 is_additional_expense: false,
-                amount: Some(10.0),
+                amount: Some(Amount::from(10)),
                 supplier_product: None,
                 supplier_product_article: None,
                 product: Some(product_id),
@@ -994,17 +997,17 @@ async fn test_import_returned_invoice() {
                 container_id: None,
                 amount_unit: None,
                 actual_unit_weight: None,
-                sum: 1000.0,
-                discount_sum: Some(0.0),
-                vat_percent: Some(12.0),
-                vat_sum: Some(107.14),
+                sum: Amount::from(1000),
+                discount_sum: Some(Amount::from(0)),
+                vat_percent: Some(Amount::from(12)),
+                vat_sum: Some("107.14".parse::<Amount>().unwrap()),
                 price_unit: None,
-                price: Some(100.0),
+                price: Some(Amount::from(100)),
                 price_without_vat: None,
                 code: None,
                 store: Some(store_id),
                 customs_declaration_number: None,
-                actual_amount: Some(10.0),
+                actual_amount: Some(Amount::from(10)),
             }],
         }),
         id: None,
@@ -1058,18 +1061,19 @@ async fn test_import_returned_invoice() {
     tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
 
     // Создаем возвратную накладную
-    let returned_invoice_date = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let returned_invoice_date: IikoDateTime =
+        now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
     let returned_invoice = ReturnedInvoiceDto {
         document_number: Some(format!("RET-{}", now.timestamp())),
         date_incoming: Some(returned_invoice_date.clone()),
         use_default_document_time: false,
         status: Some(DocumentStatus::New),
         incoming_invoice_number: final_incoming_number.clone(),
-        incoming_invoice_date: incoming_invoice_date.clone(),
+        incoming_invoice_date: date_incoming.clone(),
         store_cost_affected: false,
         account_to_code: Some("5.01".to_string()),
-        default_store_id: Some(store_id.to_string()),
-        default_store_code: store.code.as_ref().map(|c| c.clone()),
+        default_store_id: Some(store_id),
+        default_store_code: store.code.as_ref().map(StoreCode::from),
         counteragent_id: Some(supplier_id.to_string()),
         counteragent_code: None,
         conception_id: None,
@@ -1077,13 +1081,13 @@ async fn test_import_returned_invoice() {
         comment: Some("Test returned invoice".to_string()),
         items: Some(ReturnedInvoiceItems {
             items: vec![ReturnedInvoiceItemDto {
-                product_id: Some(product_id.to_string()),
+                product_id: Some(product_id),
                 product_article: product.num.as_ref().map(|a| a.clone()),
                 supplier_product: None,
                 supplier_product_article: None,
                 customs_declaration_number: None,
-                store_id: Some(store_id.to_string()),
-                store_code: store.code.as_ref().map(|c| c.clone()),
+                store_id: Some(store_id),
+                store_code: store.code.as_ref().map(StoreCode::from),
                 container_id: None,
                 container_code: None,
                 price: 100.0,
@@ -1143,11 +1147,11 @@ async fn test_import_incoming_inventory() {
 
     assert!(!stores.is_empty(), "No stores found");
     let store = stores.first().unwrap();
-    let store_id = store.id.to_string();
-    let store_code = store.code.as_ref().map(|c| c.clone());
+    let store_id = StoreId::from(store.id);
+    let store_code = store.code.as_ref().map(StoreCode::from);
 
     let now = chrono::Local::now();
-    let date_incoming = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let date_incoming: IikoDateTime = now.format("%Y-%m-%dT%H:%M:%S").to_string().parse().unwrap();
 
     // Создаем инвентаризацию
     let inventory = IncomingInventoryDto {
@@ -1157,7 +1161,7 @@ async fn test_import_incoming_inventory() {
         status: Some(DocumentStatus::New),
         account_surplus_code: Some("5.10".to_string()),
         account_shortage_code: Some("5.09".to_string()),
-        store_id: Some(store_id.clone()),
+        store_id: Some(store_id),
         store_code: store_code.clone(),
         conception_id: None,
         conception_code: None,
@@ -1166,7 +1170,7 @@ async fn test_import_incoming_inventory() {
             items: vec![IncomingInventoryItemDto {
                 status: Some(InventoryItemStatus::New),
                 recalculation_number: None,
-                product_id: Some(product_id.to_string()),
+                product_id: Some(product_id),
                 product_article: product.num.as_ref().map(|a| a.clone()),
                 container_id: None,
                 container_code: None,
@@ -1207,7 +1211,7 @@ async fn test_import_incoming_inventory() {
         );
     }
     if let Some(date) = &result.date {
-        println!("Date: {}", date);
+        println!("Date: {:?}", date);
     }
     if let Some(items) = &result.items {
         println!("Items count: {}", items.items.len());
@@ -16,12 +16,12 @@ async fn test_get_events() {
             }
             for event in events_list.events.iter().take(3) {
                 println!(
-                    "Event: {} at {} (type: {:?})",
+                    "Event: {} at {:?} (type: {:?})",
                     event
                         .id
                         .map(|id| id.to_string())
                         .unwrap_or_else(|| "N/A".to_string()),
-                    event.date.as_deref().unwrap_or("N/A"),
+                    event.date,
                     event.r#type
                 );
             }
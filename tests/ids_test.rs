@@ -0,0 +1,98 @@
+mod common;
+
+use common::fake::{FakeTransport, client, server_info};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{
+    ProductCategoryId, ProductGroupId, ProductId, ProductQuery, StoreBalanceQuery, StoreId,
+};
+
+#[tokio::test]
+async fn typed_ids_keep_wire_format() {
+    let store: StoreId = "1239d270-1bbe-f64f-b7ea-5f00518ef508".parse().unwrap();
+    let product: ProductId = "4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f".parse().unwrap();
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, &server_info("7.9.3007.0"))
+        .respond(StatusCode::OK, "session-1")
+        .respond(
+            StatusCode::OK,
+            r#"[{"store":"1239d270-1bbe-f64f-b7ea-5f00518ef508",
+             "product":"4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f","amount":3,"sum":300}]"#,
+        );
+    let client = client(&transport);
+
+    let balances = client
+        .reports()
+        .balance_stores(
+            StoreBalanceQuery::at("2024-03-01T00:00:00")
+                .stores([store])
+                .products([product]),
+        )
+        .await
+        .unwrap();
+
+    let request = transport.request(2);
+    assert_eq!(
+        request.query_param("store"),
+        Some("1239d270-1bbe-f64f-b7ea-5f00518ef508")
+    );
+    assert_eq!(
+        request.query_param("product"),
+        Some("4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f")
+    );
+    assert_eq!(balances[0].store, store);
+    assert_eq!(balances[0].product, product);
+    assert_eq!(
+        serde_json::to_value(&balances[0]).unwrap()["store"],
+        "1239d270-1bbe-f64f-b7ea-5f00518ef508"
+    );
+}
+
+#[tokio::test]
+async fn product_categories_and_groups_use_typed_ids() {
+    let category: ProductCategoryId = "5b1e6c4a-2f3d-4e8a-9c7b-1d2e3f4a5b6c".parse().unwrap();
+    let group: ProductGroupId = "7c2f8d5b-3a4e-4f9b-8d1c-2e3f4a5b6c7d".parse().unwrap();
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(
+            StatusCode::OK,
+            r#"[{"id":"4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f","deleted":false,
+             "category":"5b1e6c4a-2f3d-4e8a-9c7b-1d2e3f4a5b6c",
+             "parent":"7c2f8d5b-3a4e-4f9b-8d1c-2e3f4a5b6c7d"}]"#,
+        )
+        .respond(
+            StatusCode::OK,
+            r#"{"result":"SUCCESS","errors":null,"response":{"id":"5b1e6c4a-2f3d-4e8a-9c7b-1d2e3f4a5b6c",
+             "rootType":"ProductCategory","deleted":true,"code":null,"name":"Десерты"}}"#,
+        );
+    let client = client(&transport);
+
+    let products = client
+        .products()
+        .find(
+            ProductQuery::new()
+                .category_ids([category])
+                .parent_ids([group]),
+        )
+        .await
+        .unwrap();
+    let deleted = client.products().delete_category(category).await.unwrap();
+
+    let request = transport.request(1);
+    assert_eq!(
+        request.query_param("categoryIds"),
+        Some("5b1e6c4a-2f3d-4e8a-9c7b-1d2e3f4a5b6c")
+    );
+    assert_eq!(
+        request.query_param("parentIds"),
+        Some("7c2f8d5b-3a4e-4f9b-8d1c-2e3f4a5b6c7d")
+    );
+    assert_eq!(products[0].category, Some(category));
+    assert_eq!(products[0].parent, Some(group));
+    assert_eq!(
+        transport.request(2).body_text(),
+        Some(r#"{"id":"5b1e6c4a-2f3d-4e8a-9c7b-1d2e3f4a5b6c"}"#)
+    );
+    assert_eq!(deleted.response.id, category);
+}
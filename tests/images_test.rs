@@ -1,5 +1,6 @@
 mod common;
 use common::{cleanup_after_test, get_test_client};
+use iiko_server_api_sdk::ProductQuery;
 
 #[tokio::test]
 async fn test_load_image() {
@@ -8,7 +9,7 @@ async fn test_load_image() {
     // Получаем список продуктов, чтобы найти изображение
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
@@ -62,7 +63,7 @@ async fn test_delete_image() {
     // Получаем список продуктов, чтобы найти изображение
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
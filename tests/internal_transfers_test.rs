@@ -2,6 +2,7 @@ mod common;
 
 use chrono::{Duration, Local};
 use common::{cleanup_after_test, get_test_client};
+use iiko_server_api_sdk::InternalTransfersQuery;
 
 #[tokio::test]
 #[ignore = "read-only live smoke; requires IIKO_* credentials for a safe test integration"]
@@ -14,7 +15,7 @@ async fn qitech_internal_transfer_list_read_only() {
 
     let result = client
         .documents()
-        .list_internal_transfers(date_from, date_to, None, Some(-1))
+        .internal_transfers(InternalTransfersQuery::between(date_from, date_to).revision_from(-1))
         .await
         .expect("Failed to list internal transfers");
 
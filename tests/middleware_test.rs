@@ -0,0 +1,100 @@
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::transport::{StatusCode, async_trait};
+use iiko_server_api_sdk::{
+    ApiRequest, IikoError, Middleware, Next, StreamingResponse, TransportResponse,
+};
+use std::sync::{Arc, Mutex};
+
+/// Добавляет заголовок, записывает запросы и повторяет 400 один раз
+#[derive(Default)]
+struct Journal {
+    calls: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl Middleware for Journal {
+    async fn handle(
+        &self,
+        mut request: ApiRequest,
+        next: Next<'_>,
+    ) -> iiko_server_api_sdk::Result<StreamingResponse> {
+        if request.endpoint == "cached" {
+            return Ok(TransportResponse::new(StatusCode::OK, "from cache").into());
+        }
+
+        let body = request.body.text()?.unwrap_or_default().into_owned();
+        request
+            .headers
+            .push(("X-Tenant".to_string(), "cafe-17".to_string()));
+
+        let result = match next.run(request.clone()).await {
+            Err(IikoError::BadRequest(_)) => next.run(request.clone()).await,
+            result => result,
+        };
+
+        self.calls.lock().unwrap().push(format!(
+            "{} {} mutating={} body={} -> {}",
+            request.method,
+            request.endpoint,
+            request.is_mutating(),
+            body,
+            match &result {
+                Ok(response) => response.status.as_u16().to_string(),
+                Err(error) => error.to_string(),
+            }
+        ));
+        result
+    }
+}
+
+#[tokio::test]
+async fn middleware_sees_requests_and_can_short_circuit() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::BAD_REQUEST, "Wrong document")
+        .respond(StatusCode::OK, "<ok/>")
+        .respond(StatusCode::OK, "<employees/>");
+
+    let journal = Arc::new(Journal::default());
+    let client = client(&transport).with_middleware(journal.clone());
+
+    assert_eq!(client.get("cached").await.unwrap(), "from cache");
+    assert!(transport.endpoints().is_empty());
+
+    let response = client
+        .post_xml("documents/import/incomingInvoice", "<document/>")
+        .await
+        .unwrap();
+    assert_eq!(response, "<ok/>");
+    client
+        .get_with_params("employees", &[("includeDeleted", "true")])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        transport.endpoints(),
+        [
+            "auth",
+            "documents/import/incomingInvoice",
+            "documents/import/incomingInvoice",
+            "employees"
+        ]
+    );
+    let request = transport.request(3);
+    assert!(
+        request
+            .headers
+            .contains(&("X-Tenant".to_string(), "cafe-17".to_string()))
+    );
+    assert_eq!(request.query_param("includeDeleted"), Some("true"));
+    assert_eq!(
+        *journal.calls.lock().unwrap(),
+        [
+            "POST documents/import/incomingInvoice mutating=true body=<document/> -> 200",
+            "GET employees mutating=false body= -> 200",
+        ]
+    );
+}
@@ -0,0 +1,279 @@
+#![cfg(feature = "mock-server")]
+
+use futures_util::TryStreamExt;
+use iiko_server_api_sdk::mock_server::MockIikoServer;
+use iiko_server_api_sdk::money::Amount;
+use iiko_server_api_sdk::{
+    FileSessionStore, IikoError, IncomingInvoiceDto, IncomingInvoiceItemDto, IncomingInvoiceItems,
+    OlapFieldValue, OlapReportRequest, OlapReportType, ProductDto, ProductQuery, ServerVersion,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn lists_fixture_products_without_deleted() {
+    let server = MockIikoServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let products = client
+        .products()
+        .find(ProductQuery::new())
+        .await
+        .expect("products should be listed");
+
+    assert_eq!(products.len(), 2);
+    assert!(products.iter().all(|p| !p.deleted));
+
+    let with_deleted = client
+        .products()
+        .find_post(ProductQuery::new().include_deleted(true))
+        .await
+        .unwrap();
+    assert_eq!(with_deleted.len(), 3);
+}
+
+#[tokio::test]
+async fn saved_product_appears_in_next_list() {
+    let server = MockIikoServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let product: ProductDto = serde_json::from_value(serde_json::json!({
+        "name": "Раф",
+        "type": "DISH",
+        "defaultSalePrice": 220.0,
+    }))
+    .unwrap();
+
+    let saved = client.products().save(product, None, None).await.unwrap();
+    assert_eq!(saved.result, "SUCCESS");
+    let id = saved.response.id.expect("saved product should get an id");
+
+    let found = client
+        .products()
+        .find(ProductQuery::new().ids([id]))
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name.as_deref(), Some("Раф"));
+
+    client.products().delete(vec![id]).await.unwrap();
+    let second_delete = client.products().delete(vec![id]).await;
+    assert!(matches!(second_delete, Err(IikoError::BusinessLogic(_))));
+}
+
+#[tokio::test]
+async fn imports_incoming_invoice() {
+    let server = MockIikoServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let invoice = IncomingInvoiceDto {
+        items: Some(IncomingInvoiceItems {
+            items: vec![IncomingInvoiceItemDto {
+                is_additional_expense: false,
+                amount: Some(Amount::from(2)),
+                supplier_product: None,
+                supplier_product_article: None,
+                product: Some(Uuid::new_v4().into()),
+                product_article: None,
+                producer: None,
+                num: 1,
+                container_id: None,
+                amount_unit: None,
+                actual_unit_weight: None,
+                sum: Amount::from(200),
+                discount_sum: None,
+                vat_percent: None,
+                vat_sum: None,
+                price_unit: None,
+                price: Some(Amount::from(100)),
+                price_without_vat: None,
+                code: None,
+                store: None,
+                customs_declaration_number: None,
+                actual_amount: None,
+            }],
+        }),
+        id: None,
+        conception: None,
+        conception_code: None,
+        comment: None,
+        document_number: Some("MOCK-1".to_string()),
+        date_incoming: None,
+        invoice: None,
+        default_store: None,
+        supplier: None,
+        due_date: None,
+        incoming_date: None,
+        use_default_document_time: false,
+        status: None,
+        incoming_document_number: None,
+        employee_pass_to_account: None,
+        transport_invoice_number: None,
+        linked_outgoing_invoice_id: None,
+        distribution_algorithm: None,
+    };
+
+    let result = client
+        .documents()
+        .import_incoming_invoice(invoice)
+        .await
+        .unwrap();
+    assert!(result.valid);
+    assert_eq!(result.document_number.as_deref(), Some("MOCK-1"));
+
+    let documents = server.imported_documents();
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].kind, "incomingInvoice");
+}
+
+#[tokio::test]
+async fn serves_xml_references_and_olap() {
+    let server = MockIikoServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let suppliers = client.suppliers().list(None).await.unwrap();
+    assert_eq!(suppliers.len(), 2);
+
+    let employee = client.employees().get_by_code("1").await.unwrap();
+    assert_eq!(employee.code, "1");
+
+    let stores = client.corporation().get_stores(None).await.unwrap();
+    assert!(!stores.is_empty());
+
+    let report = client
+        .reports()
+        .get_olap_report(OlapReportRequest {
+            report_type: OlapReportType::Sales,
+            build_summary: Some(false),
+            group_by_row_fields: vec!["DishName".to_string()],
+            group_by_col_fields: None,
+            aggregate_fields: vec!["DishSumInt".to_string()],
+            filters: None,
+        })
+        .await
+        .unwrap();
+    assert!(!report.data.is_empty());
+    assert!(report.data.iter().all(|row| row.len() == 2));
+}
+
+#[tokio::test]
+async fn expired_sessions_and_license_slots() {
+    let server = MockIikoServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    client.products().list_groups(None).await.unwrap();
+    assert_eq!(server.active_sessions(), 1);
+
+    server.expire_sessions();
+    client
+        .products()
+        .list_groups(None)
+        .await
+        .expect("client should re-authenticate after the key expires");
+
+    server.set_license_slots(1);
+    let second = server.client().unwrap();
+    assert!(matches!(
+        second.authenticate().await,
+        Err(IikoError::LicenseExhausted { .. })
+    ));
+
+    client.logout().await.unwrap();
+    assert_eq!(server.active_sessions(), 0);
+}
+
+#[tokio::test]
+async fn rejects_methods_newer_than_server() {
+    let server = MockIikoServer::start().await.unwrap();
+    server.set_server_version("6.1.0012.0");
+    let client = server.client().unwrap();
+
+    let info = client.server_info().await.unwrap();
+    assert_eq!(info.version, ServerVersion::new(6, 1, 0));
+    assert_eq!(info.server_name.as_deref(), Some("Mock iiko"));
+
+    let error = client.images().delete(Vec::new()).await.unwrap_err();
+    assert!(matches!(error, IikoError::UnsupportedVersion { .. }));
+    assert_eq!(server.active_sessions(), 0);
+
+    let unchecked = server.config().with_version_check(false);
+    let client = iiko_server_api_sdk::IikoClient::new(unchecked).unwrap();
+    // Фейковый сервер не знает `v2/images/delete`, но запрос до него дошел
+    assert!(matches!(
+        client.images().delete(Vec::new()).await,
+        Err(IikoError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn restarted_client_reuses_stored_session_key() {
+    let server = MockIikoServer::start().await.unwrap();
+    let path = std::env::temp_dir().join(format!("iiko-mock-session-{}.json", Uuid::new_v4()));
+
+    let first = server
+        .client()
+        .unwrap()
+        .with_session_store(FileSessionStore::new(&path));
+    first.products().list_groups(None).await.unwrap();
+    drop(first);
+
+    // Перезапуск воркера: новый клиент берет ключ из файла и не занимает второй слот
+    let second = server
+        .client()
+        .unwrap()
+        .with_session_store(FileSessionStore::new(&path));
+    second.products().list_groups(None).await.unwrap();
+    assert_eq!(server.active_sessions(), 1);
+
+    // Сервер перезагружен: сохраненный ключ отклонен, клиент авторизуется заново
+    server.expire_sessions();
+    let third = server
+        .client()
+        .unwrap()
+        .with_session_store(FileSessionStore::new(&path));
+    third.products().list_groups(None).await.unwrap();
+    assert_eq!(server.active_sessions(), 1);
+
+    third.logout().await.unwrap();
+    assert_eq!(server.active_sessions(), 0);
+    let stored: HashMap<String, String> =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(stored.is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn streams_large_olap_report_row_by_row() {
+    let server = MockIikoServer::start().await.unwrap();
+    let rows = (0..20_000)
+        .map(|n| {
+            HashMap::from([
+                (
+                    "DishName".to_string(),
+                    OlapFieldValue::String(format!("Блюдо {{{n}}}")),
+                ),
+                ("DishSumInt".to_string(), OlapFieldValue::Integer(n)),
+            ])
+        })
+        .collect();
+    server.set_olap_rows(rows);
+    let client = server.client().unwrap();
+
+    let request = OlapReportRequest {
+        report_type: OlapReportType::Sales,
+        build_summary: Some(false),
+        group_by_row_fields: vec!["DishName".to_string()],
+        group_by_col_fields: None,
+        aggregate_fields: vec!["DishSumInt".to_string()],
+        filters: None,
+    };
+    let mut stream = client.reports().stream_olap_report(request).await.unwrap();
+
+    let mut count = 0;
+    while let Some(row) = stream.try_next().await.unwrap() {
+        assert!(matches!(row["DishSumInt"], OlapFieldValue::Integer(n) if n == count));
+        count += 1;
+    }
+    assert_eq!(count, 20_000);
+    assert!(!client.queue_stats().busy);
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "mock-server")]
+
+use iiko_server_api_sdk::mock_server::MockIikoServer;
+use iiko_server_api_sdk::{IikoClientPool, IikoConfig, IikoError, RetryPolicy};
+use std::time::Duration;
+
+#[tokio::test]
+async fn creates_clients_lazily_and_caps_license_slots() {
+    let msk = MockIikoServer::start().await.unwrap();
+    let spb = MockIikoServer::start().await.unwrap();
+
+    let pool = IikoClientPool::new(1);
+    pool.register("msk", msk.config());
+    pool.register("spb", spb.config());
+    assert_eq!(pool.active_sessions(), 0);
+
+    {
+        let client = pool.get("msk").await.unwrap();
+        client.products().list_groups(None).await.unwrap();
+    }
+    assert_eq!(msk.active_sessions(), 1);
+
+    let client = pool.get("spb").await.unwrap();
+    client.products().list_groups(None).await.unwrap();
+
+    assert_eq!(
+        msk.active_sessions(),
+        0,
+        "idle msk session should be evicted"
+    );
+    assert_eq!(spb.active_sessions(), 1);
+    assert_eq!(pool.active_sessions(), 1);
+    assert!(pool.health("spb").unwrap().session_active);
+    assert!(!pool.health("msk").unwrap().session_active);
+}
+
+#[tokio::test]
+async fn logs_out_idle_sessions() {
+    let server = MockIikoServer::start().await.unwrap();
+
+    let pool = IikoClientPool::new(2).with_idle_timeout(Duration::ZERO);
+    pool.register("msk", server.config());
+
+    let client = pool.get("msk").await.unwrap();
+    client.products().list_groups(None).await.unwrap();
+    assert_eq!(pool.logout_idle().await, 0, "client in use is not idle");
+
+    drop(client);
+    assert_eq!(pool.logout_idle().await, 1);
+    assert_eq!(server.active_sessions(), 0);
+    assert_eq!(pool.active_sessions(), 0);
+}
+
+#[tokio::test]
+async fn reports_per_server_health() {
+    let server = MockIikoServer::start().await.unwrap();
+
+    let pool = IikoClientPool::new(2);
+    pool.register("msk", server.config());
+    pool.register(
+        "down",
+        IikoConfig::new("http://127.0.0.1:1/resto/api", "admin", "hash")
+            .with_retry_policy(RetryPolicy::none()),
+    );
+
+    pool.get("msk")
+        .await
+        .unwrap()
+        .products()
+        .list_groups(None)
+        .await
+        .unwrap();
+    assert!(
+        pool.get("down")
+            .await
+            .unwrap()
+            .authenticate()
+            .await
+            .is_err()
+    );
+
+    let health = pool.health_all();
+    assert!(health["msk"].is_healthy());
+    assert_eq!(health["msk"].requests, 2);
+    assert!(!health["down"].is_healthy());
+    assert_eq!(health["down"].consecutive_failures, 1);
+
+    assert!(matches!(
+        pool.get("unknown").await,
+        Err(IikoError::Configuration(_))
+    ));
+}
+
+#[tokio::test]
+async fn logs_out_evicted_session_before_handing_over_its_slot() {
+    let msk = MockIikoServer::start().await.unwrap();
+    let spb = MockIikoServer::start().await.unwrap();
+
+    let pool = IikoClientPool::new(1);
+    pool.register("msk", msk.config());
+    pool.register("spb", spb.config());
+    pool.get("msk")
+        .await
+        .unwrap()
+        .products()
+        .list_groups(None)
+        .await
+        .unwrap();
+
+    let _spb = pool.get("spb").await.unwrap();
+
+    assert_eq!(msk.active_sessions(), 0);
+    assert_eq!(pool.active_sessions(), 1);
+}
+
+#[tokio::test]
+async fn register_keeps_leased_session_until_client_is_returned() {
+    let server = MockIikoServer::start().await.unwrap();
+
+    let pool = IikoClientPool::new(2);
+    pool.register("msk", server.config());
+    let client = pool.get("msk").await.unwrap();
+    client.products().list_groups(None).await.unwrap();
+
+    pool.register("msk", server.config());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        server.active_sessions(),
+        1,
+        "leased session is not logged out"
+    );
+    client.products().list_groups(None).await.unwrap();
+    assert_eq!(pool.active_sessions(), 1);
+
+    drop(client);
+    for _ in 0..100 {
+        if server.active_sessions() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(server.active_sessions(), 0);
+    assert_eq!(pool.active_sessions(), 0);
+}
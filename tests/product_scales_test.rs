@@ -1,6 +1,6 @@
 mod common;
 use common::{cleanup_after_test, get_test_client};
-use uuid::Uuid;
+use iiko_server_api_sdk::{ProductId, ProductQuery};
 
 #[tokio::test]
 async fn test_list_product_scales() {
@@ -82,7 +82,7 @@ async fn test_get_product_scale_for_product() {
     // Получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
@@ -115,12 +115,12 @@ async fn test_get_product_scales_for_products() {
     // Получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
     if products.len() >= 2 {
-        let product_ids: Vec<Uuid> = products.iter().take(3).filter_map(|p| p.id).collect();
+        let product_ids: Vec<ProductId> = products.iter().take(3).filter_map(|p| p.id).collect();
 
         let scales_map = client
             .product_scales()
@@ -158,12 +158,12 @@ async fn test_get_product_scales_for_products_post() {
     // Получаем список продуктов
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
     if products.len() >= 2 {
-        let product_ids: Vec<Uuid> = products.iter().take(3).filter_map(|p| p.id).collect();
+        let product_ids: Vec<ProductId> = products.iter().take(3).filter_map(|p| p.id).collect();
 
         let scales_map = client
             .product_scales()
@@ -1,5 +1,6 @@
 mod common;
 use common::{cleanup_after_test, get_test_client};
+use iiko_server_api_sdk::ProductQuery;
 
 #[tokio::test]
 async fn test_list_products() {
@@ -7,7 +8,7 @@ async fn test_list_products() {
 
     let products = client
         .products()
-        .list(Some(false), None, None, None, None, None)
+        .find(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products");
 
@@ -33,7 +34,7 @@ async fn test_list_products_post() {
 
     let products = client
         .products()
-        .list_post(Some(false), None, None, None, None, None, None, None)
+        .find_post(ProductQuery::new().include_deleted(false))
         .await
         .expect("Failed to get products via POST");
 
@@ -0,0 +1,105 @@
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{EmployeeSearch, StoreId, StoreOperationsQuery};
+
+#[tokio::test]
+async fn request_objects_build_same_query_as_positional_shims() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<employees/>")
+        .respond(StatusCode::OK, "<employees/>")
+        .respond(StatusCode::OK, "<storeReportItemDtoes/>")
+        .respond(StatusCode::OK, "<storeReportItemDtoes/>");
+    let client = client(&transport);
+
+    client
+        .employees()
+        .find(EmployeeSearch::new().last_name("Иванов").supplier(true))
+        .await
+        .unwrap();
+    #[allow(deprecated)]
+    client
+        .employees()
+        .search(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("Иванов"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let stores = [
+        "1239d270-1bbe-f64f-b7ea-5f00518ef508",
+        "5f9c8a6e-3b1d-4e2f-9a7c-8d6e5f4a3b2c",
+    ];
+    client
+        .reports()
+        .store_operations(
+            StoreOperationsQuery::between("01.03.2024", "31.03.2024")
+                .stores(stores.map(|id| id.parse::<StoreId>().unwrap()))
+                .product_detalization(false),
+        )
+        .await
+        .unwrap();
+    #[allow(deprecated)]
+    client
+        .reports()
+        .get_store_operations(
+            "01.03.2024",
+            "31.03.2024",
+            Some(&stores),
+            None,
+            Some(false),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let search = transport.request(1);
+    assert_eq!(
+        search.query,
+        [
+            ("key", "session-1"),
+            ("lastName", "Иванов"),
+            ("supplier", "true"),
+        ]
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+    );
+    assert_eq!(transport.request(2).query, search.query);
+
+    let operations = transport.request(3);
+    let stores: Vec<_> = operations
+        .query
+        .iter()
+        .filter(|(key, _)| key == "stores")
+        .map(|(_, value)| value.as_str())
+        .collect();
+    assert_eq!(
+        stores,
+        [
+            "1239d270-1bbe-f64f-b7ea-5f00518ef508",
+            "5f9c8a6e-3b1d-4e2f-9a7c-8d6e5f4a3b2c",
+        ]
+    );
+    assert_eq!(operations.query_param("productDetalization"), Some("false"));
+    assert_eq!(operations.query_param("showCostCorrections"), None);
+    assert_eq!(transport.request(4).query, operations.query);
+}
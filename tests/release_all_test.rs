@@ -0,0 +1,38 @@
+//! `session::release_all` работает со списком ключей всего процесса, поэтому тест
+//! живет в отдельном файле: параллельные тесты сессий не делят с ним этот список.
+
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::session;
+use iiko_server_api_sdk::transport::StatusCode;
+
+#[tokio::test]
+async fn release_all_logs_out_every_held_key() {
+    let first = FakeTransport::default();
+    first
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "")
+        .respond(StatusCode::OK, "");
+    let second = FakeTransport::default();
+    second
+        .respond(StatusCode::OK, "session-2")
+        .respond(StatusCode::OK, "")
+        .respond(StatusCode::INTERNAL_SERVER_ERROR, "");
+
+    let first_client = client(&first);
+    let second_client = client(&second);
+    first_client.get("replication/serverType").await.unwrap();
+    second_client.get("replication/serverType").await.unwrap();
+    assert_eq!(session::held_count(), 2);
+
+    assert_eq!(session::release_all().await, 1);
+    assert_eq!(session::held_count(), 0);
+    assert_eq!(first.endpoints().last().unwrap(), "logout");
+    assert_eq!(first.request(2).body_text(), Some("key=session-1"));
+    assert_eq!(second.request(2).body_text(), Some("key=session-2"));
+
+    // Ключи уже освобождены: повторный вызов ничего не отправляет
+    assert_eq!(session::release_all().await, 0);
+    assert_eq!(first.endpoints().len(), 3);
+}
@@ -1,3 +1,6 @@
+// Тесты вызывают и прежние методы с позиционными параметрами
+#![allow(deprecated)]
+
 mod common;
 use common::{cleanup_after_test, get_test_client};
 
@@ -1377,7 +1380,9 @@ async fn test_get_monthly_income_plan() {
     // Нужен реальный department ID для теста
     let result = reports
         .get_monthly_income_plan(
-            "49023e1b-6e3a-6c33-0133-cce1f6f5000b",
+            "49023e1b-6e3a-6c33-0133-cce1f6f5000b"
+                .parse::<iiko_server_api_sdk::DepartmentId>()
+                .unwrap(),
             "01.12.2014",
             "18.12.2014",
         )
@@ -0,0 +1,85 @@
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::Priority;
+use iiko_server_api_sdk::transport::StatusCode;
+use std::time::Duration;
+
+#[tokio::test]
+async fn cancelled_call_keeps_session_and_releases_queue() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<ok/>");
+    transport.delay(Some(Duration::from_millis(50)));
+
+    let client = client(&transport);
+    let cancelled = tokio::time::timeout(
+        Duration::from_millis(5),
+        client.get("replication/serverType"),
+    )
+    .await;
+    assert!(cancelled.is_err(), "call should be cancelled during /auth");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    transport.delay(None);
+
+    let body = tokio::time::timeout(Duration::from_secs(1), client.get("replication/serverType"))
+        .await
+        .expect("queue should not stay locked after cancellation")
+        .unwrap();
+
+    assert_eq!(body, "<ok/>");
+    assert_eq!(transport.endpoints(), ["auth", "replication/serverType"]);
+    assert_eq!(transport.request(1).query_param("key"), Some("session-1"));
+}
+
+#[tokio::test]
+async fn interactive_request_overtakes_queued_bulk_requests() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<report/>")
+        .respond(StatusCode::OK, "<employee/>")
+        .respond(StatusCode::OK, "<report/>");
+    transport.delay(Some(Duration::from_millis(20)));
+
+    let client = client(&transport);
+    let bulk = client.with_priority(Priority::Bulk);
+    let pos = client.with_priority(Priority::Interactive);
+
+    let running = tokio::spawn({
+        let bulk = bulk.clone();
+        async move { bulk.get("v2/reports/olap").await }
+    });
+    while !client.queue_stats().busy {
+        tokio::task::yield_now().await;
+    }
+    let queued_bulk = tokio::spawn(async move { bulk.get("v2/reports/olap").await });
+    let lookup = tokio::spawn(async move { pos.get("employees/byCode/42").await });
+    while client.queue_stats().queued() < 2 {
+        tokio::task::yield_now().await;
+    }
+
+    let stats = client.queue_stats();
+    assert_eq!(stats.bulk.queued, 1);
+    assert_eq!(stats.interactive.queued, 1);
+
+    running.await.unwrap().unwrap();
+    lookup.await.unwrap().unwrap();
+    queued_bulk.await.unwrap().unwrap();
+
+    assert_eq!(
+        transport.endpoints(),
+        [
+            "auth",
+            "v2/reports/olap",
+            "employees/byCode/42",
+            "v2/reports/olap"
+        ]
+    );
+    let stats = client.queue_stats();
+    assert_eq!(stats.queued(), 0);
+    assert_eq!(stats.interactive.served, 1);
+    assert!(stats.interactive.max_wait > Duration::ZERO);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::fake::{FakeTransport, client, server_info};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{IikoError, InternalTransfersQuery, ServerVersion};
+
+#[tokio::test]
+async fn checks_server_version_before_gated_calls() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, &server_info("6.2.4021.0"))
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<employees/>");
+
+    let client = client(&transport);
+    let error = client
+        .documents()
+        .internal_transfers(InternalTransfersQuery::between("2024-01-01", "2024-01-31"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        IikoError::UnsupportedVersion { required, actual }
+            if required == ServerVersion::new(7, 9, 3) && actual == ServerVersion::new(6, 2, 4)
+    ));
+    assert_eq!(
+        transport.endpoints(),
+        ["http://iiko.test/resto/get_server_info.jsp"]
+    );
+
+    // revisionFrom появился в 6.4 и не передается, includeDeleted (5.0) передается
+    client.employees().list(Some(true), Some(42)).await.unwrap();
+    let request = transport.request(2);
+    assert_eq!(request.query_param("includeDeleted"), Some("true"));
+    assert_eq!(request.query_param("revisionFrom"), None);
+
+    let info = client.server_info().await.unwrap();
+    assert_eq!(info.raw_version, "6.2.4021.0");
+    assert_eq!(transport.endpoints().len(), 3);
+}
+
+#[tokio::test]
+async fn retries_server_info_after_transient_failure() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::SERVICE_UNAVAILABLE, "")
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<employees/>")
+        .respond(StatusCode::OK, &server_info("6.2.4021.0"))
+        .respond(StatusCode::OK, "<employees/>");
+
+    let client = client(&transport);
+    client.employees().list(None, Some(42)).await.unwrap();
+    client.employees().list(None, Some(42)).await.unwrap();
+
+    assert_eq!(
+        transport.endpoints()[3],
+        "http://iiko.test/resto/get_server_info.jsp"
+    );
+    // Версия неизвестна — параметр передается, после ответа 6.2 — нет
+    assert_eq!(transport.request(2).query_param("revisionFrom"), Some("42"));
+    assert_eq!(transport.request(4).query_param("revisionFrom"), None);
+}
+
+#[tokio::test]
+async fn remembers_missing_server_info() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::NOT_FOUND, "")
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<employees/>")
+        .respond(StatusCode::OK, "<employees/>");
+
+    let client = client(&transport);
+    client.employees().list(None, Some(42)).await.unwrap();
+    client.employees().list(None, Some(42)).await.unwrap();
+
+    assert_eq!(
+        transport.endpoints(),
+        [
+            "http://iiko.test/resto/get_server_info.jsp",
+            "auth",
+            "employees",
+            "employees"
+        ]
+    );
+}
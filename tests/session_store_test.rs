@@ -0,0 +1,39 @@
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{MemorySessionStore, SessionStore};
+
+#[tokio::test]
+async fn reuses_stored_session_key_until_rejected() {
+    let store = MemorySessionStore::new();
+    let scope = "admin@http://iiko.test/resto/api";
+    store.save(scope, "stored-key").await.unwrap();
+
+    let transport = FakeTransport::default();
+    transport.respond(StatusCode::OK, "first");
+    let first = client(&transport).with_session_store(store.clone());
+    assert_eq!(first.get("corporation/departments").await.unwrap(), "first");
+    assert_eq!(transport.endpoints(), ["corporation/departments"]);
+    assert_eq!(transport.request(0).query_param("key"), Some("stored-key"));
+
+    // Ключ истек на сервере: следующий процесс получает 401, авторизуется и сохраняет новый ключ
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::UNAUTHORIZED, "Token is expired or invalid")
+        .respond(StatusCode::OK, "session-2")
+        .respond(StatusCode::OK, "second");
+    let second = client(&transport).with_session_store(store.clone());
+    assert_eq!(
+        second.get("corporation/departments").await.unwrap(),
+        "second"
+    );
+    assert_eq!(
+        transport.endpoints(),
+        ["corporation/departments", "auth", "corporation/departments"]
+    );
+    assert_eq!(
+        store.load(scope).await.unwrap().as_deref(),
+        Some("session-2")
+    );
+}
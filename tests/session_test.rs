@@ -0,0 +1,49 @@
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::IikoError;
+use iiko_server_api_sdk::transport::StatusCode;
+
+#[tokio::test]
+async fn session_guard_logs_out_on_error_path() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::NOT_FOUND, "Unknown path")
+        .respond(StatusCode::OK, "");
+
+    let client = client(&transport);
+    let result: iiko_server_api_sdk::Result<String> = async {
+        let session = client.session().await?;
+        session.get("replication/serverType").await
+    }
+    .await;
+    assert!(matches!(result, Err(IikoError::NotFound(_))));
+
+    for _ in 0..10 {
+        if transport.endpoints().len() == 3 {
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+
+    assert_eq!(
+        transport.endpoints(),
+        ["auth", "replication/serverType", "logout"]
+    );
+    assert_eq!(transport.request(2).body_text(), Some("key=session-1"));
+}
+
+#[tokio::test]
+async fn released_session_guard_logs_out_once() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "");
+
+    let session = client(&transport).session().await.unwrap();
+    session.release().await.unwrap();
+    tokio::task::yield_now().await;
+
+    assert_eq!(transport.endpoints(), ["auth", "logout"]);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::fake::{FakeTransport, client, server_info};
+use futures_util::TryStreamExt;
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{OlapReportRequest, OlapReportType};
+
+#[tokio::test]
+async fn olap_stream_holds_queue_until_dropped() {
+    let transport = FakeTransport::default();
+    transport.respond(StatusCode::OK, "session-1").respond(
+        StatusCode::OK,
+        r#"{"data": [{"DishSumInt": 1}, {"DishSumInt": 2}], "summary": []}"#,
+    );
+
+    let client = client(&transport);
+    let request = OlapReportRequest {
+        report_type: OlapReportType::Sales,
+        build_summary: Some(false),
+        group_by_row_fields: vec![],
+        group_by_col_fields: None,
+        aggregate_fields: vec!["DishSumInt".to_string()],
+        filters: None,
+    };
+    let mut rows = client.reports().stream_olap_report(request).await.unwrap();
+    assert!(client.queue_stats().busy);
+
+    let first = rows.try_next().await.unwrap().unwrap();
+    assert_eq!(first["DishSumInt"].as_integer(), Some(1));
+    drop(rows);
+
+    assert!(!client.queue_stats().busy);
+}
+
+#[tokio::test]
+async fn writes_decoded_image_to_async_writer() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, &server_info("7.9.3007.0"))
+        .respond(StatusCode::OK, "session-1")
+        .respond(
+            StatusCode::OK,
+            r#"{"id": "9f6b0c4e-8f3e-4a53-9e4e-1a2b3c4d5e6f", "data": "iVBORw0KGgo\/AA=="}"#,
+        );
+
+    let client = client(&transport);
+    let mut image = Vec::new();
+    let written = client
+        .images()
+        .load_into(uuid::Uuid::nil(), &mut image)
+        .await
+        .unwrap();
+
+    assert_eq!(written, 10);
+    assert_eq!(image[..4], [0x89, b'P', b'N', b'G']);
+    assert_eq!(
+        transport.request(2).query_param("imageId"),
+        Some("00000000-0000-0000-0000-000000000000")
+    );
+}
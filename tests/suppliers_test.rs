@@ -53,8 +53,8 @@ async fn test_get_supplier_pricelist() {
         .iter()
         .filter(|supplier| !supplier.code.trim().is_empty())
     {
-        if seen_codes.insert(supplier.code.clone()) {
-            candidate_codes.push(supplier.code.clone());
+        if seen_codes.insert(supplier.code.to_string()) {
+            candidate_codes.push(supplier.code.to_string());
         }
     }
 
@@ -68,7 +68,7 @@ async fn test_get_supplier_pricelist() {
     for code in candidate_codes {
         match client
             .suppliers()
-            .get_pricelist(&code, requested_date.as_deref())
+            .get_pricelist(&code, requested_date.as_deref().map(Into::into))
             .await
         {
             Ok(items) => {
@@ -0,0 +1,105 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use common::fake::{BASE_URL, FakeTransport};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::xml::response::Employee;
+use iiko_server_api_sdk::{IikoClient, IikoConfig, WriteMode};
+use std::sync::{Arc, Mutex};
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Вывод `tracing_subscriber::fmt` в памяти теста
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Capture {
+    fn subscribe(&self, level: LevelFilter) -> DefaultGuard {
+        let writer = self.clone();
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish()
+            .set_default()
+    }
+
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn traces_requests_without_secrets() {
+    let capture = Capture::default();
+    let _subscriber = capture.subscribe(LevelFilter::TRACE);
+
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-secret")
+        .respond(StatusCode::OK, "<serverType>CHAIN</serverType>");
+
+    let config = IikoConfig::new(BASE_URL, "admin", "password-hash");
+    IikoClient::with_transport(config, transport.clone())
+        .replication()
+        .get_server_type()
+        .await
+        .unwrap();
+
+    let output = capture.output();
+    assert!(output.contains("iiko.replication.get_server_type"));
+    assert!(output.contains("endpoint=\"replication/serverType\""));
+    assert!(output.contains("<serverType>CHAIN</serverType>"));
+    assert!(!output.contains("session-secret"));
+    assert!(!output.contains("password-hash"));
+}
+
+async fn dry_run_employee_update(level: LevelFilter) -> String {
+    let capture = Capture::default();
+    let _subscriber = capture.subscribe(level);
+
+    let config = IikoConfig::new(BASE_URL, "admin", "hash").with_write_mode(WriteMode::DryRun);
+    let employee = Employee {
+        name: "Повар".to_string(),
+        password: Some("cook-password".to_string()),
+        ..Employee::default()
+    };
+    let _ = IikoClient::with_transport(config, FakeTransport::default())
+        .employees()
+        .update_partial(uuid::Uuid::nil(), &employee)
+        .await;
+
+    capture.output()
+}
+
+#[tokio::test]
+async fn dry_run_logs_only_method_and_endpoint_at_info() {
+    let output = dry_run_employee_update(LevelFilter::INFO).await;
+
+    assert!(output.contains("iiko dry run: request not sent"));
+    assert!(output.contains("endpoint=employees/byId/"));
+    assert!(!output.contains("<name>Повар</name>"));
+    assert!(!output.contains("cook-password"));
+}
+
+#[tokio::test]
+async fn dry_run_traces_body_without_password() {
+    let output = dry_run_employee_update(LevelFilter::TRACE).await;
+
+    assert!(output.contains("<name>Повар</name>"));
+    assert!(output.contains("<password>[REDACTED]</password>"));
+    assert!(!output.contains("cook-password"));
+}
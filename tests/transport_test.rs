@@ -0,0 +1,135 @@
+mod common;
+
+use common::fake::{BASE_URL, FakeTransport, client};
+use iiko_server_api_sdk::transport::{Method, StatusCode};
+use iiko_server_api_sdk::{IikoClient, IikoConfig, IikoError};
+use std::time::Duration;
+
+#[tokio::test]
+async fn sends_session_key_and_parses_canned_response() {
+    let transport = FakeTransport::default();
+    transport.respond(StatusCode::OK, "session-1").respond(
+        StatusCode::OK,
+        r#"[{"id":"d3b07384-d9a0-4c9b-8d5f-0c2f5e6a7b8c","name":"Основная"}]"#,
+    );
+
+    let categories = client(&transport)
+        .products()
+        .list_categories(Some(false), None, None)
+        .await
+        .expect("canned categories should parse");
+
+    assert_eq!(categories.len(), 1);
+    assert_eq!(
+        transport.endpoints(),
+        ["auth", "v2/entities/products/category/list"]
+    );
+
+    let auth = transport.request(0);
+    assert_eq!(auth.method, Method::POST);
+    assert_eq!(auth.body_text(), Some("login=admin&pass=hash"));
+
+    let list = transport.request(1);
+    assert_eq!(list.query_param("key"), Some("session-1"));
+    assert_eq!(list.query_param("includeDeleted"), Some("false"));
+}
+
+#[tokio::test]
+async fn reauthenticates_once_when_session_expires() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "stale")
+        .respond(StatusCode::UNAUTHORIZED, "Token is expired or invalid")
+        .respond(StatusCode::OK, "fresh")
+        .respond(StatusCode::OK, "<ok/>");
+
+    let body = client(&transport)
+        .get("replication/serverType")
+        .await
+        .expect("request should be replayed with a new key");
+
+    assert_eq!(body, "<ok/>");
+    assert_eq!(
+        transport.endpoints(),
+        [
+            "auth",
+            "replication/serverType",
+            "auth",
+            "replication/serverType"
+        ]
+    );
+    assert_eq!(transport.request(3).query_param("key"), Some("fresh"));
+}
+
+#[tokio::test]
+async fn auto_reauth_can_be_disabled() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "stale")
+        .respond(StatusCode::UNAUTHORIZED, "Token is expired or invalid");
+
+    let config = IikoConfig::new(BASE_URL, "admin", "hash").with_auto_reauth(false);
+    let result = IikoClient::with_transport(config, transport.clone())
+        .get("replication/serverType")
+        .await;
+
+    assert!(matches!(result, Err(IikoError::SessionExpired(_))));
+    assert_eq!(transport.endpoints().len(), 2);
+}
+
+#[tokio::test]
+async fn retries_reads_on_service_unavailable() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::SERVICE_UNAVAILABLE, "replication restart")
+        .respond(StatusCode::OK, "<ok/>");
+
+    let body = client(&transport)
+        .get("replication/statuses")
+        .await
+        .expect("read should be retried");
+
+    assert_eq!(body, "<ok/>");
+    assert_eq!(transport.endpoints().len(), 3);
+}
+
+#[tokio::test]
+async fn does_not_retry_writes_by_default() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::SERVICE_UNAVAILABLE, "replication restart");
+
+    let result = client(&transport)
+        .post_xml("documents/import/incomingInvoice", "<document/>")
+        .await;
+
+    assert!(matches!(result, Err(IikoError::ServiceUnavailable(_))));
+    assert_eq!(transport.endpoints().len(), 2);
+    assert_eq!(
+        transport.request(1).header("content-type"),
+        Some("application/xml")
+    );
+}
+
+#[tokio::test]
+async fn passes_per_call_and_auth_timeouts_to_transport() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<ok/>")
+        .respond(StatusCode::OK, "<ok/>");
+
+    let client = client(&transport);
+    client
+        .with_request_timeout(Duration::from_secs(600))
+        .get("v2/reports/olap/columns")
+        .await
+        .unwrap();
+    client.get("replication/serverType").await.unwrap();
+
+    assert_eq!(transport.request(0).timeout, Some(Duration::from_secs(30)));
+    assert_eq!(transport.request(1).timeout, Some(Duration::from_secs(600)));
+    assert_eq!(transport.request(2).timeout, None);
+}
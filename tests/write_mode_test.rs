@@ -0,0 +1,93 @@
+mod common;
+
+use common::fake::{BASE_URL, FakeTransport};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{IikoClient, IikoConfig, IikoError, WriteMode};
+
+#[tokio::test]
+async fn read_only_and_dry_run_modes_do_not_send_writes() {
+    let transport = FakeTransport::default();
+    transport.respond(StatusCode::OK, "session-1").respond(
+        StatusCode::OK,
+        r#"[{"id":"d3b07384-d9a0-4c9b-8d5f-0c2f5e6a7b8c","name":"Основная"}]"#,
+    );
+    let config = IikoConfig::new(BASE_URL, "admin", "hash")
+        .with_version_check(false)
+        .with_write_mode(WriteMode::ReadOnly);
+    let read_only = IikoClient::with_transport(config, transport.clone());
+    let employee = uuid::Uuid::new_v4();
+
+    read_only
+        .products()
+        .list_categories(None, None, None)
+        .await
+        .unwrap();
+    let error = read_only.employees().delete(employee).await.unwrap_err();
+    assert!(matches!(
+        &error,
+        IikoError::ReadOnly { method, endpoint }
+            if method == "DELETE" && *endpoint == format!("employees/byId/{}", employee)
+    ));
+    assert_eq!(
+        transport.endpoints(),
+        ["auth", "v2/entities/products/category/list"]
+    );
+
+    // Без подготовленных ответов: любой отправленный запрос завершился бы ошибкой
+    let transport = FakeTransport::default();
+    let config = IikoConfig::new(BASE_URL, "admin", "hash")
+        .with_version_check(false)
+        .with_write_mode(WriteMode::DryRun);
+    let dry_run = IikoClient::with_transport(config, transport.clone());
+
+    let invoice = quick_xml::de::from_str(
+        "<document><documentNumber>ПН-42</documentNumber><supplier>a0e1b7e2-5f8a-4f3e-9d1c-2b3a4c5d6e7f</supplier></document>",
+    )
+    .unwrap();
+    let result = dry_run
+        .documents()
+        .import_incoming_invoice(invoice)
+        .await
+        .unwrap();
+    assert!(result.valid);
+    assert_eq!(result.document_number.as_deref(), Some("ПН-42"));
+
+    let scale = uuid::Uuid::new_v4();
+    let deleted = dry_run.product_scales().delete(vec![scale]).await.unwrap();
+    assert_eq!(deleted.id, scale);
+    let deleted = dry_run
+        .products()
+        .delete(vec![employee.into()])
+        .await
+        .unwrap();
+    assert_eq!(deleted.result, "SUCCESS");
+    assert!(deleted.response.is_none());
+    let category = dry_run
+        .products()
+        .save_category("Десерты".to_string())
+        .await
+        .unwrap();
+    assert_eq!(category.response.id, uuid::Uuid::nil());
+    assert_eq!(category.response.name.as_deref(), Some("Десерты"));
+    assert_eq!(dry_run.employees().delete(employee).await.unwrap(), "");
+
+    assert!(transport.endpoints().is_empty());
+    let requests = dry_run.dry_run_requests();
+    let endpoints: Vec<_> = requests.iter().map(|r| r.endpoint.as_str()).collect();
+    assert_eq!(
+        endpoints,
+        [
+            "documents/import/incomingInvoice",
+            "v2/entities/productScales/delete",
+            "v2/entities/products/delete",
+            "v2/entities/products/category/save",
+            &format!("employees/byId/{}", employee),
+        ]
+    );
+    let body = requests[0].body.text().unwrap().unwrap();
+    assert!(body.contains("<documentNumber>ПН-42</documentNumber>"));
+    assert_eq!(
+        requests[3].body.text().unwrap().as_deref(),
+        Some(r#"{"name":"Десерты"}"#)
+    );
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::fake::{FakeTransport, client};
+use iiko_server_api_sdk::transport::StatusCode;
+use iiko_server_api_sdk::{DepartmentId, IikoError, SalesQuery};
+
+#[tokio::test]
+async fn report_lists_accept_any_shape_and_name_failed_item() {
+    let item = |value: &str| {
+        format!(
+            "<dayDishValue><date>01.03.2024</date><value>{}</value></dayDishValue>",
+            value
+        )
+    };
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(
+            StatusCode::OK,
+            &format!(
+                "<dayDishValues>{}{}</dayDishValues>",
+                item("1.5"),
+                item("2")
+            ),
+        )
+        .respond(StatusCode::OK, &item("3"))
+        .respond(StatusCode::OK, "")
+        .respond(StatusCode::OK, &format!("{}{}", item("1"), item("много")));
+    let client = client(&transport);
+    let department = DepartmentId::from(uuid::Uuid::nil());
+    let sales = || SalesQuery::new(department, "01.03.2024", "31.03.2024");
+
+    let wrapped = client.reports().sales(sales()).await.unwrap();
+    assert_eq!(
+        wrapped.iter().map(|day| day.value).collect::<Vec<_>>(),
+        [Some(1.5), Some(2.0)]
+    );
+    assert_eq!(client.reports().sales(sales()).await.unwrap().len(), 1);
+    assert!(client.reports().sales(sales()).await.unwrap().is_empty());
+
+    let error = client.reports().sales(sales()).await.unwrap_err();
+    assert!(matches!(
+        &error,
+        IikoError::XmlListItem { element, index: 1, field, .. }
+            if element == "dayDishValue" && field == "value"
+    ));
+}
+
+#[tokio::test]
+async fn report_list_rejects_unexpected_root() {
+    let transport = FakeTransport::default();
+    transport
+        .respond(StatusCode::OK, "session-1")
+        .respond(StatusCode::OK, "<html><body>Bad gateway</body></html>");
+    let client = client(&transport);
+    let department = DepartmentId::from(uuid::Uuid::nil());
+
+    let error = client
+        .reports()
+        .sales(SalesQuery::new(department, "01.03.2024", "31.03.2024"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        &error,
+        IikoError::XmlListRoot { wrapper, root, .. } if wrapper == "dayDishValues" && root == "html"
+    ));
+}